@@ -0,0 +1,54 @@
+//! Compares `cosine_similarity` with `EmbeddingRecord::cosine_to`.
+//!
+//! Both score one query against every stored embedding, as a full scan in
+//! `search_filtered` does; `cosine_to` reuses the norms stored in each record
+//! instead of recomputing them per pair.
+//!
+//! ```text
+//! cargo bench --bench cosine
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use temporal_ai::similarity::cosine_similarity;
+use temporal_ai::{EmbeddingRecord, EMBEDDING_DIM};
+
+const RECORD_COUNT: usize = 2_000;
+
+fn cosine_benchmark(c: &mut Criterion) {
+    let records: Vec<EmbeddingRecord> = (0..RECORD_COUNT)
+        .map(|i| {
+            EmbeddingRecord::new(
+                (0..EMBEDDING_DIM)
+                    .map(|d| ((i * 31 + d * 17) % 97) as f32 / 97.0)
+                    .collect(),
+            )
+        })
+        .collect();
+    let query =
+        EmbeddingRecord::new((0..EMBEDDING_DIM).map(|d| (d % 13) as f32 / 13.0).collect());
+
+    let mut group = c.benchmark_group("cosine_full_scan");
+
+    group.bench_function("cosine_similarity", |b| {
+        b.iter(|| {
+            records
+                .iter()
+                .map(|record| cosine_similarity(black_box(&query.vector), &record.vector))
+                .sum::<f32>()
+        })
+    });
+
+    group.bench_function("cosine_to", |b| {
+        b.iter(|| {
+            records
+                .iter()
+                .map(|record| black_box(&query).cosine_to(record))
+                .sum::<f32>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, cosine_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+//! Compares the sequential and rayon-parallel `search_filtered` scan over a
+//! 10k-pattern store.
+//!
+//! The sequential baseline runs the same code on a one-thread rayon pool:
+//!
+//! ```text
+//! cargo bench --bench parallel_scan --features rayon
+//! ```
+
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, Criterion};
+use temporal_ai::{Pattern, SearchFilters, SimilaritySearch, VectorStore};
+
+const PATTERN_COUNT: usize = 10_000;
+const DIMENSION: usize = 768;
+
+fn populate(store: &VectorStore) {
+    for i in 0..PATTERN_COUNT {
+        let pattern = Pattern {
+            id: format!("pattern-{i}"),
+            description: format!("Benchmark pattern {i}"),
+            file_paths: vec![format!("src/module_{}.rs", i % 50)],
+            commit_sha: format!("{i:040x}"),
+            timestamp: Utc::now().timestamp(),
+            tags: vec!["bench".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        };
+        let embedding: Vec<f32> = (0..DIMENSION)
+            .map(|d| ((i * 31 + d * 17) % 97) as f32 / 97.0)
+            .collect();
+        store.insert(&pattern, embedding).expect("insert failed");
+    }
+}
+
+fn scan_benchmark(c: &mut Criterion) {
+    let dir = tempfile::tempdir().expect("failed to create bench dir");
+    let store = VectorStore::open(dir.path().join("bench.redb")).expect("open failed");
+    populate(&store);
+
+    let search = SimilaritySearch::new(&store);
+    let filters = SearchFilters::default();
+    let query: Vec<f32> = (0..DIMENSION).map(|d| (d % 13) as f32 / 13.0).collect();
+    let sequential = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .expect("failed to build thread pool");
+
+    let mut group = c.benchmark_group("search_filtered_scan");
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| sequential.install(|| search.search_filtered(&query, 10, &filters)))
+    });
+
+    group.bench_function("parallel", |b| {
+        b.iter(|| search.search_filtered(&query, 10, &filters))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, scan_benchmark);
+criterion_main!(benches);
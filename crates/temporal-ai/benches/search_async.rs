@@ -0,0 +1,121 @@
+//! Compares `search_filtered` against `search_filtered_async`, and repeated
+//! `search_filtered` queries against `search_from_cache` over a warmed cache.
+//!
+//! The async path only pays off when reads are latency-bound, so point
+//! `TEMPORAL_AI_BENCH_DIR` at a networked or tmpfs mount to reproduce the
+//! deployment setup, e.g.:
+//!
+//! ```text
+//! TEMPORAL_AI_BENCH_DIR=/mnt/nfs/bench cargo bench --bench search_async
+//! ```
+//!
+//! Without it, the database is created in the system temp directory.
+
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use temporal_ai::{Pattern, SearchFilters, SimilaritySearch, VectorStore};
+
+const PATTERN_COUNT: usize = 2_000;
+const DIMENSION: usize = 768;
+
+fn bench_dir() -> tempfile::TempDir {
+    match std::env::var_os("TEMPORAL_AI_BENCH_DIR") {
+        Some(dir) => tempfile::tempdir_in(dir).expect("failed to create bench dir"),
+        None => tempfile::tempdir().expect("failed to create bench dir"),
+    }
+}
+
+fn populate(store: &VectorStore) {
+    for i in 0..PATTERN_COUNT {
+        let pattern = Pattern {
+            id: format!("pattern-{i}"),
+            description: format!("Benchmark pattern {i}"),
+            file_paths: vec![format!("src/module_{}.rs", i % 50)],
+            commit_sha: format!("{i:040x}"),
+            timestamp: Utc::now().timestamp(),
+            tags: vec!["bench".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        };
+        let embedding: Vec<f32> = (0..DIMENSION)
+            .map(|d| ((i * 31 + d * 17) % 97) as f32 / 97.0)
+            .collect();
+        store.insert(&pattern, embedding).expect("insert failed");
+    }
+}
+
+fn search_benchmark(c: &mut Criterion) {
+    let dir = bench_dir();
+    let store = VectorStore::open(dir.path().join("bench.redb")).expect("open failed");
+    populate(&store);
+
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start runtime");
+    let search = SimilaritySearch::new(&store);
+    let query: Vec<f32> = (0..DIMENSION).map(|d| (d % 13) as f32 / 13.0).collect();
+
+    let mut group = c.benchmark_group("search_filtered");
+    group.sample_size(20);
+
+    group.bench_function("sync", |b| {
+        b.iter(|| search.search_filtered(&query, 10, &SearchFilters::default()))
+    });
+
+    for concurrency in [1, 8, 32] {
+        let filters = SearchFilters {
+            max_concurrent_reads: concurrency,
+            ..Default::default()
+        };
+        group.bench_with_input(
+            BenchmarkId::new("async", concurrency),
+            &filters,
+            |b, filters| {
+                b.iter(|| runtime.block_on(search.search_filtered_async(&query, 10, filters)))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn cache_benchmark(c: &mut Criterion) {
+    let dir = bench_dir();
+    let store = VectorStore::open(dir.path().join("bench.redb")).expect("open failed");
+    populate(&store);
+
+    let search = SimilaritySearch::new(&store);
+    let cache = search.warm_cache().expect("warm_cache failed");
+    let filters = SearchFilters::default();
+    let queries: Vec<Vec<f32>> = (0..10)
+        .map(|q| (0..DIMENSION).map(|d| ((d + q) % 13) as f32 / 13.0).collect())
+        .collect();
+
+    let mut group = c.benchmark_group("repeated_queries");
+    group.sample_size(20);
+
+    group.bench_function("store", |b| {
+        b.iter(|| {
+            for query in &queries {
+                search.search_filtered(query, 10, &filters).expect("search failed");
+            }
+        })
+    });
+
+    group.bench_function("cache", |b| {
+        b.iter(|| {
+            for query in &queries {
+                search
+                    .search_from_cache(&cache, query, 10, &filters)
+                    .expect("search failed");
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, search_benchmark, cache_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+//! Stream commit messages from a file into the vector store
+//!
+//! Each non-empty line of the input is embedded with `Embedder::embed_stream`
+//! and stored as a pattern:
+//!
+//! ```text
+//! git log --format=%s > commits.txt
+//! cargo run --example embed_stream --features async -- commits.txt
+//! ```
+
+use anyhow::{Context, Result};
+use futures::{stream, StreamExt};
+use std::path::PathBuf;
+use std::sync::Arc;
+use temporal_ai::{Embedder, Pattern, VectorStore};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Texts embedded ahead of the insert loop.
+const BUFFER_SIZE: usize = 8;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let input = std::env::args()
+        .nth(1)
+        .context("Usage: embed_stream <commit-messages-file>")?;
+
+    let model_path = PathBuf::from("models/embeddinggemma-300M-Q8_0.gguf");
+    let embedder = Arc::new(Embedder::from_gguf(&model_path)?);
+    let store = VectorStore::open("data/temporal-ai.redb")?;
+
+    let file = tokio::fs::File::open(&input)
+        .await
+        .with_context(|| format!("Failed to open {}", input))?;
+    let lines = stream::unfold(BufReader::new(file).lines(), |mut lines| async move {
+        match lines.next_line().await {
+            Ok(Some(line)) => Some((line, lines)),
+            Ok(None) | Err(_) => None,
+        }
+    })
+    .filter(|line| std::future::ready(!line.trim().is_empty()))
+    .boxed();
+
+    // `embed_stream` consumes the texts but yields in input order, so a copy of
+    // each message is sent through a channel and zipped back onto its embedding.
+    let (tx, messages) = futures::channel::mpsc::unbounded();
+    let texts = lines.inspect(move |line| {
+        let _ = tx.unbounded_send(line.clone());
+    });
+    let mut embedded = embedder
+        .embed_stream(texts, BUFFER_SIZE)
+        .zip(messages)
+        .boxed();
+
+    let mut inserted = 0;
+    while let Some((embedding, message)) = embedded.next().await {
+        let pattern = Pattern {
+            id: Pattern::generate_ulid_id(),
+            description: message,
+            file_paths: Vec::new(),
+            commit_sha: String::new(),
+            timestamp: chrono::Utc::now().timestamp(),
+            tags: Vec::new(),
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        };
+        store.insert(&pattern, embedding?)?;
+        inserted += 1;
+    }
+
+    println!("✓ Inserted {} patterns from {}", inserted, input);
+    Ok(())
+}
@@ -1,21 +1,66 @@
 //! Temporal AI CLI - Pattern recommendation engine
 
 use anyhow::{Context, Result};
+use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
+use std::sync::Arc;
+use temporal_ai::centroid::compute_tag_centroid;
+use temporal_ai::model_download::{download_model, ModelVariant};
 use temporal_ai::observability_aggregator::ObservabilityClient;
+use temporal_ai::similarity::{export_similarity_matrix, MAX_MATRIX_PATTERNS};
+use temporal_ai::snapshot::{RecommendationSnapshot, SNAPSHOT_SIZE};
 use temporal_ai::{
-    Embedder, PatternExtractor, RecommendationRanker, SimilaritySearch, VectorStore,
+    import_jsonl_with_embeddings, Embedder, PatternExtractor, PerformanceMetrics, Recommendation,
+    RecommendationRanker, RefreshState, SearchFilters, SimilaritySearch, SortOrder, TagSynonyms,
+    VectorStore,
 };
 
 #[derive(Debug)]
 enum Command {
-    Refresh { commits: usize },
+    Refresh {
+        commits: usize,
+        full: bool,
+        authors: Vec<String>,
+        branch: Option<String>,
+    },
     RefreshMetrics { days: u32 },
-    Query { text: String, top: usize },
+    Query {
+        text: Option<String>,
+        centroid_tags: Vec<String>,
+        top: usize,
+        tags: Vec<String>,
+        synonyms: Option<PathBuf>,
+    },
     Init,
     Stats,
+    Status,
+    List { order: SortOrder, limit: Option<usize> },
+    Serve { http: bool, addr: String, warm_up: bool },
+    Import { path: PathBuf, overwrite: bool },
+    Compact { in_place: bool },
+    Backup { path: PathBuf },
+    Restore { path: PathBuf, overwrite: bool },
+    Migrate { to: u8 },
+    DedupByContent,
+    BuildIndex { m: usize, ef_construction: usize },
+    DownloadModel { model: ModelVariant, dest: PathBuf, sha256: Option<String> },
+    ExportMatrix { format: MatrixFormat, max: usize },
+    Export { format: ExportFormat, path: PathBuf },
+    SnapshotCreate { query: String, output: PathBuf },
+    SnapshotTest { query: String, input: PathBuf, tolerance: f32 },
 }
 
+#[derive(Debug, Clone, Copy)]
+enum MatrixFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ExportFormat {
+    Parquet,
+    Jsonl,
+}
 
 fn parse_args() -> Result<Command> {
     let args: Vec<String> = std::env::args().collect();
@@ -28,13 +73,42 @@ fn parse_args() -> Result<Command> {
     match args[1].as_str() {
         "init" => Ok(Command::Init),
         "stats" => Ok(Command::Stats),
+        "status" => Ok(Command::Status),
         "refresh" => {
-            let commits = if args.len() > 2 && args[2] == "--commits" && args.len() > 3 {
-                args[3].parse().context("Invalid commit count")?
-            } else {
-                1000
-            };
-            Ok(Command::Refresh { commits })
+            let mut commits = 1000;
+            let mut full = false;
+            let mut authors = Vec::new();
+            let mut branch = None;
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--commits" => {
+                        commits = rest
+                            .next()
+                            .context("--commits requires a value")?
+                            .parse()
+                            .context("Invalid commit count")?;
+                    }
+                    "--full" => full = true,
+                    "--branch" => {
+                        branch = Some(rest.next().context("--branch requires a value")?.clone())
+                    }
+                    "--author" => {
+                        authors.push(rest.next().context("--author requires a value")?.clone())
+                    }
+                    "--exclude-author" => {
+                        let glob = rest.next().context("--exclude-author requires a value")?;
+                        authors.push(format!("!{}", glob));
+                    }
+                    other => anyhow::bail!("Unknown refresh option: {}", other),
+                }
+            }
+            Ok(Command::Refresh {
+                commits,
+                full,
+                authors,
+                branch,
+            })
         }
         "refresh-metrics" => {
             let days = if args.len() > 2 && args[2] == "--days" && args.len() > 3 {
@@ -45,16 +119,318 @@ fn parse_args() -> Result<Command> {
             Ok(Command::RefreshMetrics { days })
         }
         "query" => {
-            if args.len() < 3 {
-                anyhow::bail!("Query text required");
+            let mut text = None;
+            let mut centroid_tags = Vec::new();
+            let mut top = 5;
+            let mut tags = Vec::new();
+            let mut synonyms = None;
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--from-tag-centroid" => {
+                        let list = rest.next().context("--from-tag-centroid requires a value")?;
+                        centroid_tags = list
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|tag| !tag.is_empty())
+                            .map(String::from)
+                            .collect();
+                    }
+                    "--top" => {
+                        top = rest
+                            .next()
+                            .context("--top requires a value")?
+                            .parse()
+                            .context("Invalid top count")?;
+                    }
+                    "--tag" => tags.push(rest.next().context("--tag requires a value")?.clone()),
+                    "--synonyms" => {
+                        let path = rest.next().context("--synonyms requires a value")?;
+                        synonyms = Some(PathBuf::from(path));
+                    }
+                    other if other.starts_with("--") => {
+                        anyhow::bail!("Unknown query option: {}", other)
+                    }
+                    other => text = Some(other.to_string()),
+                }
+            }
+            if text.is_none() && centroid_tags.is_empty() {
+                anyhow::bail!("Query text or --from-tag-centroid required");
+            }
+            Ok(Command::Query {
+                text,
+                centroid_tags,
+                top,
+                tags,
+                synonyms,
+            })
+        }
+        "list" => {
+            let mut order = SortOrder::Descending;
+            let mut limit = None;
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--sort-by" => {
+                        let key = rest.next().context("--sort-by requires a value")?;
+                        if key != "timestamp" {
+                            anyhow::bail!("Unknown sort key: {}", key);
+                        }
+                    }
+                    "--order" => {
+                        order = match rest.next().context("--order requires a value")?.as_str() {
+                            "asc" => SortOrder::Ascending,
+                            "desc" => SortOrder::Descending,
+                            other => anyhow::bail!("Unknown sort order: {}", other),
+                        };
+                    }
+                    "--limit" => {
+                        limit = Some(
+                            rest.next()
+                                .context("--limit requires a value")?
+                                .parse()
+                                .context("Invalid --limit")?,
+                        );
+                    }
+                    other => anyhow::bail!("Unknown list option: {}", other),
+                }
+            }
+            Ok(Command::List { order, limit })
+        }
+        "serve" => {
+            let mut http = false;
+            let mut warm_up = false;
+            let mut addr = "127.0.0.1:8080".to_string();
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--http" => http = true,
+                    "--warm-up" => warm_up = true,
+                    "--addr" => {
+                        addr = rest.next().context("--addr requires a value")?.clone();
+                    }
+                    other => anyhow::bail!("Unknown serve option: {}", other),
+                }
+            }
+            Ok(Command::Serve {
+                http,
+                addr,
+                warm_up,
+            })
+        }
+        "import" => {
+            let mut path = None;
+            let mut overwrite = false;
+            for arg in &args[2..] {
+                match arg.as_str() {
+                    "--overwrite" => overwrite = true,
+                    other if other.starts_with("--") => {
+                        anyhow::bail!("Unknown import option: {}", other)
+                    }
+                    other => path = Some(PathBuf::from(other)),
+                }
+            }
+            let path = path.context("Import path required")?;
+            Ok(Command::Import { path, overwrite })
+        }
+        "compact" => {
+            let mut in_place = false;
+            for arg in &args[2..] {
+                match arg.as_str() {
+                    "--in-place" => in_place = true,
+                    other => anyhow::bail!("Unknown compact option: {}", other),
+                }
+            }
+            Ok(Command::Compact { in_place })
+        }
+        "backup" => {
+            let path = args.get(2).context("Backup path required")?;
+            Ok(Command::Backup {
+                path: PathBuf::from(path),
+            })
+        }
+        "migrate" => {
+            let mut to = temporal_ai::SCHEMA_VERSION;
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--to" => {
+                        to = rest
+                            .next()
+                            .context("--to requires a value")?
+                            .parse()
+                            .context("Invalid --to")?;
+                    }
+                    other => anyhow::bail!("Unknown migrate option: {}", other),
+                }
+            }
+            Ok(Command::Migrate { to })
+        }
+        "restore" => {
+            let mut path = None;
+            let mut overwrite = false;
+            for arg in &args[2..] {
+                match arg.as_str() {
+                    "--overwrite" => overwrite = true,
+                    other if other.starts_with("--") => {
+                        anyhow::bail!("Unknown restore option: {}", other)
+                    }
+                    other => path = Some(PathBuf::from(other)),
+                }
+            }
+            let path = path.context("Backup path required")?;
+            Ok(Command::Restore { path, overwrite })
+        }
+        "dedup" => {
+            let mut by_content = false;
+            for arg in &args[2..] {
+                match arg.as_str() {
+                    "--by-content" => by_content = true,
+                    other => anyhow::bail!("Unknown dedup option: {}", other),
+                }
+            }
+            if !by_content {
+                anyhow::bail!("No dedup mode selected; pass --by-content");
+            }
+            Ok(Command::DedupByContent)
+        }
+        "build-index" => {
+            let mut m = 16;
+            let mut ef_construction = 100;
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--m" => {
+                        m = rest
+                            .next()
+                            .context("--m requires a value")?
+                            .parse()
+                            .context("Invalid --m")?;
+                    }
+                    "--ef-construction" => {
+                        ef_construction = rest
+                            .next()
+                            .context("--ef-construction requires a value")?
+                            .parse()
+                            .context("Invalid --ef-construction")?;
+                    }
+                    other => anyhow::bail!("Unknown build-index option: {}", other),
+                }
+            }
+            Ok(Command::BuildIndex { m, ef_construction })
+        }
+        "download-model" => {
+            let mut model = ModelVariant::Gemma300mQ8;
+            let mut dest = PathBuf::from("models");
+            let mut sha256 = None;
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--model" => {
+                        let name = rest.next().context("--model requires a value")?;
+                        model = ModelVariant::from_name(name)
+                            .with_context(|| format!("Unknown model: {}", name))?;
+                    }
+                    "--dest" => {
+                        dest = PathBuf::from(rest.next().context("--dest requires a value")?);
+                    }
+                    "--sha256" => {
+                        sha256 = Some(rest.next().context("--sha256 requires a value")?.clone());
+                    }
+                    other => anyhow::bail!("Unknown download-model option: {}", other),
+                }
+            }
+            Ok(Command::DownloadModel {
+                model,
+                dest,
+                sha256,
+            })
+        }
+        "export-matrix" => {
+            let mut format = MatrixFormat::Json;
+            let mut max = MAX_MATRIX_PATTERNS;
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--format" => {
+                        format = match rest.next().context("--format requires a value")?.as_str() {
+                            "csv" => MatrixFormat::Csv,
+                            "json" => MatrixFormat::Json,
+                            other => anyhow::bail!("Unknown matrix format: {}", other),
+                        };
+                    }
+                    "--max" => {
+                        max = rest
+                            .next()
+                            .context("--max requires a value")?
+                            .parse()
+                            .context("Invalid --max")?;
+                    }
+                    other => anyhow::bail!("Unknown export-matrix option: {}", other),
+                }
+            }
+            Ok(Command::ExportMatrix { format, max })
+        }
+        "export" => {
+            let mut format = None;
+            let mut path = None;
+            let mut rest = args[2..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--format" => {
+                        format = match rest.next().context("--format requires a value")?.as_str() {
+                            "parquet" => Some(ExportFormat::Parquet),
+                            "jsonl" => Some(ExportFormat::Jsonl),
+                            other => anyhow::bail!("Unknown export format: {}", other),
+                        };
+                    }
+                    other if other.starts_with("--") => {
+                        anyhow::bail!("Unknown export option: {}", other)
+                    }
+                    other => path = Some(PathBuf::from(other)),
+                }
+            }
+            Ok(Command::Export {
+                format: format.context("--format is required")?,
+                path: path.context("Export path required")?,
+            })
+        }
+        "snapshot" => {
+            let action = args.get(2).context("Snapshot action required (create or test)")?;
+            let query = args.get(3).context("Snapshot query required")?.clone();
+            let mut file = None;
+            let mut tolerance = 0.05;
+            let mut rest = args[4..].iter();
+            while let Some(arg) = rest.next() {
+                match (action.as_str(), arg.as_str()) {
+                    ("create", "--output") | ("test", "--input") => {
+                        let path = rest
+                            .next()
+                            .with_context(|| format!("{} requires a value", arg))?;
+                        file = Some(PathBuf::from(path));
+                    }
+                    ("test", "--tolerance") => {
+                        tolerance = rest
+                            .next()
+                            .context("--tolerance requires a value")?
+                            .parse()
+                            .context("Invalid --tolerance")?;
+                    }
+                    (_, other) => anyhow::bail!("Unknown snapshot option: {}", other),
+                }
+            }
+            match action.as_str() {
+                "create" => Ok(Command::SnapshotCreate {
+                    query,
+                    output: file.context("--output is required")?,
+                }),
+                "test" => Ok(Command::SnapshotTest {
+                    query,
+                    input: file.context("--input is required")?,
+                    tolerance,
+                }),
+                other => anyhow::bail!("Unknown snapshot action: {}", other),
             }
-            let text = args[2].clone();
-            let top = if args.len() > 3 && args[3] == "--top" && args.len() > 4 {
-                args[4].parse().context("Invalid top count")?
-            } else {
-                5
-            };
-            Ok(Command::Query { text, top })
         }
         cmd => {
             anyhow::bail!("Unknown command: {}", cmd);
@@ -66,18 +442,50 @@ fn print_usage() {
     eprintln!("Temporal AI - Pattern Recommendation Engine\n");
     eprintln!("USAGE:");
     eprintln!("  temporal-ai init");
-    eprintln!("  temporal-ai refresh [--commits N]");
+    eprintln!("  temporal-ai refresh [--commits N] [--full] [--branch NAME]");
+    eprintln!("                      [--author GLOB] [--exclude-author GLOB]");
     eprintln!("  temporal-ai refresh-metrics [--days N]");
-    eprintln!("  temporal-ai query <text> [--top N]");
-    eprintln!("  temporal-ai stats\n");
+    eprintln!("  temporal-ai query <text> [--top N] [--tag TAG]... [--synonyms FILE]");
+    eprintln!("  temporal-ai query --from-tag-centroid TAG,TAG... [--top N]");
+    eprintln!("  temporal-ai stats");
+    eprintln!("  temporal-ai status");
+    eprintln!("  temporal-ai list [--sort-by timestamp] [--order asc|desc] [--limit N]");
+    eprintln!("  temporal-ai serve --http [--addr HOST:PORT] [--warm-up]");
+    eprintln!("  temporal-ai import <path> [--overwrite]");
+    eprintln!("  temporal-ai compact [--in-place]");
+    eprintln!("  temporal-ai backup <path>");
+    eprintln!("  temporal-ai restore <path> [--overwrite]");
+    eprintln!("  temporal-ai migrate [--to VERSION]");
+    eprintln!("  temporal-ai dedup --by-content");
+    eprintln!("  temporal-ai build-index [--m N] [--ef-construction N]");
+    eprintln!("  temporal-ai download-model [--model gemma-300m-q4|gemma-300m-q8] [--dest DIR]");
+    eprintln!("  temporal-ai export-matrix [--format csv|json] [--max N]");
+    eprintln!("  temporal-ai export --format parquet|jsonl <path>");
+    eprintln!("  temporal-ai snapshot create <text> --output FILE");
+    eprintln!("  temporal-ai snapshot test <text> --input FILE [--tolerance T]\n");
     eprintln!("COMMANDS:");
     eprintln!("  init              Initialize empty database");
     eprintln!("  refresh           Index patterns from Git history");
     eprintln!("  refresh-metrics   Fetch performance metrics from OpenObserve");
     eprintln!("  query             Find similar patterns");
-    eprintln!("  stats             Show database statistics\n");
+    eprintln!("  stats             Show database statistics");
+    eprintln!("  status            Show where the last refresh stopped");
+    eprintln!("  list              List stored patterns chronologically");
+    eprintln!("  serve             Run the REST API (requires the `http` feature)");
+    eprintln!("  import            Load pre-embedded patterns from a JSONL file");
+    eprintln!("  compact           Rebuild the database to reclaim free space");
+    eprintln!("  backup            Write a snapshot of the database to a file");
+    eprintln!("  restore           Recreate the database from a backup file");
+    eprintln!("  migrate           Bring stored embeddings up to a schema version");
+    eprintln!("  dedup             Remove patterns with identical files and description");
+    eprintln!("  build-index       Build the HNSW index (requires the `hnsw` feature)");
+    eprintln!("  download-model    Download and verify the embedding model");
+    eprintln!("  export-matrix     Print pairwise pattern similarities to stdout");
+    eprintln!("  export            Write patterns and embeddings to a Parquet or JSONL file");
+    eprintln!("  snapshot          Record or check a query's top recommendations\n");
     eprintln!("EXAMPLES:");
     eprintln!("  temporal-ai refresh --commits 1000");
+    eprintln!("  temporal-ai refresh --author '*@mycompany.com' --exclude-author 'bot@*'");
     eprintln!("  temporal-ai query \"Add FastAPI authentication\" --top 5");
 }
 
@@ -89,6 +497,15 @@ fn get_db_path() -> PathBuf {
     PathBuf::from("data/temporal-ai.redb")
 }
 
+fn get_compacted_db_path() -> PathBuf {
+    PathBuf::from("data/temporal-ai.compacted.redb")
+}
+
+#[cfg(feature = "hnsw")]
+fn get_index_path() -> PathBuf {
+    PathBuf::from("data/temporal-ai.hnsw")
+}
+
 fn get_repo_path() -> PathBuf {
     PathBuf::from(".")
 }
@@ -106,7 +523,12 @@ async fn main() -> Result<()> {
             Ok(())
         }
 
-        Command::Refresh { commits } => {
+        Command::Refresh {
+            commits,
+            full,
+            authors,
+            branch,
+        } => {
             println!("Refreshing pattern database from Git history...");
             println!("Loading embedding model...");
 
@@ -126,34 +548,67 @@ async fn main() -> Result<()> {
             let store = VectorStore::open(&get_db_path()).context("Failed to open database")?;
             println!("✓ Database opened");
 
-            println!("Extracting patterns from last {} commits...", commits);
-            let extractor =
-                PatternExtractor::new(get_repo_path()).context("Failed to open Git repository")?;
-            let patterns = extractor
-                .extract_recent(commits)
-                .context("Failed to extract patterns")?;
+            let extractor = PatternExtractor::new(get_repo_path())
+                .context("Failed to open Git repository")?
+                .with_author_filter(authors);
+            let previous = store.get_refresh_state()?;
+            // The checkpoint tracks `HEAD`, so indexing another branch leaves it
+            // alone. It only moves once every pattern is stored.
+            let (patterns, checkpoint) = if let Some(branch) = &branch {
+                println!(
+                    "Extracting patterns from last {} commits of {}...",
+                    commits, branch
+                );
+                let patterns = extractor
+                    .extract_by_branch(branch, commits)
+                    .context("Failed to extract patterns")?;
+                (patterns, None)
+            } else if full {
+                println!("Re-scanning the last {} commits...", commits);
+                let head_sha = extractor.head_sha().context("Failed to resolve HEAD")?;
+                let patterns = extractor
+                    .extract_recent(commits)
+                    .context("Failed to extract patterns")?;
+                (patterns, Some(head_sha))
+            } else {
+                match &previous {
+                    Some(state) => {
+                        println!("Extracting patterns since {}...", state.last_indexed_sha)
+                    }
+                    None => println!("Extracting patterns from last {} commits...", commits),
+                }
+                let (patterns, head_sha) = extractor
+                    .extract_incremental(&store, commits)
+                    .context("Failed to extract patterns")?;
+                (patterns, Some(head_sha))
+            };
 
             println!("✓ Extracted {} patterns", patterns.len());
 
+            let total = patterns.len();
             if patterns.is_empty() {
                 println!("No patterns found");
-                return Ok(());
-            }
-
-            println!("Generating embeddings and storing...");
-            let total = patterns.len();
-            for (idx, pattern) in patterns.into_iter().enumerate() {
-                print!("\r  Progress: {}/{}", idx + 1, total);
-                std::io::Write::flush(&mut std::io::stdout())?;
+            } else {
+                println!("Generating embeddings and storing...");
+                for (idx, pattern) in patterns.into_iter().enumerate() {
+                    print!("\r  Progress: {}/{}", idx + 1, total);
+                    std::io::Write::flush(&mut std::io::stdout())?;
 
-                let embedding = embedder.embed(&pattern.description)?;
-                store.insert(&pattern, embedding)?;
+                    let embedding = embedder.embed(&pattern.description)?;
+                    store.insert(&pattern, embedding)?;
+                }
+                println!("\n✓ Processed {} patterns", total);
             }
 
-            println!("\n✓ Processed {} patterns", total);
+            if let Some(head_sha) = checkpoint {
+                store.set_refresh_state(RefreshState {
+                    last_indexed_sha: head_sha,
+                    last_run_at: chrono::Utc::now().timestamp(),
+                    total_indexed: previous.map_or(0, |state| state.total_indexed) + total as u64,
+                })?;
+            }
             println!("Database size: {} bytes", store.size()?);
             Ok(())
-
         }
 
         Command::RefreshMetrics { days } => {
@@ -169,11 +624,13 @@ async fn main() -> Result<()> {
 
             let mut updated = 0;
             for metric in metrics {
-                let mut perf = store.get_metrics(&metric.pattern_id)?.unwrap_or_default();
-
-                perf.success_rate = Some(metric.success_rate);
-                perf.avg_latency_ms = Some(metric.avg_latency_ms);
-                perf.error_rate = Some(metric.error_rate);
+                let perf = match store.get_metrics(&metric.pattern_id)? {
+                    Some(mut perf) => {
+                        perf.apply_observability_update(&metric);
+                        perf
+                    }
+                    None => PerformanceMetrics::from_observability(&metric),
+                };
 
                 store.update_metrics(&metric.pattern_id, perf)?;
                 updated += 1;
@@ -183,25 +640,47 @@ async fn main() -> Result<()> {
             Ok(())
         }
 
-        Command::Query { text, top } => {
-            println!("Searching for: \"{}\"", text);
+        Command::Query {
+            text,
+            centroid_tags,
+            top,
+            tags,
+            synonyms,
+        } => {
+            let store = VectorStore::open(&get_db_path())?;
 
-            let model_path = get_model_path();
-            if !model_path.exists() {
-                anyhow::bail!("Model not found: {}", model_path.display());
-            }
+            let query_embedding = if centroid_tags.is_empty() {
+                let text = text.unwrap_or_default();
+                println!("Searching for: \"{}\"", text);
 
-            println!("Loading model...");
-            let embedder = Embedder::from_gguf(&model_path)?;
+                let model_path = get_model_path();
+                if !model_path.exists() {
+                    anyhow::bail!("Model not found: {}", model_path.display());
+                }
 
-            let store = VectorStore::open(&get_db_path())?;
+                println!("Loading model...");
+                let embedder = Embedder::from_gguf(&model_path)?;
 
-            println!("Generating query embedding...");
-            let query_embedding = embedder.embed(&text)?;
+                println!("Generating query embedding...");
+                embedder.embed(&text)?
+            } else {
+                println!("Searching near the centroid of: {}", centroid_tags.join(" + "));
+                let centroid_tags: Vec<&str> = centroid_tags.iter().map(String::as_str).collect();
+                compute_tag_centroid(&store, &centroid_tags)?
+            };
 
             println!("Searching for similar patterns...");
-            let search = SimilaritySearch::new(&store);
-            let results = search.search(&query_embedding, top * 2)?;
+            let mut search = SimilaritySearch::new(&store);
+            if let Some(path) = synonyms {
+                let synonyms = TagSynonyms::from_json_file(&path)
+                    .with_context(|| format!("Failed to load synonyms from {}", path.display()))?;
+                search = search.with_synonyms(Arc::new(synonyms));
+            }
+            let filters = SearchFilters {
+                tags,
+                ..Default::default()
+            };
+            let results = search.search_filtered(&query_embedding, top * 2, &filters)?;
 
             if results.is_empty() {
                 println!("\nNo patterns found. Run 'temporal-ai refresh' first.");
@@ -210,10 +689,10 @@ async fn main() -> Result<()> {
 
             println!("Ranking recommendations...");
             let ranker = RecommendationRanker::new(&store);
-            let recommendations = ranker.rank(results)?;
+            let recommendations = ranker.rank_top_k(results, top)?;
 
             println!("\n=== Top {} Recommendations ===\n", top);
-            for (i, rec) in recommendations.iter().take(top).enumerate() {
+            for (i, rec) in recommendations.iter().enumerate() {
                 println!(
                     "{}. [Score: {:.3}] {}",
                     i + 1,
@@ -229,10 +708,9 @@ async fn main() -> Result<()> {
 
         Command::Stats => {
             let store = VectorStore::open(&get_db_path())?;
-            let patterns = store.list_patterns()?;
 
             println!("=== Temporal AI Database Statistics ===\n");
-            println!("Total patterns: {}", patterns.len());
+            println!("Total patterns: {}", store.count()?);
             println!(
                 "Database size: {} bytes ({:.2} MB)",
                 store.size()?,
@@ -243,5 +721,327 @@ async fn main() -> Result<()> {
 
             Ok(())
         }
+
+        Command::Status => {
+            let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+            match store.get_refresh_state()? {
+                Some(state) => {
+                    let last_run = chrono::DateTime::from_timestamp(state.last_run_at, 0)
+                        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                        .unwrap_or_default();
+                    println!("Last indexed commit: {}", state.last_indexed_sha);
+                    println!("Last refresh: {}", last_run);
+                    println!("Patterns indexed: {}", state.total_indexed);
+                }
+                None => println!("No refresh has run yet; `refresh` will index recent commits"),
+            }
+            Ok(())
+        }
+
+        Command::List { order, limit } => {
+            let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+            for pattern_id in store.list_patterns_sorted_by_timestamp(order, limit)? {
+                if let Some(pattern) = store.get_pattern(&pattern_id)? {
+                    let date = chrono::DateTime::from_timestamp(pattern.timestamp, 0)
+                        .map(|time| time.format("%Y-%m-%d").to_string())
+                        .unwrap_or_default();
+                    println!("{}  {}  {}", date, pattern_id, pattern.description);
+                }
+            }
+            Ok(())
+        }
+
+        Command::Serve {
+            http,
+            addr,
+            warm_up,
+        } => {
+            if !http {
+                anyhow::bail!("No server mode selected; pass --http");
+            }
+            serve_http(&addr, warm_up).await
+        }
+
+        Command::Import { path, overwrite } => {
+            println!("Importing patterns from {}...", path.display());
+            let store = VectorStore::open(&get_db_path()).context("Failed to open database")?;
+            let report = import_jsonl_with_embeddings(&store, &path, overwrite)
+                .with_context(|| format!("Failed to import {}", path.display()))?;
+
+            println!(
+                "✓ Inserted {}, skipped {}, failed {}",
+                report.inserted, report.skipped, report.failed
+            );
+            Ok(())
+        }
+
+        Command::Compact { in_place } => {
+            let db_path = get_db_path();
+            let dest = if in_place {
+                db_path.with_extension("redb.compact-tmp")
+            } else {
+                get_compacted_db_path()
+            };
+            if dest.exists() {
+                std::fs::remove_file(&dest)
+                    .with_context(|| format!("Failed to remove stale {}", dest.display()))?;
+            }
+
+            println!("Compacting {}...", db_path.display());
+            let store = VectorStore::open(&db_path).context("Failed to open database")?;
+            let report = store.compact(&dest).context("Failed to compact database")?;
+            drop(store);
+
+            if in_place {
+                std::fs::rename(&dest, &db_path)
+                    .with_context(|| format!("Failed to replace {}", db_path.display()))?;
+                println!("✓ Replaced {}", db_path.display());
+            } else {
+                println!("✓ Wrote {}", dest.display());
+            }
+            println!(
+                "Size: {} -> {} bytes ({} bytes saved)",
+                report.original_size_bytes, report.compacted_size_bytes, report.space_saved_bytes
+            );
+            Ok(())
+        }
+
+        Command::Backup { path } => {
+            let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+            store
+                .backup(&path)
+                .with_context(|| format!("Failed to back up to {}", path.display()))?;
+            println!("✓ Backed up {} patterns to {}", store.count()?, path.display());
+            Ok(())
+        }
+
+        Command::Restore { path, overwrite } => {
+            let db_path = get_db_path();
+            if db_path.exists() && !overwrite {
+                anyhow::bail!(
+                    "{} already exists; pass --overwrite to replace it",
+                    db_path.display()
+                );
+            }
+            let dest = db_path.with_extension("redb.restore-tmp");
+            if dest.exists() {
+                std::fs::remove_file(&dest)
+                    .with_context(|| format!("Failed to remove stale {}", dest.display()))?;
+            }
+            let store = VectorStore::restore(&path, &dest)
+                .with_context(|| format!("Failed to restore {}", path.display()))?;
+            let count = store.count()?;
+            drop(store);
+            std::fs::rename(&dest, &db_path)
+                .with_context(|| format!("Failed to replace {}", db_path.display()))?;
+            println!("✓ Restored {} patterns into {}", count, db_path.display());
+            Ok(())
+        }
+
+        Command::Migrate { to } => {
+            let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+            if !store.needs_migration(to)? {
+                println!("✓ Database is already at schema version {}", to);
+                return Ok(());
+            }
+
+            let from = match store.schema_version()? {
+                Some(version) => version.to_string(),
+                None => "unrecorded".to_string(),
+            };
+            println!("Migrating schema version {} -> {}...", from, to);
+            let migrated = store.migrate(to, Ok).context("Failed to migrate database")?;
+            println!("✓ Migrated {} embeddings to schema version {}", migrated, to);
+            Ok(())
+        }
+
+        Command::DedupByContent => {
+            println!("Removing patterns with duplicate content...");
+            let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+            let removed = store.dedup_by_content_hash()?;
+            println!("✓ Removed {} duplicate patterns", removed);
+            Ok(())
+        }
+
+        Command::BuildIndex { m, ef_construction } => build_index(m, ef_construction),
+
+        Command::DownloadModel {
+            model,
+            dest,
+            sha256,
+        } => {
+            let dest = dest.join(model.file_name());
+            println!("Downloading {} to {}...", model.file_name(), dest.display());
+
+            let progress = ProgressBar::new(0).with_style(
+                ProgressStyle::with_template(
+                    "{bar:40} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+                )
+                .context("Invalid progress bar template")?,
+            );
+            let report = download_model(
+                &reqwest::Client::new(),
+                &model.url(),
+                &dest,
+                sha256.as_deref(),
+                |downloaded, total| {
+                    if let Some(total) = total {
+                        progress.set_length(total);
+                    }
+                    progress.set_position(downloaded);
+                },
+            )
+            .await;
+            progress.finish_and_clear();
+            let report = report.context("Failed to download model")?;
+
+            if report.resumed_from > 0 {
+                println!("Resumed from byte {}", report.resumed_from);
+            }
+            println!("✓ Verified SHA-256 {}", report.sha256);
+            println!("✓ Model saved to: {}", report.path.display());
+            Ok(())
+        }
+
+        Command::Export { format, path } => match format {
+            ExportFormat::Parquet => export_parquet(&path),
+            ExportFormat::Jsonl => {
+                println!("Exporting patterns to {}...", path.display());
+                let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+                let file = std::fs::File::create(&path)
+                    .with_context(|| format!("Failed to create {}", path.display()))?;
+                let written = store
+                    .export_json(&mut std::io::BufWriter::new(file))
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                println!("✓ Wrote {} patterns", written);
+                Ok(())
+            }
+        },
+
+        Command::ExportMatrix { format, max } => {
+            let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+            let export = export_similarity_matrix(&store, max)?;
+            match format {
+                MatrixFormat::Csv => print!("{}", export.to_csv()),
+                MatrixFormat::Json => println!("{}", serde_json::to_string(&export)?),
+            }
+            Ok(())
+        }
+
+        Command::SnapshotCreate { query, output } => {
+            let recommendations = top_recommendations(&query, SNAPSHOT_SIZE)?;
+            let snapshot = RecommendationSnapshot::new(query, &recommendations);
+            snapshot
+                .save(&output)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            println!(
+                "✓ Saved {} recommendations to {}",
+                snapshot.entries.len(),
+                output.display()
+            );
+            Ok(())
+        }
+
+        Command::SnapshotTest {
+            query,
+            input,
+            tolerance,
+        } => {
+            let expected = RecommendationSnapshot::load(&input)
+                .with_context(|| format!("Failed to read {}", input.display()))?;
+            let recommendations = top_recommendations(&query, SNAPSHOT_SIZE)?;
+            let actual = RecommendationSnapshot::new(query, &recommendations);
+
+            let mismatches = expected.compare(&actual, tolerance);
+            if mismatches.is_empty() {
+                println!("✓ Recommendations match {}", input.display());
+                return Ok(());
+            }
+            for mismatch in &mismatches {
+                println!("✗ {}", mismatch);
+            }
+            anyhow::bail!(
+                "{} recommendations differ from {}",
+                mismatches.len(),
+                input.display()
+            )
+        }
     }
 }
+
+/// Embed `text` and rank the `top` best matches in the live database.
+fn top_recommendations(text: &str, top: usize) -> Result<Vec<Recommendation>> {
+    let model_path = get_model_path();
+    if !model_path.exists() {
+        anyhow::bail!("Model not found: {}", model_path.display());
+    }
+    let embedder = Embedder::from_gguf(&model_path)?;
+    let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+
+    let query_embedding = embedder.embed(text)?;
+    let results = SimilaritySearch::new(&store).search(&query_embedding, top * 2)?;
+    Ok(RecommendationRanker::new(&store).rank_top_k(results, top)?)
+}
+
+#[cfg(feature = "http")]
+async fn serve_http(addr: &str, warm_up: bool) -> Result<()> {
+    let addr: std::net::SocketAddr = addr.parse().context("Invalid --addr")?;
+    let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+
+    if warm_up {
+        let stats = store.warm_up(usize::MAX)?;
+        println!(
+            "✓ Warmed up {} embeddings ({} bytes) in {}ms",
+            stats.embeddings_read, stats.bytes_read, stats.elapsed_ms
+        );
+    }
+
+    println!("Serving REST API on http://{}", addr);
+    println!("OpenAPI spec: http://{}/openapi.json", addr);
+    temporal_ai::server::serve(std::sync::Arc::new(store), addr).await?;
+    Ok(())
+}
+
+#[cfg(not(feature = "http"))]
+async fn serve_http(_addr: &str, _warm_up: bool) -> Result<()> {
+    anyhow::bail!("temporal-ai was built without the `http` feature; rebuild with `--features http`")
+}
+
+#[cfg(feature = "hnsw")]
+fn build_index(m: usize, ef_construction: usize) -> Result<()> {
+    println!(
+        "Building HNSW index (m = {}, ef_construction = {})...",
+        m, ef_construction
+    );
+    let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+    let index = store
+        .build_hnsw_index(m, ef_construction)
+        .context("Failed to build index")?;
+
+    let index_path = get_index_path();
+    index.save(&index_path).context("Failed to write index")?;
+    println!("✓ Index written to: {}", index_path.display());
+    Ok(())
+}
+
+#[cfg(not(feature = "hnsw"))]
+fn build_index(_m: usize, _ef_construction: usize) -> Result<()> {
+    anyhow::bail!("temporal-ai was built without the `hnsw` feature; rebuild with `--features hnsw`")
+}
+
+#[cfg(feature = "parquet")]
+fn export_parquet(path: &std::path::Path) -> Result<()> {
+    println!("Exporting patterns to {}...", path.display());
+    let store = VectorStore::open(get_db_path()).context("Failed to open database")?;
+    let rows = temporal_ai::export_to_parquet(&store, path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("✓ Wrote {} patterns", rows);
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn export_parquet(_path: &std::path::Path) -> Result<()> {
+    anyhow::bail!(
+        "temporal-ai was built without the `parquet` feature; rebuild with `--features parquet`"
+    )
+}
@@ -0,0 +1,115 @@
+//! Tag centroids as query vectors
+//!
+//! Instead of embedding query text, a search can start from "the average of all
+//! patterns tagged `feat` and `rust`". [`compute_tag_centroid`] builds that
+//! vector from the stored embeddings; pass it to
+//! [`SimilaritySearch::search`](crate::similarity::SimilaritySearch::search) like
+//! any other query embedding.
+
+use crate::vector_store::VectorStore;
+use crate::{Result, TemporalAIError};
+use std::collections::HashSet;
+
+/// Compute the unit-length mean embedding of patterns carrying every tag in `tags`
+///
+/// # Errors
+///
+/// Returns `TemporalAIError::PatternNotFound` if `tags` is empty or no stored
+/// pattern has all of them, or an error if the store cannot be read.
+pub fn compute_tag_centroid(store: &VectorStore, tags: &[&str]) -> Result<Vec<f32>> {
+    let mut matching: Option<HashSet<String>> = None;
+    for tag in tags {
+        let ids: HashSet<String> = store.find_by_tag(tag)?.into_iter().collect();
+        matching = Some(match matching {
+            Some(current) => current.intersection(&ids).cloned().collect(),
+            None => ids,
+        });
+    }
+
+    let mut centroid: Vec<f32> = Vec::new();
+    let mut count = 0usize;
+    for pattern_id in matching.unwrap_or_default() {
+        let Some(embedding) = store.get_embedding(&pattern_id)? else {
+            continue;
+        };
+        if centroid.is_empty() {
+            centroid = vec![0.0; embedding.len()];
+        }
+        for (sum, value) in centroid.iter_mut().zip(&embedding) {
+            *sum += value;
+        }
+        count += 1;
+    }
+
+    if count == 0 {
+        return Err(TemporalAIError::PatternNotFound(format!(
+            "no pattern tagged {}",
+            tags.join(" + ")
+        )));
+    }
+
+    // Dividing by `count` does not change the direction, so normalizing the
+    // sum gives the normalized mean.
+    let norm = centroid.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in &mut centroid {
+            *value /= norm;
+        }
+    }
+    Ok(centroid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern_extractor::Pattern;
+    use tempfile::tempdir;
+
+    fn create_test_pattern(id: &str, tags: &[&str]) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            description: format!("Test pattern {}", id),
+            file_paths: vec![format!("src/{}.rs", id)],
+            commit_sha: format!("abcdef{}", id),
+            timestamp: 1_700_000_000,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        }
+    }
+
+    fn unit_vector(axis: usize) -> Vec<f32> {
+        let mut vector = vec![0.0; crate::EMBEDDING_DIM];
+        vector[axis] = 1.0;
+        vector
+    }
+
+    #[test]
+    fn test_compute_tag_centroid() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        store.insert(&create_test_pattern("1", &["feat", "rust"]), unit_vector(0))?;
+        store.insert(&create_test_pattern("2", &["feat", "rust"]), unit_vector(1))?;
+        store.insert(&create_test_pattern("3", &["feat", "python"]), unit_vector(2))?;
+
+        let centroid = compute_tag_centroid(&store, &["rust", "feat"])?;
+        let expected = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((centroid[0] - expected).abs() < 1e-6);
+        assert!((centroid[1] - expected).abs() < 1e-6);
+        assert_eq!(centroid[2], 0.0);
+
+        assert!(matches!(
+            compute_tag_centroid(&store, &["rust", "python"]),
+            Err(TemporalAIError::PatternNotFound(_))
+        ));
+        assert!(matches!(
+            compute_tag_centroid(&store, &[]),
+            Err(TemporalAIError::PatternNotFound(_))
+        ));
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 //! Embedding generation using GGUF models via llama.cpp
 
 use crate::{Result, TemporalAIError, EMBEDDING_DIM};
+#[cfg(feature = "async")]
+use futures::{Stream, StreamExt};
 use llama_cpp_2::{
     context::params::LlamaContextParams,
     llama_backend::LlamaBackend,
@@ -20,6 +22,11 @@ pub struct Embedder {
 
 impl Embedder {
     /// Load model from GGUF file
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::ModelLoadError` if the llama.cpp backend cannot be
+    /// initialized or the model file cannot be loaded.
     pub fn from_gguf(model_path: impl AsRef<Path>) -> Result<Self> {
         let model_path = model_path.as_ref();
 
@@ -52,6 +59,16 @@ impl Embedder {
     }
 
     /// Generate 768-dimensional embedding for text
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::InferenceError` if tokenization, context creation or
+    /// decoding fails.
+    #[tracing::instrument(
+        name = "embedder.embed",
+        skip_all,
+        fields(text_len = text.len(), embedding_dim = EMBEDDING_DIM)
+    )]
     pub fn embed(&self, text: &str) -> Result<Vec<f32>> {
         // Tokenize input
         let tokens = self.model.str_to_token(text, AddBos::Always).map_err(|e| {
@@ -142,11 +159,47 @@ impl Embedder {
     /// llama.cpp currently supports embedding one sequence at a time, so this
     /// method simply iterates through `texts` and calls [`Self::embed`] for
     /// each entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error produced by [`Embedder::embed`].
     pub fn embed_many(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         texts.iter().map(|text| self.embed(text)).collect()
     }
 
+    /// Embed a stream of texts on Tokio's blocking pool.
+    ///
+    /// Each text is embedded with [`Embedder::embed`] inside `spawn_blocking`, so
+    /// inference never stalls the async executor. Up to `buffer_size` texts are
+    /// embedded ahead of the consumer; results are yielded in input order. A
+    /// `buffer_size` of zero is treated as one.
+    ///
+    /// Each item is an error if embedding fails or the blocking task panics.
+    ///
+    /// Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn embed_stream(
+        self: Arc<Self>,
+        texts: impl Stream<Item = String>,
+        buffer_size: usize,
+    ) -> impl Stream<Item = Result<Vec<f32>>> {
+        texts
+            .map(move |text| {
+                let embedder = Arc::clone(&self);
+                async move {
+                    tokio::task::spawn_blocking(move || embedder.embed(&text))
+                        .await
+                        .map_err(std::io::Error::from)?
+                }
+            })
+            .buffered(buffer_size.max(1))
+    }
+
     /// Deprecated alias for [`Embedder::embed_many`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error produced by [`Embedder::embed`].
     #[deprecated(note = "Use `embed_many` for sequential embeddings")]
     pub fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         self.embed_many(texts)
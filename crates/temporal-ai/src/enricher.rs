@@ -0,0 +1,130 @@
+//! This module defines the [`PatternEnricher`] extension point, which lets callers
+//! attach their own metadata to patterns as they are extracted from Git history.
+//!
+//! Enrichers are registered on a `PatternExtractor` with `with_enricher` and run,
+//! in registration order, on every pattern after it has been built from a commit.
+//!
+//! # Implementing a custom enricher
+//!
+//! ```rust
+//! use temporal_ai::enricher::PatternEnricher;
+//! use temporal_ai::{Pattern, Result};
+//!
+//! /// Tags every pattern that touches a migration file.
+//! struct MigrationEnricher;
+//!
+//! impl PatternEnricher for MigrationEnricher {
+//!     fn enrich(&self, pattern: &mut Pattern) -> Result<()> {
+//!         if pattern.file_paths.iter().any(|p| p.contains("migrations/")) {
+//!             pattern.tags.push("migration".to_string());
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! ```rust,no_run
+//! # use temporal_ai::enricher::JiraTicketEnricher;
+//! # use temporal_ai::PatternExtractor;
+//! # fn main() -> temporal_ai::Result<()> {
+//! let extractor = PatternExtractor::new(".")?.with_enricher(Box::new(JiraTicketEnricher::new()));
+//! let patterns = extractor.extract_recent(100)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::pattern_extractor::Pattern;
+use crate::Result;
+use regex::Regex;
+
+/// A hook for post-processing patterns after extraction.
+///
+/// Implementations may modify any field of the pattern. Returning an error aborts
+/// extraction of the current commit and propagates to the caller.
+pub trait PatternEnricher: Send + Sync {
+    /// Adds metadata to `pattern` in place.
+    fn enrich(&self, pattern: &mut Pattern) -> Result<()>;
+}
+
+/// An enricher that tags patterns with the JIRA ticket keys in their description.
+///
+/// Any token of the form `ABC-123` is appended to `Pattern.tags`, skipping tags
+/// that are already present.
+pub struct JiraTicketEnricher {
+    ticket_re: Regex,
+}
+
+impl JiraTicketEnricher {
+    /// Creates a new `JiraTicketEnricher`.
+    pub fn new() -> Self {
+        Self {
+            ticket_re: Regex::new(r"\b[A-Z]+-\d+\b").unwrap(),
+        }
+    }
+}
+
+impl Default for JiraTicketEnricher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PatternEnricher for JiraTicketEnricher {
+    fn enrich(&self, pattern: &mut Pattern) -> Result<()> {
+        let tickets: Vec<String> = self
+            .ticket_re
+            .find_iter(&pattern.description)
+            .map(|m| m.as_str().to_string())
+            .collect();
+
+        for ticket in tickets {
+            if !pattern.tags.contains(&ticket) {
+                pattern.tags.push(ticket);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_pattern(description: &str) -> Pattern {
+        Pattern {
+            id: "test123".to_string(),
+            description: description.to_string(),
+            file_paths: vec!["src/main.rs".to_string()],
+            commit_sha: "abc123".to_string(),
+            timestamp: 1234567890,
+            tags: vec!["feat".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        }
+    }
+
+    #[test]
+    fn test_jira_ticket_enricher_adds_ticket_tags() {
+        let enricher = JiraTicketEnricher::new();
+        let mut pattern = create_test_pattern("add login flow for AUTH-42 and WEB-7 (AUTH-42)");
+
+        enricher.enrich(&mut pattern).unwrap();
+
+        assert_eq!(pattern.tags, vec!["feat", "AUTH-42", "WEB-7"]);
+    }
+
+    #[test]
+    fn test_jira_ticket_enricher_ignores_plain_text() {
+        let enricher = JiraTicketEnricher::new();
+        let mut pattern = create_test_pattern("bump utf-8 handling in x-42 parser");
+
+        enricher.enrich(&mut pattern).unwrap();
+
+        assert_eq!(pattern.tags, vec!["feat"]);
+    }
+}
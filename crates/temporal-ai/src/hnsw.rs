@@ -0,0 +1,225 @@
+//! Approximate nearest-neighbour retrieval with HNSW.
+//!
+//! [`SimilaritySearch`](crate::similarity::SimilaritySearch) scans every stored
+//! embedding. For large databases, [`TwoStageSearch`] first narrows the
+//! candidates with an HNSW graph built by [`VectorStore::build_hnsw_index`], and
+//! only the `10 × k` nearest patterns are loaded and passed to the
+//! [`RecommendationRanker`].
+//! [`SimilaritySearch::search_hnsw`](crate::similarity::SimilaritySearch::search_hnsw)
+//! uses the same candidates but returns them re-scored with exact cosine
+//! similarity instead of ranked.
+//!
+//! Requires the `hnsw` feature.
+
+use crate::ranker::{Recommendation, RecommendationRanker};
+use crate::similarity::{cosine_similarity, SimilarityResult};
+use crate::vector_store::VectorStore;
+use crate::Result;
+use instant_distance::{Builder, HnswMap, Search};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+/// Number of ANN candidates fetched per requested result.
+pub(crate) const CANDIDATE_MULTIPLIER: usize = 10;
+
+/// An embedding as a point in the HNSW graph, using cosine distance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EmbeddingPoint(Vec<f32>);
+
+impl instant_distance::Point for EmbeddingPoint {
+    fn distance(&self, other: &Self) -> f32 {
+        1.0 - cosine_similarity(&self.0, &other.0)
+    }
+}
+
+/// An HNSW graph over stored embeddings, keyed by pattern ID.
+#[derive(Serialize, Deserialize)]
+pub struct HnswIndex {
+    map: HnswMap<EmbeddingPoint, String>,
+}
+
+impl HnswIndex {
+    /// Builds an index from `(pattern ID, embedding)` pairs.
+    ///
+    /// `m` is the target number of neighbours per node and sets the level
+    /// distribution of the graph. `ef_construction` is the candidate list size
+    /// used while inserting, and also bounds how many results one search returns.
+    pub fn build(entries: Vec<(String, Vec<f32>)>, m: usize, ef_construction: usize) -> Self {
+        let (ids, points): (Vec<String>, Vec<EmbeddingPoint>) = entries
+            .into_iter()
+            .map(|(id, embedding)| (id, EmbeddingPoint(embedding)))
+            .unzip();
+
+        let map = Builder::default()
+            .ml(1.0 / (m.max(2) as f32).ln())
+            .ef_construction(ef_construction)
+            .ef_search(ef_construction)
+            .build(points, ids);
+
+        Self { map }
+    }
+
+    /// Returns up to `n` `(pattern ID, cosine similarity)` pairs nearest to `query`,
+    /// most similar first.
+    pub fn search(&self, query: &[f32], n: usize) -> Vec<(String, f32)> {
+        let mut search = Search::default();
+        self.map
+            .search(&EmbeddingPoint(query.to_vec()), &mut search)
+            .take(n)
+            .map(|item| (item.value.clone(), 1.0 - item.distance))
+            .collect()
+    }
+
+    /// Writes the index to `path` as MessagePack.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or the index cannot be encoded.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        rmp_serde::encode::write(&mut writer, self)?;
+        Ok(())
+    }
+
+    /// Reads an index written by [`HnswIndex::save`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or does not hold a saved index.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(rmp_serde::decode::from_read(reader)?)
+    }
+}
+
+/// ANN candidate retrieval followed by full re-ranking.
+pub struct TwoStageSearch<'a> {
+    /// Index used for the candidate retrieval stage.
+    pub ann_index: HnswIndex,
+    /// Store the candidates' patterns and metrics are loaded from.
+    pub store: &'a VectorStore,
+}
+
+impl<'a> TwoStageSearch<'a> {
+    /// Creates a two-stage search over `store` using a prebuilt `ann_index`.
+    pub fn new(ann_index: HnswIndex, store: &'a VectorStore) -> Self {
+        Self { ann_index, store }
+    }
+
+    /// Returns the top `k` recommendations for `query`.
+    ///
+    /// The index supplies `10 × k` candidates; patterns that have since been
+    /// removed from the store are skipped before ranking.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a candidate pattern cannot be read from the store.
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<Recommendation>> {
+        let mut candidates = Vec::new();
+        for (pattern_id, score) in self
+            .ann_index
+            .search(query, k.saturating_mul(CANDIDATE_MULTIPLIER))
+        {
+            if let Some(pattern) = self.store.get_pattern(&pattern_id)? {
+                candidates.push(SimilarityResult {
+                    pattern_id,
+                    score,
+                    pattern,
+                });
+            }
+        }
+
+        RecommendationRanker::new(self.store).rank_top_k(candidates, k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern_extractor::Pattern;
+    use crate::similarity::SimilaritySearch;
+    use tempfile::tempdir;
+
+    fn create_pattern(id: &str) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            description: format!("Pattern {}", id),
+            file_paths: vec!["src/lib.rs".to_string()],
+            commit_sha: format!("sha{}", id),
+            timestamp: 1_700_000_000,
+            tags: vec!["feat".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        }
+    }
+
+    fn unit_vector(axis: usize) -> Vec<f32> {
+        let mut embedding = vec![0.0; 768];
+        embedding[axis] = 1.0;
+        embedding
+    }
+
+    #[test]
+    fn test_two_stage_search_returns_nearest_pattern() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("hnsw.redb"))?;
+        for axis in 0..50 {
+            store.insert(&create_pattern(&axis.to_string()), unit_vector(axis))?;
+        }
+
+        let index = store.build_hnsw_index(16, 100)?;
+        let nearest = index.search(&unit_vector(7), 1);
+        assert_eq!(nearest[0].0, "7");
+        assert!((nearest[0].1 - 1.0).abs() < 1e-6);
+
+        let search = TwoStageSearch::new(index, &store);
+        let recommendations = search.search(&unit_vector(7), 3)?;
+        assert_eq!(recommendations.len(), 3);
+        assert_eq!(recommendations[0].pattern.id, "7");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_hnsw_rescores_candidates() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("hnsw.redb"))?;
+        for axis in 0..50 {
+            store.insert(&create_pattern(&axis.to_string()), unit_vector(axis))?;
+        }
+        let index = store.build_hnsw_index(16, 100)?;
+        store.delete_pattern("7")?;
+
+        let mut query = unit_vector(7);
+        query[8] = 0.5;
+        let results = SimilaritySearch::new(&store).search_hnsw(&query, 2, &index)?;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].pattern_id, "8");
+        assert!((results[0].score - cosine_similarity(&query, &unit_vector(8))).abs() < 1e-6);
+        assert!(results[1].score <= results[0].score);
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_save_and_load() -> Result<()> {
+        let dir = tempdir()?;
+        let entries = (0..10)
+            .map(|axis| (axis.to_string(), unit_vector(axis)))
+            .collect();
+        let index = HnswIndex::build(entries, 16, 50);
+
+        let path = dir.path().join("index.hnsw");
+        index.save(&path)?;
+        let loaded = HnswIndex::load(&path)?;
+
+        assert_eq!(loaded.search(&unit_vector(3), 1)[0].0, "3");
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+//! Import of pre-embedded patterns from newline-delimited JSON.
+//!
+//! Each line of the input holds one [`PatternRecord`]:
+//!
+//! ```text
+//! {"pattern": {...}, "embedding": [0.01, ...], "metrics": {...}}
+//! ```
+//!
+//! `metrics` is optional. Because the embedding travels with the pattern, a
+//! database built once (for example in CI) can be distributed and loaded on
+//! other machines without running embedding inference again.
+
+use crate::pattern_extractor::Pattern;
+use crate::schema::PerformanceMetrics;
+use crate::vector_store::VectorStore;
+use crate::{Result, EMBEDDING_DIM};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Number of records written per `insert_batch` transaction.
+pub(crate) const IMPORT_BATCH_SIZE: usize = 500;
+
+/// A single line of the JSONL interchange format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternRecord {
+    /// The pattern metadata.
+    pub pattern: Pattern,
+    /// The pattern's embedding; must have [`EMBEDDING_DIM`] components.
+    pub embedding: Vec<f32>,
+    /// Usage metrics to restore alongside the pattern, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<PerformanceMetrics>,
+}
+
+/// Outcome of [`import_jsonl_with_embeddings`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Records written to the store.
+    pub inserted: usize,
+    /// Records whose pattern ID already existed and were left untouched.
+    pub skipped: usize,
+    /// Lines that could not be parsed or carried an embedding of the wrong dimension.
+    pub failed: usize,
+}
+
+/// Import patterns and their embeddings from a JSONL file.
+///
+/// Patterns whose IDs already exist in `store` are counted as skipped unless
+/// `overwrite` is set. Malformed lines and embeddings whose length is not
+/// [`EMBEDDING_DIM`] are counted as failed without aborting the import. Valid
+/// records are written with [`VectorStore::insert_batch`]; stored metrics are
+/// restored when the record carries them.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or a database write fails.
+pub fn import_jsonl_with_embeddings(
+    store: &VectorStore,
+    path: impl AsRef<Path>,
+    overwrite: bool,
+) -> Result<ImportReport> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut report = ImportReport::default();
+    let mut batch: Vec<PatternRecord> = Vec::with_capacity(IMPORT_BATCH_SIZE);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: PatternRecord = match serde_json::from_str(&line) {
+            Ok(record) => record,
+            Err(_) => {
+                report.failed += 1;
+                continue;
+            }
+        };
+
+        if record.embedding.len() != EMBEDDING_DIM {
+            report.failed += 1;
+            continue;
+        }
+
+        if !overwrite && store.get_pattern(&store.storage_id(&record.pattern))?.is_some() {
+            report.skipped += 1;
+            continue;
+        }
+
+        batch.push(record);
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            report.inserted += flush_batch(store, &mut batch)?;
+        }
+    }
+
+    report.inserted += flush_batch(store, &mut batch)?;
+
+    Ok(report)
+}
+
+/// Write the pending records and restore any metrics they carry.
+pub(crate) fn flush_batch(store: &VectorStore, batch: &mut Vec<PatternRecord>) -> Result<usize> {
+    if batch.is_empty() {
+        return Ok(0);
+    }
+
+    let records: Vec<(Pattern, Vec<f32>)> = batch
+        .iter()
+        .map(|record| (record.pattern.clone(), record.embedding.clone()))
+        .collect();
+    store.insert_batch(&records)?;
+
+    for record in batch.iter_mut() {
+        if let Some(metrics) = record.metrics.take() {
+            store.update_metrics(&store.storage_id(&record.pattern), metrics)?;
+        }
+    }
+
+    let written = batch.len();
+    batch.clear();
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn create_pattern(id: &str) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            description: format!("Pattern {}", id),
+            file_paths: vec!["src/lib.rs".to_string()],
+            commit_sha: format!("sha{}", id),
+            timestamp: 1_700_000_000,
+            tags: vec!["feat".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        }
+    }
+
+    fn write_lines(path: &Path, lines: &[String]) {
+        let mut file = File::create(path).unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+    }
+
+    fn record_line(id: &str, dim: usize, metrics: Option<PerformanceMetrics>) -> String {
+        serde_json::to_string(&PatternRecord {
+            pattern: create_pattern(id),
+            embedding: vec![0.5; dim],
+            metrics,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_import_counts_inserted_skipped_and_failed() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("import.redb"))?;
+        store.insert(&create_pattern("existing"), vec![0.1; EMBEDDING_DIM])?;
+
+        let mut metrics = PerformanceMetrics::new();
+        metrics.usage_count = 7;
+
+        let input = dir.path().join("patterns.jsonl");
+        write_lines(
+            &input,
+            &[
+                record_line("a", EMBEDDING_DIM, Some(metrics)),
+                record_line("existing", EMBEDDING_DIM, None),
+                record_line("short", 3, None),
+                "not json".to_string(),
+                String::new(),
+                record_line("b", EMBEDDING_DIM, None),
+            ],
+        );
+
+        let report = import_jsonl_with_embeddings(&store, &input, false)?;
+        assert_eq!(
+            report,
+            ImportReport {
+                inserted: 2,
+                skipped: 1,
+                failed: 2,
+            }
+        );
+
+        assert_eq!(store.get_embedding("a")?.unwrap(), vec![0.5; EMBEDDING_DIM]);
+        assert_eq!(store.get_metrics("a")?.unwrap().usage_count, 7);
+        assert_eq!(store.get_embedding("existing")?.unwrap(), vec![0.1; EMBEDDING_DIM]);
+        assert!(store.get_pattern("short")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_overwrite_replaces_existing() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("import.redb"))?;
+        store.insert(&create_pattern("existing"), vec![0.1; EMBEDDING_DIM])?;
+
+        let input = dir.path().join("patterns.jsonl");
+        write_lines(&input, &[record_line("existing", EMBEDDING_DIM, None)]);
+
+        let report = import_jsonl_with_embeddings(&store, &input, true)?;
+        assert_eq!(report.inserted, 1);
+        assert_eq!(report.skipped, 0);
+        assert_eq!(store.get_embedding("existing")?.unwrap(), vec![0.5; EMBEDDING_DIM]);
+
+        Ok(())
+    }
+}
@@ -11,6 +11,26 @@
 //! - **Similarity Search** (`similarity`): Cosine similarity over vector space
 //! - **Recommendation Ranker** (`ranker`): Multi-factor scoring with recency and usage metrics
 //!
+//! ## WebAssembly
+//!
+//! Building for `wasm32-unknown-unknown` compiles out the llama.cpp `Embedder`, the
+//! `git2`-based `PatternExtractor` and the OpenObserve client. Enabling the `wasm32`
+//! feature adds `wasm::WasmRecommender`, which serves recommendations from an
+//! in-memory copy of a database file using embeddings computed by the caller:
+//!
+//! ```text
+//! wasm-pack build crates/temporal-ai --target web -- --features wasm32
+//! ```
+//!
+//! ## Tracing
+//!
+//! Embedding, insertion, search and ranking run inside named `tracing` spans
+//! (`embedder.embed`, `vector_store.insert`, `similarity.search_filtered`,
+//! `ranker.rank`) carrying `k`, `filter_count`, `result_count`, `embedding_dim`
+//! and `pattern_id` where relevant. The crate installs no subscriber itself;
+//! hosts that call `vibepro_observe::init_tracing` with `VIBEPRO_OBSERVE=1`
+//! export the resulting trace tree over OTLP.
+//!
 //! ## Usage
 //!
 //! ```rust,no_run
@@ -41,67 +61,142 @@
 //! # }
 //! ```
 
+#![deny(missing_docs)]
+#![deny(rustdoc::broken_intra_doc_links)]
+
+pub mod centroid;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod embedder;
+pub mod enricher;
+#[cfg(feature = "hnsw")]
+pub mod hnsw;
+pub mod import;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod model_download;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod observability_aggregator;
+#[cfg(feature = "parquet")]
+pub mod parquet_export;
 pub mod pattern_extractor;
 pub mod ranker;
 pub mod schema;
+#[cfg(feature = "http")]
+pub mod server;
 pub mod similarity;
+pub mod snapshot;
+pub mod synonyms;
 pub mod vector_store;
+#[cfg(feature = "wasm32")]
+pub mod wasm;
 
 // Re-exports
+#[cfg(not(target_arch = "wasm32"))]
 pub use embedder::Embedder;
-pub use pattern_extractor::{Pattern, PatternExtractor};
-pub use ranker::{Recommendation, RecommendationRanker};
-pub use schema::{EmbeddingRecord, PerformanceMetrics};
-pub use similarity::{SearchFilters, SimilarityResult, SimilaritySearch};
-pub use vector_store::VectorStore;
+pub use enricher::{JiraTicketEnricher, PatternEnricher};
+#[cfg(feature = "hnsw")]
+pub use hnsw::{HnswIndex, TwoStageSearch};
+pub use import::{import_jsonl_with_embeddings, ImportReport, PatternRecord};
+#[cfg(feature = "parquet")]
+pub use parquet_export::export_to_parquet;
+pub use pattern_extractor::{Pattern, PatternExtractorConfig, PrMetadata};
+#[cfg(not(target_arch = "wasm32"))]
+pub use pattern_extractor::PatternExtractor;
+pub use ranker::{RankerWeights, Recommendation, RecommendationRanker, RecommendationSet};
+pub use schema::{EmbeddingRecord, IdStrategy, PerformanceMetrics, RefreshState, StorageMode};
+pub use similarity::{
+    DedupStrategy, EmbeddingCache, SearchFilters, SimilarityMatrix, SimilarityMetric,
+    SimilarityResult, SimilaritySearch, TagFilterMode,
+};
+pub use synonyms::TagSynonyms;
+pub use vector_store::{CompactionReport, SortOrder, UpsertResult, VectorStore, WarmUpStats};
 
 /// Error types for the temporal-ai crate
 #[derive(Debug, thiserror::Error)]
 pub enum TemporalAIError {
+    /// The GGUF embedding model could not be loaded.
     #[error("Model loading failed: {0}")]
     ModelLoadError(String),
 
+    /// Tokenization or embedding inference failed.
     #[error("Inference failed: {0}")]
     InferenceError(String),
 
+    /// A generic redb error.
     #[error("Database error: {0}")]
     DatabaseError(#[from] redb::Error),
 
+    /// The database file could not be opened or created.
     #[error("Redb database error: {0}")]
     RedbDatabaseError(#[from] redb::DatabaseError),
 
+    /// A read or write transaction could not be started.
     #[error("Redb transaction error: {0}")]
     RedbTransactionError(#[from] redb::TransactionError),
 
+    /// A table could not be opened, e.g. because of a type mismatch.
     #[error("Redb table error: {0}")]
     RedbTableError(#[from] redb::TableError),
 
+    /// The storage backend failed while reading or writing pages.
     #[error("Redb storage error: {0}")]
     RedbStorageError(#[from] redb::StorageError),
 
+    /// A write transaction could not be committed.
     #[error("Redb commit error: {0}")]
     RedbCommitError(#[from] redb::CommitError),
 
+    /// Reading the Git repository failed.
+    #[cfg(not(target_arch = "wasm32"))]
     #[error("Git repository error: {0}")]
     GitError(#[from] git2::Error),
 
+    /// No pattern (or commit) with the given identifier is stored.
     #[error("Pattern not found: {0}")]
     PatternNotFound(String),
 
+    /// An embedding did not have the expected number of components.
     #[error("Invalid embedding dimension: expected {expected}, got {actual}")]
-    DimensionMismatch { expected: usize, actual: usize },
-
+    DimensionMismatch {
+        /// The required number of components.
+        expected: usize,
+        /// The number of components actually supplied.
+        actual: usize,
+    },
+
+    /// A downloaded model's or a backup's SHA-256 digest did not match the
+    /// expected value.
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// The expected lowercase hex digest.
+        expected: String,
+        /// The digest of the file's contents.
+        actual: String,
+    },
+
+    /// Encoding or decoding JSON or MessagePack failed.
     #[error("Serialization error: {0}")]
     SerializationError(String),
 
+    /// A filesystem operation failed.
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 
+    /// The OpenObserve backend could not be queried or returned an error.
     #[error("Observability error: {0}")]
     ObservabilityError(String),
 
+    /// Building an Arrow record batch failed.
+    #[cfg(feature = "parquet")]
+    #[error("Arrow error: {0}")]
+    ArrowError(#[from] arrow_schema::ArrowError),
+
+    /// Writing or reading a Parquet file failed.
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    ParquetError(#[from] parquet::errors::ParquetError),
+
+    /// The HTTP client failed to send a request.
+    #[cfg(not(target_arch = "wasm32"))]
     #[error("HTTP client error: {0}")]
     HttpError(#[from] reqwest::Error),
 }
@@ -124,6 +219,7 @@ impl From<rmp_serde::decode::Error> for TemporalAIError {
     }
 }
 
+/// Result type returned throughout the crate
 pub type Result<T> = std::result::Result<T, TemporalAIError>;
 
 /// Embedding dimension for embedding-gemma-300M
@@ -0,0 +1,234 @@
+//! Downloading the embedding model with integrity verification.
+//!
+//! [`download_model`] streams a GGUF file into `<dest>.part`, resuming an
+//! earlier partial download with an HTTP `Range` request, and only renames it
+//! to `dest` once its SHA-256 digest matches the expected value. A file that
+//! fails verification is deleted so the next attempt starts from scratch.
+//!
+//! The expected digest is either passed in by the caller or taken from the
+//! `X-Linked-Etag` header Hugging Face sends for LFS files, which carries the
+//! SHA-256 of the stored object.
+
+use crate::{Result, TemporalAIError};
+use reqwest::header::{HeaderMap, RANGE};
+use reqwest::{Client, StatusCode};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Base URL of the Hugging Face repository hosting the GGUF files.
+pub const MODEL_REPO_URL: &str = "https://huggingface.co/ggml-org/embeddinggemma-300M-GGUF";
+
+/// Header Hugging Face uses to publish the SHA-256 of an LFS file.
+const LINKED_ETAG_HEADER: &str = "x-linked-etag";
+
+/// Quantizations of embedding-gemma-300M that can be downloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelVariant {
+    /// 4-bit `Q4_K_M` quantization (~180MB).
+    Gemma300mQ4,
+    /// 8-bit `Q8_0` quantization, the CLI's default model.
+    Gemma300mQ8,
+}
+
+impl ModelVariant {
+    /// Parses the CLI name (`gemma-300m-q4` or `gemma-300m-q8`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gemma-300m-q4" => Some(Self::Gemma300mQ4),
+            "gemma-300m-q8" => Some(Self::Gemma300mQ8),
+            _ => None,
+        }
+    }
+
+    /// File name of the GGUF model.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Gemma300mQ4 => "embeddinggemma-300M-Q4_K_M.gguf",
+            Self::Gemma300mQ8 => "embeddinggemma-300M-Q8_0.gguf",
+        }
+    }
+
+    /// Direct download URL of the GGUF model.
+    pub fn url(self) -> String {
+        format!("{}/resolve/main/{}", MODEL_REPO_URL, self.file_name())
+    }
+}
+
+/// Outcome of a successful [`download_model`] call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadReport {
+    /// Final location of the verified model file.
+    pub path: PathBuf,
+    /// Total size of the file in bytes.
+    pub size_bytes: u64,
+    /// Bytes reused from an earlier partial download.
+    pub resumed_from: u64,
+    /// Lowercase hex SHA-256 digest of the file.
+    pub sha256: String,
+}
+
+/// Downloads `url` to `dest` and verifies its SHA-256 digest.
+///
+/// `expected_sha256` takes precedence over the digest published by the server.
+/// `on_progress` is called after every received chunk with the number of bytes
+/// on disk and, when known, the total size.
+///
+/// # Errors
+///
+/// Returns [`TemporalAIError::HttpError`] if the request fails or the server
+/// answers with an error status, [`TemporalAIError::ModelLoadError`] if no
+/// expected digest is available, and [`TemporalAIError::ChecksumMismatch`] if
+/// the downloaded file does not match it. Filesystem failures are returned as
+/// [`TemporalAIError::IoError`].
+pub async fn download_model(
+    client: &Client,
+    url: &str,
+    dest: impl AsRef<Path>,
+    expected_sha256: Option<&str>,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<DownloadReport> {
+    let dest = dest.as_ref();
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let part_path = partial_path(dest);
+
+    let existing = fs::metadata(&part_path).map_or(0, |meta| meta.len());
+    let mut request = client.get(url);
+    if existing > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing));
+    }
+    let mut response = request.send().await?;
+
+    // A server that ignores the range, or a file that already holds every byte,
+    // means starting over; anything else that is not a success is an error.
+    let resumed_from = match response.status() {
+        StatusCode::PARTIAL_CONTENT => existing,
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            fs::remove_file(&part_path)?;
+            response = client.get(url).send().await?.error_for_status()?;
+            0
+        }
+        _ => {
+            response = response.error_for_status()?;
+            0
+        }
+    };
+
+    let expected = match expected_sha256 {
+        Some(hash) => hash.trim().to_lowercase(),
+        None => published_sha256(response.headers()).ok_or_else(|| {
+            TemporalAIError::ModelLoadError(format!(
+                "{} did not publish a SHA-256 digest; pass one explicitly",
+                url
+            ))
+        })?,
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed_from > 0)
+        .truncate(resumed_from == 0)
+        .open(&part_path)?;
+    let total = response.content_length().map(|len| len + resumed_from);
+    let mut downloaded = resumed_from;
+    on_progress(downloaded, total);
+
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total);
+    }
+    file.flush()?;
+    drop(file);
+
+    let actual = sha256_file(&part_path)?;
+    if actual != expected {
+        fs::remove_file(&part_path)?;
+        return Err(TemporalAIError::ChecksumMismatch { expected, actual });
+    }
+
+    fs::rename(&part_path, dest)?;
+    Ok(DownloadReport {
+        path: dest.to_path_buf(),
+        size_bytes: downloaded,
+        resumed_from,
+        sha256: actual,
+    })
+}
+
+/// Path partial downloads of `dest` are written to.
+pub fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+/// Reads the SHA-256 digest from Hugging Face's `X-Linked-Etag` header.
+fn published_sha256(headers: &HeaderMap) -> Option<String> {
+    let etag = headers.get(LINKED_ETAG_HEADER)?.to_str().ok()?;
+    let etag = etag.trim_start_matches("W/").trim_matches('"');
+    let is_sha256 = etag.len() == 64 && etag.bytes().all(|b| b.is_ascii_hexdigit());
+    is_sha256.then(|| etag.to_lowercase())
+}
+
+/// Computes the lowercase hex SHA-256 digest of the file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn sha256_file(path: impl AsRef<Path>) -> Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+
+    let mut hex = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    Ok(hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_model_variant_names() {
+        assert_eq!(
+            ModelVariant::from_name("gemma-300m-q4"),
+            Some(ModelVariant::Gemma300mQ4)
+        );
+        assert_eq!(
+            ModelVariant::from_name("gemma-300m-q8"),
+            Some(ModelVariant::Gemma300mQ8)
+        );
+        assert_eq!(ModelVariant::from_name("gemma"), None);
+        assert_eq!(
+            ModelVariant::Gemma300mQ4.url(),
+            "https://huggingface.co/ggml-org/embeddinggemma-300M-GGUF/resolve/main/embeddinggemma-300M-Q4_K_M.gguf"
+        );
+        assert_eq!(
+            ModelVariant::Gemma300mQ8.url(),
+            "https://huggingface.co/ggml-org/embeddinggemma-300M-GGUF/resolve/main/embeddinggemma-300M-Q8_0.gguf"
+        );
+    }
+
+    #[test]
+    fn test_published_sha256() {
+        let digest = "A".repeat(64);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            LINKED_ETAG_HEADER,
+            HeaderValue::from_str(&format!("\"{}\"", digest)).unwrap(),
+        );
+        assert_eq!(published_sha256(&headers), Some("a".repeat(64)));
+
+        headers.insert(LINKED_ETAG_HEADER, HeaderValue::from_static("\"abc123\""));
+        assert_eq!(published_sha256(&headers), None);
+    }
+}
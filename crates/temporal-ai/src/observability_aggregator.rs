@@ -12,7 +12,7 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::env;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Defines the strategy for connecting to the OpenObserve backend.
 ///
@@ -72,6 +72,19 @@ pub struct PatternMetrics {
     pub recommendation_count: u64,
 }
 
+/// The result of a pre-flight [`ObservabilityClient::health_check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthStatus {
+    /// Whether `/healthz` answered with a success status.
+    pub reachable: bool,
+    /// Whether the configured credentials were accepted by the streams API.
+    pub authenticated: bool,
+    /// Round-trip time of the `/healthz` request, in milliseconds.
+    pub latency_ms: u32,
+    /// The server version, if `/healthz` reported one.
+    pub version: Option<String>,
+}
+
 #[derive(Serialize)]
 struct SearchRequest {
     query: SqlQuery,
@@ -157,6 +170,97 @@ impl ObservabilityClient {
         }
     }
 
+    /// Resolves the base URL to use for a request, applying the `Auto` fallback.
+    async fn active_url(&self) -> String {
+        let mut active_url = self.base_url.clone();
+
+        // Handle the fallback logic for `Auto` mode.
+        if self.mode == ObservabilityMode::Auto {
+            if self.is_local_available().await {
+                active_url = "http://localhost:5080".to_string();
+            } else if active_url.contains("localhost") {
+                if let Ok(online_url) = env::var("OPENOBSERVE_URL") {
+                    if !online_url.contains("localhost") {
+                        active_url = online_url;
+                    }
+                }
+            }
+        }
+
+        active_url
+    }
+
+    /// Checks whether the OpenObserve backend is reachable and accepts our credentials.
+    ///
+    /// The check first requests `/healthz` to determine reachability and measure
+    /// latency, then lists streams via `/api/{org}/streams` to verify authentication.
+    /// The streams call is skipped when the backend is unreachable.
+    ///
+    /// # Returns
+    ///
+    /// A `HealthStatus` describing the backend. Connection failures are reported as
+    /// `reachable: false` rather than as an error, so callers can use this for
+    /// pre-flight validation without special-casing transport errors.
+    ///
+    /// # Errors
+    ///
+    /// This method currently always returns `Ok`; transport failures are reported
+    /// through the returned status.
+    pub async fn health_check(&self) -> Result<HealthStatus> {
+        let active_url = self.active_url().await;
+
+        let started = Instant::now();
+        let health = self
+            .http_client
+            .get(format!("{}/healthz", active_url))
+            .send()
+            .await;
+        let latency_ms = u32::try_from(started.elapsed().as_millis()).unwrap_or(u32::MAX);
+
+        let unreachable = HealthStatus {
+            reachable: false,
+            authenticated: false,
+            latency_ms,
+            version: None,
+        };
+
+        let health = match health {
+            Ok(resp) if resp.status().is_success() => resp,
+            _ => return Ok(unreachable),
+        };
+
+        let version = health
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("version").and_then(|v| v.as_str()).map(String::from));
+
+        let authenticated = self
+            .http_client
+            .get(format!("{}/api/{}/streams", active_url, self.org))
+            .basic_auth(&self.user, Some(&self.auth_token))
+            .send()
+            .await
+            .map(|resp| resp.status().is_success())
+            .unwrap_or(false);
+
+        Ok(HealthStatus {
+            reachable: true,
+            authenticated,
+            latency_ms,
+            version,
+        })
+    }
+
+    /// Returns a view of this client that targets `org` instead of `OPENOBSERVE_ORG`.
+    ///
+    /// Useful for installations with one OpenObserve organization per team: the
+    /// connection, credentials and mode are shared, only the org in request URLs
+    /// changes.
+    pub fn with_org_override<'a>(&'a self, org: &'a str) -> ObservabilityClientWithOrg<'a> {
+        ObservabilityClientWithOrg { client: self, org }
+    }
+
     /// Queries the OpenObserve backend to retrieve performance metrics for all patterns.
     ///
     /// This method constructs and executes a SQL query against the OpenObserve search
@@ -171,25 +275,82 @@ impl ObservabilityClient {
     ///
     /// A `Result` containing a `Vec<PatternMetrics>` where each element corresponds
     /// to a pattern. The vector is sorted by recommendation count in descending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::ObservabilityError` if the request fails, the API
+    /// responds with a non-success status, or the response cannot be parsed.
     pub async fn query_pattern_metrics(&self, since_days: u32) -> Result<Vec<PatternMetrics>> {
-        let mut active_url = self.base_url.clone();
+        self.query_pattern_metrics_in(&self.org, since_days).await
+    }
 
-        // Handle the fallback logic for `Auto` mode.
-        if self.mode == ObservabilityMode::Auto {
-            if self.is_local_available().await {
-                active_url = "http://localhost:5080".to_string();
-            } else if active_url.contains("localhost") {
-                if let Ok(online_url) = env::var("OPENOBSERVE_URL") {
-                    if !online_url.contains("localhost") {
-                        active_url = online_url;
-                    }
-                }
-            }
-        }
+    /// Retrieves metrics for the `limit` most recommended patterns.
+    ///
+    /// Unlike [`ObservabilityClient::query_pattern_metrics`], the backend sorts
+    /// and truncates the result, so only `limit` rows are transferred.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::ObservabilityError` if the request fails, the API
+    /// responds with a non-success status, or the response cannot be parsed.
+    pub async fn query_top_patterns_by_usage(
+        &self,
+        limit: u32,
+        since_days: u32,
+    ) -> Result<Vec<PatternMetrics>> {
+        let sql = format!(
+            r#"
+            SELECT
+              pattern_id,
+              AVG(latency_ms) as avg_latency_ms,
+              COUNT(*) as recommendation_count,
+              SUM(CASE WHEN error = true THEN 1 ELSE 0 END) as error_count
+            FROM temporal_ai_recommendations
+            GROUP BY pattern_id
+            ORDER BY recommendation_count DESC LIMIT {limit}
+        "#
+        );
+        self.run_metrics_query(&self.org, sql, since_days, limit).await
+    }
 
-        let end_time = Utc::now().timestamp_micros();
-        let start_time = (Utc::now() - ChronoDuration::days(since_days as i64)).timestamp_micros();
+    /// Retrieves metrics for the `limit` patterns with the highest error rate.
+    ///
+    /// Patterns recommended fewer than `min_count` times are skipped, so a single
+    /// failed recommendation does not put a pattern at the top.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::ObservabilityError` if the request fails, the API
+    /// responds with a non-success status, or the response cannot be parsed.
+    pub async fn query_top_patterns_by_error_rate(
+        &self,
+        min_count: u32,
+        limit: u32,
+        since_days: u32,
+    ) -> Result<Vec<PatternMetrics>> {
+        let sql = format!(
+            r#"
+            SELECT
+              pattern_id,
+              AVG(latency_ms) as avg_latency_ms,
+              COUNT(*) as recommendation_count,
+              SUM(CASE WHEN error = true THEN 1 ELSE 0 END) as error_count,
+              SUM(CASE WHEN error = true THEN 1 ELSE 0 END) * 1.0 / COUNT(*) as error_rate
+            FROM temporal_ai_recommendations
+            GROUP BY pattern_id
+            HAVING COUNT(*) >= {min_count}
+            ORDER BY error_rate DESC LIMIT {limit}
+        "#
+        );
+        self.run_metrics_query(&self.org, sql, since_days, limit).await
+    }
 
+    /// Runs the pattern metrics query against the given organization.
+    async fn query_pattern_metrics_in(
+        &self,
+        org: &str,
+        since_days: u32,
+    ) -> Result<Vec<PatternMetrics>> {
         let sql = r#"
             SELECT
               pattern_id,
@@ -201,17 +362,37 @@ impl ObservabilityClient {
             ORDER BY recommendation_count DESC
         "#.to_string();
 
+        // Limit to the top 1000 patterns.
+        self.run_metrics_query(org, sql, since_days, 1000).await
+    }
+
+    /// Runs a per-pattern metrics `sql` query and parses the hits.
+    ///
+    /// `sql` must select `pattern_id`, `avg_latency_ms`, `recommendation_count`
+    /// and `error_count`; at most `size` hits are requested.
+    async fn run_metrics_query(
+        &self,
+        org: &str,
+        sql: String,
+        since_days: u32,
+        size: u32,
+    ) -> Result<Vec<PatternMetrics>> {
+        let active_url = self.active_url().await;
+
+        let end_time = Utc::now().timestamp_micros();
+        let start_time = (Utc::now() - ChronoDuration::days(since_days as i64)).timestamp_micros();
+
         let request = SearchRequest {
             query: SqlQuery {
                 sql,
                 start_time,
                 end_time,
                 from: 0,
-                size: 1000, // Limit to the top 1000 patterns.
+                size,
             },
         };
 
-        let url = format!("{}/api/{}/search", active_url, self.org);
+        let url = format!("{}/api/{}/search", active_url, org);
 
         let response = self.http_client
             .post(&url)
@@ -259,10 +440,35 @@ impl ObservabilityClient {
     }
 }
 
+/// An [`ObservabilityClient`] whose requests target an overriding organization.
+///
+/// Created by [`ObservabilityClient::with_org_override`].
+pub struct ObservabilityClientWithOrg<'a> {
+    client: &'a ObservabilityClient,
+    org: &'a str,
+}
+
+impl ObservabilityClientWithOrg<'_> {
+    /// Returns the organization used in request URLs.
+    pub fn org(&self) -> &str {
+        self.org
+    }
+
+    /// Same as [`ObservabilityClient::query_pattern_metrics`], scoped to the
+    /// overriding organization.
+    ///
+    /// # Errors
+    ///
+    /// See [`ObservabilityClient::query_pattern_metrics`].
+    pub async fn query_pattern_metrics(&self, since_days: u32) -> Result<Vec<PatternMetrics>> {
+        self.client.query_pattern_metrics_in(self.org, since_days).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mockito::Server;
+    use mockito::{Matcher, Server};
 
     #[tokio::test]
     async fn test_query_pattern_metrics_mock() {
@@ -303,4 +509,92 @@ mod tests {
         assert_eq!(metrics[0].error_rate, 0.02);
         assert_eq!(metrics[0].success_rate, 0.98);
     }
+
+    #[tokio::test]
+    async fn test_org_override_is_used_in_request_url() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/team-b/search")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"hits": []}"#)
+            .create_async()
+            .await;
+
+        let client = ObservabilityClient {
+            base_url: server.url(),
+            org: "default".to_string(),
+            user: "root".to_string(),
+            auth_token: "test-token".to_string(),
+            http_client: Client::new(),
+            mode: ObservabilityMode::Online,
+        };
+
+        let scoped = client.with_org_override("team-b");
+        assert_eq!(scoped.org(), "team-b");
+
+        let metrics = scoped.query_pattern_metrics(7).await.unwrap();
+        mock.assert_async().await;
+        assert!(metrics.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_top_patterns_queries_sort_on_backend() {
+        let mut server = Server::new_async().await;
+        let usage_mock = server
+            .mock("POST", "/api/default/search")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex("ORDER BY recommendation_count DESC LIMIT 20".to_string()),
+                Matcher::PartialJsonString(r#"{"query": {"size": 20}}"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{
+                "hits": [{
+                    "pattern_id": "popular",
+                    "avg_latency_ms": 12.0,
+                    "error_count": 0,
+                    "recommendation_count": 500
+                }]
+            }"#)
+            .create_async()
+            .await;
+        let error_mock = server
+            .mock("POST", "/api/default/search")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex("HAVING COUNT\\(\\*\\) >= 10".to_string()),
+                Matcher::Regex("ORDER BY error_rate DESC LIMIT 5".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{
+                "hits": [{
+                    "pattern_id": "flaky",
+                    "avg_latency_ms": 80.0,
+                    "error_count": 5,
+                    "recommendation_count": 10
+                }]
+            }"#)
+            .create_async()
+            .await;
+
+        let client = ObservabilityClient {
+            base_url: server.url(),
+            org: "default".to_string(),
+            user: "root".to_string(),
+            auth_token: "test-token".to_string(),
+            http_client: Client::new(),
+            mode: ObservabilityMode::Online,
+        };
+
+        let popular = client.query_top_patterns_by_usage(20, 7).await.unwrap();
+        usage_mock.assert_async().await;
+        assert_eq!(popular[0].pattern_id, "popular");
+        assert_eq!(popular[0].recommendation_count, 500);
+
+        let flaky = client.query_top_patterns_by_error_rate(10, 5, 7).await.unwrap();
+        error_mock.assert_async().await;
+        assert_eq!(flaky[0].pattern_id, "flaky");
+        assert_eq!(flaky[0].error_rate, 0.5);
+    }
 }
@@ -0,0 +1,174 @@
+//! Export of stored patterns and embeddings to Parquet.
+//!
+//! [`export_to_parquet`] writes one row per pattern so the vector store can be
+//! loaded straight into pandas, polars or DuckDB:
+//!
+//! | column        | type                              |
+//! |---------------|-----------------------------------|
+//! | `id`          | `String`                          |
+//! | `description` | `String`                          |
+//! | `commit_sha`  | `String`                          |
+//! | `timestamp`   | `Int64`                           |
+//! | `tags`        | `List<String>`                    |
+//! | `file_paths`  | `List<String>`                    |
+//! | `embedding`   | `FixedSizeList<Float32, 768>`     |
+//!
+//! Requires the `parquet` feature.
+
+use crate::pattern_extractor::Pattern;
+use crate::vector_store::VectorStore;
+use crate::{Result, TemporalAIError, EMBEDDING_DIM};
+use arrow_array::builder::{
+    FixedSizeListBuilder, Float32Builder, Int64Builder, ListBuilder, StringBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Number of patterns written per Parquet row group.
+pub const PARQUET_ROW_GROUP_SIZE: usize = 1000;
+
+/// Arrow schema of the exported file.
+fn export_schema() -> SchemaRef {
+    let string_list = DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)));
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("description", DataType::Utf8, false),
+        Field::new("commit_sha", DataType::Utf8, false),
+        Field::new("timestamp", DataType::Int64, false),
+        Field::new("tags", string_list.clone(), false),
+        Field::new("file_paths", string_list, false),
+        Field::new(
+            "embedding",
+            DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, true)),
+                EMBEDDING_DIM as i32,
+            ),
+            false,
+        ),
+    ]))
+}
+
+/// Write every stored pattern and its embedding to a Parquet file at `path`.
+///
+/// Rows are written in row groups of [`PARQUET_ROW_GROUP_SIZE`] patterns, so
+/// at most one row group is held in memory at a time. Returns the number of
+/// rows written.
+///
+/// # Errors
+///
+/// Returns an error if the store cannot be read, a stored embedding does not
+/// have [`EMBEDDING_DIM`] components, or the file cannot be written.
+pub fn export_to_parquet(store: &VectorStore, path: impl AsRef<Path>) -> Result<usize> {
+    let schema = export_schema();
+    let properties = WriterProperties::builder()
+        .set_max_row_group_size(PARQUET_ROW_GROUP_SIZE)
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema.clone(), Some(properties))?;
+
+    let mut written = 0;
+    for ids in store.list_patterns()?.chunks(PARQUET_ROW_GROUP_SIZE) {
+        let mut rows = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(row) = store.get_embedding_and_pattern(id)? {
+                rows.push(row);
+            }
+        }
+        if rows.is_empty() {
+            continue;
+        }
+
+        writer.write(&record_batch(&schema, &rows)?)?;
+        written += rows.len();
+    }
+
+    writer.close()?;
+    Ok(written)
+}
+
+/// Build one record batch from `(embedding, pattern)` rows.
+fn record_batch(schema: &SchemaRef, rows: &[(Vec<f32>, Pattern)]) -> Result<RecordBatch> {
+    let mut ids = StringBuilder::new();
+    let mut descriptions = StringBuilder::new();
+    let mut commit_shas = StringBuilder::new();
+    let mut timestamps = Int64Builder::new();
+    let mut tags = ListBuilder::new(StringBuilder::new());
+    let mut file_paths = ListBuilder::new(StringBuilder::new());
+    let mut embeddings = FixedSizeListBuilder::new(Float32Builder::new(), EMBEDDING_DIM as i32);
+
+    for (embedding, pattern) in rows {
+        if embedding.len() != EMBEDDING_DIM {
+            return Err(TemporalAIError::DimensionMismatch {
+                expected: EMBEDDING_DIM,
+                actual: embedding.len(),
+            });
+        }
+
+        ids.append_value(&pattern.id);
+        descriptions.append_value(&pattern.description);
+        commit_shas.append_value(&pattern.commit_sha);
+        timestamps.append_value(pattern.timestamp);
+        tags.append_value(pattern.tags.iter().map(Some));
+        file_paths.append_value(pattern.file_paths.iter().map(Some));
+        embeddings.values().append_slice(embedding);
+        embeddings.append(true);
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(ids.finish()),
+        Arc::new(descriptions.finish()),
+        Arc::new(commit_shas.finish()),
+        Arc::new(timestamps.finish()),
+        Arc::new(tags.finish()),
+        Arc::new(file_paths.finish()),
+        Arc::new(embeddings.finish()),
+    ];
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use tempfile::tempdir;
+
+    fn create_pattern(id: &str) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            description: format!("Pattern {}", id),
+            file_paths: vec!["src/lib.rs".to_string(), "src/main.rs".to_string()],
+            commit_sha: format!("sha{}", id),
+            timestamp: 1_700_000_000,
+            tags: vec!["feat".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        }
+    }
+
+    #[test]
+    fn test_export_to_parquet_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("export.redb"))?;
+        for i in 0..10 {
+            store.insert(&create_pattern(&format!("p{}", i)), vec![0.1; EMBEDDING_DIM])?;
+        }
+
+        let path = dir.path().join("patterns.parquet");
+        assert_eq!(export_to_parquet(&store, &path)?, 10);
+
+        let reader = SerializedFileReader::new(File::open(&path)?)?;
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 10);
+        assert_eq!(metadata.num_row_groups(), 1);
+        assert_eq!(metadata.file_metadata().schema_descr().root_schema().get_fields().len(), 7);
+        Ok(())
+    }
+}
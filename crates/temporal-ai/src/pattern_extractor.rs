@@ -1,16 +1,32 @@
 //! Git commit pattern extraction
+//!
+//! [`Pattern`] is available on every target; [`PatternExtractor`] depends on
+//! `git2` and is compiled out on `wasm32`.
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::enricher::PatternEnricher;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::vector_store::VectorStore;
+#[cfg(not(target_arch = "wasm32"))]
 use crate::{Result, TemporalAIError};
-use git2::{Commit, DiffOptions, Repository};
+#[cfg(not(target_arch = "wasm32"))]
+use git2::{
+    BranchType, Commit, DiffOptions, ErrorCode, Repository, RepositoryOpenFlags, Revwalk,
+};
+#[cfg(not(target_arch = "wasm32"))]
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+#[cfg(not(target_arch = "wasm32"))]
 use std::collections::HashSet;
 use std::fmt::Write;
+#[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
+use ulid::Ulid;
 
 /// Extracted pattern from Git commit history
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "http", derive(utoipa::ToSchema))]
 pub struct Pattern {
     /// Unique identifier (SHA-256 hash of content)
     pub id: String,
@@ -29,33 +45,361 @@ pub struct Pattern {
 
     /// Extracted tags (language, framework, commit type)
     pub tags: Vec<String>,
+
+    /// IDs of patterns linked via `VectorStore::link_patterns`
+    #[serde(default)]
+    pub related_ids: Vec<String>,
+
+    /// Lowercased email addresses from `Co-authored-by:` commit trailers
+    #[serde(default)]
+    pub co_authors: Vec<String>,
+
+    /// Pull request number from a `(#1234)` suffix on the commit subject
+    #[serde(default)]
+    pub pr_number: Option<u32>,
+
+    /// SHA-256 of the sorted file paths and description, without the commit SHA
+    ///
+    /// Identical changes committed to different repositories share a content
+    /// hash; see [`Pattern::compute_content_hash`].
+    #[serde(default)]
+    pub content_hash: Option<String>,
+
+    /// Lines added by the commit, across every file in its diff
+    #[serde(default)]
+    pub lines_added: u32,
+
+    /// Lines deleted by the commit, across every file in its diff
+    #[serde(default)]
+    pub lines_deleted: u32,
 }
 
 impl Pattern {
     /// Generate unique ID from content
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     fn generate_id(commit_sha: &str, description: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(commit_sha.as_bytes());
         hasher.update(description.as_bytes());
-        let digest = hasher.finalize();
-        let mut id = String::with_capacity(digest.len() * 2);
-        for byte in digest {
-            write!(&mut id, "{:02x}", byte).expect("write to string");
+        to_hex(&hasher.finalize())
+    }
+
+    /// Hash the sorted file paths and description, ignoring the commit
+    ///
+    /// Unlike `id`, the result does not depend on `commit_sha`, so the same
+    /// change copied between repositories hashes identically.
+    pub fn compute_content_hash(&self) -> String {
+        let mut file_paths: Vec<&str> = self.file_paths.iter().map(String::as_str).collect();
+        file_paths.sort_unstable();
+
+        let mut hasher = Sha256::new();
+        for file_path in file_paths {
+            hasher.update(file_path.as_bytes());
+            hasher.update(b"\n");
+        }
+        hasher.update(self.description.as_bytes());
+        to_hex(&hasher.finalize())
+    }
+
+    /// Generate a fresh ULID from the current time
+    ///
+    /// ULIDs sort lexicographically by creation time, unlike SHA-256 IDs.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn generate_ulid_id() -> String {
+        Ulid::new().to_string()
+    }
+
+    /// Derive a ULID for this pattern
+    ///
+    /// The time component is the commit timestamp and the random component is
+    /// taken from the SHA-256 of `id`, so the result is stable across calls and
+    /// IDs sort chronologically by commit.
+    pub fn ulid_id(&self) -> String {
+        let millis = u64::try_from(self.timestamp).unwrap_or(0).saturating_mul(1000);
+        let digest = Sha256::digest(self.id.as_bytes());
+        let mut random = [0u8; 16];
+        random[6..].copy_from_slice(&digest[..10]);
+        Ulid::from_parts(millis, u128::from_be_bytes(random)).to_string()
+    }
+
+    /// Whether `id` is already a ULID rather than a SHA-256 hex digest
+    pub fn is_ulid_id(id: &str) -> bool {
+        Ulid::from_string(id).is_ok()
+    }
+}
+
+/// Lowercase hex encoding of a digest
+fn to_hex(digest: &[u8]) -> String {
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        write!(&mut hex, "{:02x}", byte).expect("write to string");
+    }
+    hex
+}
+
+/// Technical keywords recognised by [`extract_semantic_tags_from_description`]
+pub const SEMANTIC_TAG_KEYWORDS: &[&str] = &[
+    "api",
+    "authentication",
+    "authorization",
+    "auth",
+    "cache",
+    "caching",
+    "cli",
+    "config",
+    "configuration",
+    "database",
+    "deployment",
+    "docker",
+    "graphql",
+    "grpc",
+    "logging",
+    "metrics",
+    "middleware",
+    "migration",
+    "oauth",
+    "performance",
+    "queue",
+    "security",
+    "serialization",
+    "session",
+    "schema",
+    "tracing",
+    "validation",
+    "websocket",
+];
+
+/// Extract technical keyword tags from a commit description
+///
+/// Splits on whitespace, lowercases, strips surrounding punctuation and keeps
+/// tokens found in [`SEMANTIC_TAG_KEYWORDS`], in order of first appearance.
+pub fn extract_semantic_tags_from_description(description: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+
+    for token in description.split_whitespace() {
+        let token = token
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if SEMANTIC_TAG_KEYWORDS.contains(&token.as_str()) && !tags.contains(&token) {
+            tags.push(token);
         }
-        id
+    }
+
+    tags
+}
+
+/// Extract co-author email addresses from `Co-authored-by: Name <email>` trailers
+///
+/// The trailer key is matched case-insensitively, emails are lowercased and
+/// duplicates are dropped.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn parse_co_authors(message: &str) -> Vec<String> {
+    const TRAILER: &str = "co-authored-by:";
+    let mut emails: Vec<String> = Vec::new();
+
+    for line in message.lines() {
+        let line = line.trim();
+        if line.len() < TRAILER.len() || !line[..TRAILER.len()].eq_ignore_ascii_case(TRAILER) {
+            continue;
+        }
+
+        let value = &line[TRAILER.len()..];
+        let email = match (value.find('<'), value.rfind('>')) {
+            (Some(start), Some(end)) if start < end => &value[start + 1..end],
+            _ => continue,
+        };
+        let email = email.trim().to_lowercase();
+        if !email.is_empty() && !emails.contains(&email) {
+            emails.push(email);
+        }
+    }
+
+    emails
+}
+
+/// Pull request metadata recovered from a squash-merge commit message
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrMetadata {
+    /// PR number from a trailing `(#1234)` on the subject line
+    pub number: Option<u32>,
+
+    /// Lowercased labels from `Labels: a, b` lines in the body
+    pub labels: Vec<String>,
+
+    /// First paragraph of the body, joined onto one line
+    pub description_summary: String,
+}
+
+/// Parse the `1234` out of a subject ending in `(#1234)`
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn parse_pr_number(subject: &str) -> Option<u32> {
+    let rest = subject.trim_end().strip_suffix(')')?;
+    let start = rest.rfind("(#")?;
+    rest[start + 2..].parse().ok()
+}
+
+/// Open the repository at `path`, following a worktree's `.git` file if needed
+///
+/// In a linked worktree `.git` is a file containing `gitdir: <path>`; when the
+/// plain open fails, that git directory (relative to `path` unless absolute) is
+/// opened instead.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_repository(path: &Path) -> Result<Repository> {
+    let err = match Repository::open(path) {
+        Ok(repo) => return Ok(repo),
+        Err(err) => err,
+    };
+
+    let dot_git = path.join(".git");
+    if !dot_git.is_file() {
+        return Err(err.into());
+    }
+    let contents = std::fs::read_to_string(&dot_git)?;
+    let gitdir = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))
+        .map(str::trim)
+        .ok_or(err)?;
+
+    Ok(Repository::open_ext(
+        path.join(gitdir),
+        RepositoryOpenFlags::NO_SEARCH,
+        std::iter::empty::<&std::ffi::OsStr>(),
+    )?)
+}
+
+/// File name patterns treated as generated code or lockfiles
+///
+/// A leading `*` matches any prefix; other entries must equal the file name.
+pub const GENERATED_FILE_PATTERNS: &[&str] = &[
+    "*.pb.rs",
+    "*_generated.rs",
+    "*.pb.go",
+    "*_pb2.py",
+    "*.min.js",
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+];
+
+/// Whether the file name of `path` matches [`GENERATED_FILE_PATTERNS`]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn is_generated_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    GENERATED_FILE_PATTERNS
+        .iter()
+        .any(|pattern| match pattern.strip_prefix('*') {
+            Some(suffix) => name.ends_with(suffix),
+            None => name == *pattern,
+        })
+}
+
+/// Limits that keep enormous commits, like regenerated bindings, out of the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternExtractorConfig {
+    /// Commits changing more files than this are skipped
+    pub max_files_per_commit: usize,
+
+    /// Commits whose patch text exceeds this many bytes are skipped; `usize::MAX`
+    /// disables the check and the extra diff pass it needs
+    pub max_diff_bytes: usize,
+
+    /// Leave files matching [`GENERATED_FILE_PATTERNS`] out of `file_paths`
+    pub skip_generated_files: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for PatternExtractorConfig {
+    fn default() -> Self {
+        PatternExtractor::DEFAULT_CONFIG
     }
 }
 
 /// Extract patterns from Git repository
+#[cfg(not(target_arch = "wasm32"))]
 pub struct PatternExtractor {
     repo: Repository,
     conventional_commit_re: Regex,
+    enrichers: Vec<Box<dyn PatternEnricher>>,
+    config: PatternExtractorConfig,
+    author_filter: AuthorFilter,
+}
+
+/// Author email globs set by [`PatternExtractor::with_author_filter`]
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct AuthorFilter {
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl AuthorFilter {
+    const MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+
+    fn new(authors: &[String]) -> Self {
+        let mut filter = Self::default();
+        for author in authors {
+            let (globs, pattern) = match author.strip_prefix('!') {
+                Some(pattern) => (&mut filter.exclude, pattern),
+                None => (&mut filter.include, author.as_str()),
+            };
+            // An invalid glob is matched literally.
+            let glob = glob::Pattern::new(pattern)
+                .or_else(|_| glob::Pattern::new(&glob::Pattern::escape(pattern)))
+                .expect("escaped glob is always valid");
+            globs.push(glob);
+        }
+        filter
+    }
+
+    fn allows(&self, email: &str) -> bool {
+        let matches = |glob: &glob::Pattern| glob.matches_with(email, Self::MATCH_OPTIONS);
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 impl PatternExtractor {
+    /// Limits used by [`PatternExtractor::new`]
+    pub const DEFAULT_CONFIG: PatternExtractorConfig = PatternExtractorConfig {
+        max_files_per_commit: 200,
+        max_diff_bytes: 1024 * 1024,
+        skip_generated_files: true,
+    };
+
     /// Create new extractor for a repository
+    ///
+    /// `repo_path` may also be the root of a linked `git worktree`, whose `.git`
+    /// entry is a file pointing at the real git directory. Commits are filtered
+    /// with [`PatternExtractor::DEFAULT_CONFIG`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if `repo_path` is not a Git repository
+    /// or worktree.
     pub fn new(repo_path: impl AsRef<Path>) -> Result<Self> {
-        let repo = Repository::open(repo_path)?;
+        Self::with_config(repo_path, Self::DEFAULT_CONFIG)
+    }
+
+    /// Create new extractor for a repository with custom commit size limits
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if `repo_path` is not a Git repository
+    /// or worktree.
+    pub fn with_config(
+        repo_path: impl AsRef<Path>,
+        config: PatternExtractorConfig,
+    ) -> Result<Self> {
+        let repo = open_repository(repo_path.as_ref())?;
 
         // Conventional commits: type(scope)?: subject
         let conventional_commit_re = Regex::new(
@@ -66,18 +410,175 @@ impl PatternExtractor {
         Ok(Self {
             repo,
             conventional_commit_re,
+            enrichers: Vec::new(),
+            config,
+            author_filter: AuthorFilter::default(),
         })
     }
 
+    /// Extract patterns from the last N commits of the branch checked out in a worktree
+    ///
+    /// Shorthand for [`PatternExtractor::new`] followed by
+    /// [`PatternExtractor::extract_recent`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if `worktree_path` is not a Git
+    /// repository or worktree, or its history cannot be walked.
+    pub fn extract_from_worktree(
+        worktree_path: impl AsRef<Path>,
+        count: usize,
+    ) -> Result<Vec<Pattern>> {
+        Self::new(worktree_path)?.extract_recent(count)
+    }
+
+    /// Register an enricher to run on every extracted pattern
+    ///
+    /// Enrichers run in the order they were added. See [`PatternEnricher`].
+    pub fn with_enricher(mut self, enricher: Box<dyn PatternEnricher>) -> Self {
+        self.enrichers.push(enricher);
+        self
+    }
+
+    /// Only extract commits whose author email matches `authors`
+    ///
+    /// Each entry is a glob such as `*@mycompany.com`; a `!` prefix excludes
+    /// matching authors instead, e.g. `!bot@*`. A commit is kept if its author
+    /// matches at least one including glob (or there are none) and no excluding
+    /// glob. Matching ignores case. An empty list keeps every author.
+    pub fn with_author_filter(mut self, authors: Vec<String>) -> Self {
+        self.author_filter = AuthorFilter::new(&authors);
+        self
+    }
+
     /// Extract patterns from last N commits
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if the history cannot be walked or a commit
+    /// cannot be diffed.
     pub fn extract_recent(&self, count: usize) -> Result<Vec<Pattern>> {
         let mut revwalk = self.repo.revwalk()?;
         revwalk.push_head()?;
+        self.extract_from_revwalk(revwalk, count)
+    }
+
+    /// Extract patterns from the last N commits of a local branch
+    ///
+    /// Like [`PatternExtractor::extract_recent`], but walks from the tip of
+    /// `branch` instead of `HEAD`, so branches other than the checked-out one
+    /// can be indexed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if `branch` is not a local branch (the
+    /// message lists the local branches), or the history cannot be walked.
+    pub fn extract_by_branch(&self, branch: &str, count: usize) -> Result<Vec<Pattern>> {
+        let oid = match self.repo.find_branch(branch, BranchType::Local) {
+            Ok(found) => found.get().peel_to_commit()?.id(),
+            Err(e) if e.code() == ErrorCode::NotFound => {
+                let mut names = Vec::new();
+                for local in self.repo.branches(Some(BranchType::Local))? {
+                    if let Some(name) = local?.0.name()? {
+                        names.push(name.to_string());
+                    }
+                }
+                let available = if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                };
+                return Err(git2::Error::from_str(&format!(
+                    "branch '{}' not found; local branches: {}",
+                    branch, available
+                ))
+                .into());
+            }
+            Err(e) => return Err(e.into()),
+        };
 
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(oid)?;
+        self.extract_from_revwalk(revwalk, count)
+    }
+
+    /// Extract patterns from commits reachable from `HEAD` but not from `sha`
+    ///
+    /// Used by incremental `refresh` runs: `sha` is the `HEAD` recorded by the
+    /// previous run, so only commits made since then are read. Patterns are
+    /// returned newest first; merge commits are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if `sha` is not a commit in the
+    /// repository (e.g. after a force push), or the history cannot be walked.
+    pub fn extract_since_sha(&self, sha: &str) -> Result<Vec<Pattern>> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.hide(self.repo.revparse_single(sha)?.peel_to_commit()?.id())?;
+        self.extract_from_revwalk(revwalk, usize::MAX)
+    }
+
+    /// Extract patterns from commits made since the store's checkpoint
+    ///
+    /// The checkpoint is the `last_indexed_sha` of the store's
+    /// [`RefreshState`](crate::RefreshState); every commit after it is read,
+    /// however many there are. Without one, this behaves like
+    /// [`PatternExtractor::extract_recent`] with `fallback_count`; if the
+    /// checkpoint commit is no longer in the repository (e.g. after a force
+    /// push), a warning is logged and the `fallback_count` most recent commits
+    /// are read instead.
+    ///
+    /// Returns the patterns together with the SHA of the `HEAD` that was walked.
+    /// The checkpoint is left alone: store the patterns first, then record that
+    /// SHA with [`VectorStore::set_refresh_state`], so a failed run is retried.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if the history cannot be walked, or an
+    /// error if the checkpoint cannot be read.
+    pub fn extract_incremental(
+        &self,
+        store: &VectorStore,
+        fallback_count: usize,
+    ) -> Result<(Vec<Pattern>, String)> {
+        let head = self.repo.head()?.peel_to_commit()?.id();
+
+        let checkpoint = match store.get_refresh_state()? {
+            Some(state) => match self.repo.revparse_single(&state.last_indexed_sha) {
+                Ok(object) => Some(object.peel_to_commit()?.id()),
+                Err(e) if e.code() == ErrorCode::NotFound => {
+                    tracing::warn!(
+                        sha = %state.last_indexed_sha,
+                        "checkpoint commit not found; re-extracting recent commits"
+                    );
+                    None
+                }
+                Err(e) => return Err(e.into()),
+            },
+            None => None,
+        };
+
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(head)?;
+        let limit = match checkpoint {
+            Some(oid) => {
+                revwalk.hide(oid)?;
+                usize::MAX
+            }
+            None => fallback_count,
+        };
+        let patterns = self.extract_from_revwalk(revwalk, limit)?;
+
+        Ok((patterns, head.to_string()))
+    }
+
+    /// Extract up to `limit` patterns from the commits of `revwalk`, skipping merges
+    fn extract_from_revwalk(&self, revwalk: Revwalk<'_>, limit: usize) -> Result<Vec<Pattern>> {
         let mut patterns = Vec::new();
 
         for oid in revwalk {
-            if patterns.len() >= count {
+            if patterns.len() >= limit {
                 break;
             }
             let oid = oid?;
@@ -96,7 +597,22 @@ impl PatternExtractor {
         Ok(patterns)
     }
 
+    /// The full SHA of the commit `HEAD` points to
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if `HEAD` cannot be resolved, e.g. in
+    /// a repository without commits.
+    pub fn head_sha(&self) -> Result<String> {
+        Ok(self.repo.head()?.peel_to_commit()?.id().to_string())
+    }
+
     /// Extract patterns matching file glob
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if the history cannot be walked or a commit
+    /// cannot be diffed.
     pub fn extract_by_path(&self, glob: &str) -> Result<Vec<Pattern>> {
         let mut revwalk = self.repo.revwalk()?;
         revwalk.push_head()?;
@@ -129,6 +645,11 @@ impl PatternExtractor {
     }
 
     /// Extract patterns from date range
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::GitError` if the history cannot be walked or a commit
+    /// cannot be diffed.
     pub fn extract_by_date(&self, since: i64, until: i64) -> Result<Vec<Pattern>> {
         let mut revwalk = self.repo.revwalk()?;
         revwalk.push_head()?;
@@ -170,31 +691,113 @@ impl PatternExtractor {
             return Ok(None);
         }
 
+        if !self
+            .author_filter
+            .allows(commit.author().email().unwrap_or(""))
+        {
+            return Ok(None);
+        }
+
         let (commit_type, description) = self.parse_commit_message(message);
 
-        // Get file paths from diff
-        let file_paths = self.extract_file_paths(commit)?;
+        // Get file paths and line counts from diff
+        let (file_paths, lines_added, lines_deleted) = self.extract_file_paths(commit)?;
 
         if file_paths.is_empty() {
             return Ok(None);
         }
 
-        // Extract tags
+        // Extract tags, falling back to description keywords when no language
+        // could be inferred (e.g. config- or docs-only commits)
         let mut tags = vec![commit_type.to_string()];
-        tags.extend(self.extract_language_tags(&file_paths));
+        let language_tags = self.extract_language_tags(&file_paths);
+        if language_tags.is_empty() {
+            for tag in extract_semantic_tags_from_description(description) {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        } else {
+            tags.extend(language_tags);
+        }
 
-        let pattern = Pattern {
+        let pr_metadata = Self::extract_pr_metadata(message);
+        if let Some(metadata) = &pr_metadata {
+            for label in &metadata.labels {
+                if !tags.contains(label) {
+                    tags.push(label.clone());
+                }
+            }
+        }
+
+        let mut pattern = Pattern {
             id: Pattern::generate_id(&commit.id().to_string(), description),
             description: description.to_string(),
             file_paths,
             commit_sha: commit.id().to_string(),
             timestamp: commit.time().seconds(),
             tags,
+            related_ids: Vec::new(),
+            co_authors: parse_co_authors(message),
+            pr_number: pr_metadata.and_then(|metadata| metadata.number),
+            content_hash: None,
+            lines_added,
+            lines_deleted,
         };
 
+        for enricher in &self.enrichers {
+            enricher.enrich(&mut pattern)?;
+        }
+        pattern.content_hash = Some(pattern.compute_content_hash());
+
         Ok(Some(pattern))
     }
 
+    /// Parse pull request metadata from a squash-merged commit message
+    ///
+    /// Reads the PR number from a `(#1234)` suffix on the first line, labels
+    /// from `Labels:` lines (comma-separated, matched case-insensitively) and
+    /// the first body paragraph as the summary. Returns `None` when the message
+    /// carries none of these.
+    pub fn extract_pr_metadata(message: &str) -> Option<PrMetadata> {
+        const LABELS: &str = "labels:";
+        let mut lines = message.lines();
+        let number = lines.next().and_then(parse_pr_number);
+
+        let mut labels: Vec<String> = Vec::new();
+        let mut summary: Vec<&str> = Vec::new();
+        let mut summary_done = false;
+        for line in lines {
+            let line = line.trim();
+            let is_labels = line
+                .get(..LABELS.len())
+                .is_some_and(|key| key.eq_ignore_ascii_case(LABELS));
+            if is_labels {
+                for label in line[LABELS.len()..].split(',') {
+                    let label = label.trim().to_lowercase();
+                    if !label.is_empty() && !labels.contains(&label) {
+                        labels.push(label);
+                    }
+                }
+            } else if line.is_empty() {
+                summary_done |= !summary.is_empty();
+            } else if !summary_done {
+                summary.push(line);
+            }
+        }
+
+        let description_summary = summary.join(" ");
+        if number.is_none() && labels.is_empty() && description_summary.is_empty() {
+            return None;
+        }
+
+        Some(PrMetadata {
+            number,
+            labels,
+            description_summary,
+        })
+    }
+
     /// Parse commit message into type and description
     fn parse_commit_message<'a>(&self, message: &'a str) -> (&'a str, &'a str) {
         let first_line = message.lines().next().unwrap_or("");
@@ -223,8 +826,8 @@ impl PatternExtractor {
         automated_patterns.iter().any(|p| message.contains(p))
     }
 
-    /// Extract file paths from commit diff
-    fn extract_file_paths(&self, commit: &Commit) -> Result<Vec<String>> {
+    /// Extract file paths and the lines added and deleted from commit diff
+    fn extract_file_paths(&self, commit: &Commit) -> Result<(Vec<String>, u32, u32)> {
         let mut file_paths = HashSet::new();
 
         let tree = commit.tree()?;
@@ -243,6 +846,30 @@ impl PatternExtractor {
                 .diff_tree_to_tree(None, Some(&tree), Some(&mut diff_opts))?
         };
 
+        // An empty list makes `extract_from_commit` skip the commit.
+        if diff.deltas().len() > self.config.max_files_per_commit {
+            return Ok((Vec::new(), 0, 0));
+        }
+
+        if self.config.max_diff_bytes < usize::MAX {
+            let max_diff_bytes = self.config.max_diff_bytes;
+            let mut diff_bytes = 0;
+            let result = diff.foreach(
+                &mut |_, _| true,
+                None,
+                None,
+                Some(&mut |_, _, line| {
+                    diff_bytes += line.content().len();
+                    diff_bytes <= max_diff_bytes
+                }),
+            );
+            // Returning `false` above aborts the walk with an error.
+            if diff_bytes > max_diff_bytes {
+                return Ok((Vec::new(), 0, 0));
+            }
+            result?;
+        }
+
         diff.foreach(
             &mut |delta, _| {
                 if let Some(path) = delta.new_file().path() {
@@ -257,7 +884,15 @@ impl PatternExtractor {
             None,
         )?;
 
-        Ok(file_paths.into_iter().collect())
+        if self.config.skip_generated_files {
+            file_paths.retain(|path| !is_generated_file(path));
+        }
+
+        let stats = diff.stats()?;
+        let lines_added = u32::try_from(stats.insertions()).unwrap_or(u32::MAX);
+        let lines_deleted = u32::try_from(stats.deletions()).unwrap_or(u32::MAX);
+
+        Ok((file_paths.into_iter().collect(), lines_added, lines_deleted))
     }
 
     /// Extract language tags from file extensions
@@ -293,18 +928,23 @@ impl PatternExtractor {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parse_conventional_commit() {
         let extractor = PatternExtractor {
-            repo: unsafe { std::mem::zeroed() }, // Not used in this test
+            // SAFETY: `Repository` wraps a single raw pointer; these tests never
+            // touch the repository, and libgit2 ignores a null handle on drop.
+            repo: unsafe { std::mem::zeroed() },
             conventional_commit_re: Regex::new(
                 r"^(feat|fix|docs|style|refactor|perf|test|chore|build|ci)(\([^)]+\))?: (.+)$",
             )
             .unwrap(),
+            enrichers: Vec::new(),
+            config: PatternExtractor::DEFAULT_CONFIG,
+            author_filter: AuthorFilter::default(),
         };
 
         let (typ, desc) = extractor.parse_commit_message("feat(auth): add JWT validation");
@@ -316,11 +956,33 @@ mod tests {
         assert_eq!(desc, "resolve memory leak");
     }
 
+    #[test]
+    fn test_author_filter() {
+        let filter = AuthorFilter::new(&[
+            "*@mycompany.com".to_string(),
+            "!bot@*".to_string(),
+        ]);
+        assert!(filter.allows("alice@mycompany.com"));
+        assert!(filter.allows("Alice@MyCompany.COM"));
+        assert!(!filter.allows("bot@mycompany.com"));
+        assert!(!filter.allows("alice@example.com"));
+        assert!(!filter.allows(""));
+
+        let exclude_only = AuthorFilter::new(&["!ci-bot@*".to_string()]);
+        assert!(!exclude_only.allows("CI-Bot@example.com"));
+        assert!(exclude_only.allows("alice@example.com"));
+        assert!(AuthorFilter::default().allows(""));
+    }
+
     #[test]
     fn test_automated_commit_detection() {
         let extractor = PatternExtractor {
+            // SAFETY: see `test_parse_conventional_commit`.
             repo: unsafe { std::mem::zeroed() },
             conventional_commit_re: Regex::new("").unwrap(),
+            enrichers: Vec::new(),
+            config: PatternExtractor::DEFAULT_CONFIG,
+            author_filter: AuthorFilter::default(),
         };
 
         assert!(extractor.is_automated_commit("Merge pull request #123"));
@@ -328,11 +990,57 @@ mod tests {
         assert!(!extractor.is_automated_commit("feat: add new feature"));
     }
 
+    #[test]
+    fn test_content_hash_ignores_commit_and_path_order() {
+        let pattern = Pattern {
+            id: "a".to_string(),
+            description: "add JWT validation".to_string(),
+            file_paths: vec!["src/b.rs".to_string(), "src/a.rs".to_string()],
+            commit_sha: "aaa111".to_string(),
+            timestamp: 1,
+            tags: Vec::new(),
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        };
+        let copy = Pattern {
+            id: "b".to_string(),
+            file_paths: vec!["src/a.rs".to_string(), "src/b.rs".to_string()],
+            commit_sha: "bbb222".to_string(),
+            ..pattern.clone()
+        };
+        let changed = Pattern {
+            description: "add JWT refresh".to_string(),
+            ..pattern.clone()
+        };
+
+        assert_eq!(pattern.compute_content_hash(), copy.compute_content_hash());
+        assert_ne!(pattern.compute_content_hash(), changed.compute_content_hash());
+        assert_eq!(pattern.compute_content_hash().len(), 64);
+    }
+
+    #[test]
+    fn test_is_generated_file() {
+        assert!(is_generated_file("proto/src/user.pb.rs"));
+        assert!(is_generated_file("src/schema_generated.rs"));
+        assert!(is_generated_file("Cargo.lock"));
+        assert!(is_generated_file("web/package-lock.json"));
+        assert!(!is_generated_file("src/main.rs"));
+        assert!(!is_generated_file("docs/Cargo.lock.md"));
+    }
+
     #[test]
     fn test_language_tag_extraction() {
         let extractor = PatternExtractor {
+            // SAFETY: see `test_parse_conventional_commit`.
             repo: unsafe { std::mem::zeroed() },
             conventional_commit_re: Regex::new("").unwrap(),
+            enrichers: Vec::new(),
+            config: PatternExtractor::DEFAULT_CONFIG,
+            author_filter: AuthorFilter::default(),
         };
 
         let paths = vec![
@@ -347,4 +1055,54 @@ mod tests {
         assert!(tags.contains(&"typescript".to_string()));
         assert!(tags.contains(&"react".to_string()));
     }
+
+    #[test]
+    fn test_semantic_tag_extraction() {
+        let tags = extract_semantic_tags_from_description(
+            "Add API caching middleware; update Database migration (API v2)",
+        );
+        assert_eq!(tags, vec!["api", "caching", "middleware", "database", "migration"]);
+
+        assert!(extract_semantic_tags_from_description("bump version").is_empty());
+    }
+
+    #[test]
+    fn test_parse_co_authors() {
+        let message = "feat(auth): add SSO login\n\n\
+            Implements the SAML flow.\n\n\
+            Co-authored-by: Jane Doe <Jane@Example.com>\n\
+            co-authored-by: John Smith <john@example.com>\n\
+            Co-authored-by: Jane Doe <jane@example.com>\n\
+            Co-authored-by: No Email\n";
+
+        assert_eq!(
+            parse_co_authors(message),
+            vec!["jane@example.com", "john@example.com"]
+        );
+        assert!(parse_co_authors("fix: typo").is_empty());
+    }
+
+    #[test]
+    fn test_extract_pr_metadata() {
+        let message = "feat(auth): add SSO login (#1234)\n\n\
+            Adds SAML support\n\
+            for enterprise tenants.\n\n\
+            Labels: Feature, security\n\
+            labels: security\n";
+
+        let metadata = PatternExtractor::extract_pr_metadata(message).unwrap();
+        assert_eq!(metadata.number, Some(1234));
+        assert_eq!(metadata.labels, vec!["feature", "security"]);
+        assert_eq!(
+            metadata.description_summary,
+            "Adds SAML support for enterprise tenants."
+        );
+
+        let metadata = PatternExtractor::extract_pr_metadata("fix: typo (#7)").unwrap();
+        assert_eq!(metadata.number, Some(7));
+        assert!(metadata.labels.is_empty());
+
+        assert!(PatternExtractor::extract_pr_metadata("fix: typo").is_none());
+        assert!(PatternExtractor::extract_pr_metadata("fix: typo (#abc)").is_none());
+    }
 }
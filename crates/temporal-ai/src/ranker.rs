@@ -3,17 +3,23 @@
 //! final, context-aware recommendations.
 
 use crate::pattern_extractor::Pattern;
-use crate::similarity::SimilarityResult;
+use crate::similarity::{cosine_similarity, SimilarityResult};
 use crate::vector_store::VectorStore;
 use crate::Result;
 use chrono::Utc;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
 
 /// Represents a final, scored, and explained recommendation.
 ///
 /// This struct is the output of the `RecommendationRanker`. It contains the original
 /// `Pattern`, the individual scores that contributed to its ranking, and a
 /// human-readable explanation of why it was recommended.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "http", derive(utoipa::ToSchema))]
 pub struct Recommendation {
     /// The underlying development pattern being recommended.
     pub pattern: Pattern,
@@ -31,6 +37,57 @@ pub struct Recommendation {
     pub explanation: String,
 }
 
+impl PartialEq for Recommendation {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Recommendation {}
+
+impl PartialOrd for Recommendation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Recommendation {
+    /// The comparison is reversed to make `BinaryHeap` a min-heap of final scores,
+    /// mirroring `SimilarityResult`: the lowest-scoring recommendation is popped
+    /// first when the heap is full. Scores are compared with `f32::total_cmp`,
+    /// so a NaN score still has a consistent place in the order.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.final_score.total_cmp(&self.final_score)
+    }
+}
+
+/// Relative weights of the ranker's score components.
+///
+/// Produced by [`RecommendationRanker::calibrate_weights_from_feedback`] and
+/// applied with [`RecommendationRanker::from_weights`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RankerWeights {
+    /// Weight of the cosine similarity score.
+    pub similarity: f32,
+    /// Weight of the recency score.
+    pub recency: f32,
+    /// Weight of the usage score.
+    pub usage: f32,
+    /// Weight of the success rate score.
+    pub success_rate: f32,
+}
+
+impl Default for RankerWeights {
+    /// The weights used by [`RecommendationRanker::new`].
+    fn default() -> Self {
+        Self {
+            similarity: 0.35,
+            recency: 0.2,
+            usage: 0.3,
+            success_rate: 0.15,
+        }
+    }
+}
+
 /// A multi-factor scoring engine for ranking similarity search results.
 ///
 /// The `RecommendationRanker` takes the initial list of semantically similar
@@ -56,15 +113,86 @@ impl<'a> RecommendationRanker<'a> {
     /// * `store` - A reference to the `VectorStore`, which is needed to retrieve
     ///   performance metrics for the patterns.
     pub fn new(store: &'a VectorStore) -> Self {
+        let weights = RankerWeights::default();
         Self {
             store,
-            recency_weight: 0.2,
-            usage_weight: 0.3,
-            similarity_weight: 0.35,
-            success_rate_weight: 0.15,
+            recency_weight: weights.recency,
+            usage_weight: weights.usage,
+            similarity_weight: weights.similarity,
+            success_rate_weight: weights.success_rate,
         }
     }
 
+    /// Creates a new `RecommendationRanker` from a set of [`RankerWeights`].
+    ///
+    /// Equivalent to [`RecommendationRanker::with_weights`] with the individual
+    /// weights, so they are normalized the same way.
+    pub fn from_weights(store: &'a VectorStore, weights: RankerWeights) -> Self {
+        Self::with_weights(
+            store,
+            weights.recency,
+            weights.usage,
+            weights.similarity,
+            weights.success_rate,
+        )
+    }
+
+    /// Derives ranking weights from the feedback stored with each pattern's metrics.
+    ///
+    /// For every pattern with at least one feedback score, the four score
+    /// components are computed as in ranking, using `avg_relevance_score` as the
+    /// similarity component. Each component's weight is its Pearson correlation
+    /// with `avg_feedback()`, with negative correlations clamped to zero, and the
+    /// weights are normalized to sum to 1.0. If fewer than two patterns have
+    /// feedback, or no component correlates positively, the default weights are
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern or its metrics cannot be read.
+    pub fn calibrate_weights_from_feedback(store: &VectorStore) -> Result<RankerWeights> {
+        let now = Utc::now().timestamp();
+        let mut components: [Vec<f32>; 4] = Default::default();
+        let mut feedback = Vec::new();
+
+        for pattern_id in store.list_patterns()? {
+            let Some(metrics) = store.get_metrics(&pattern_id)? else {
+                continue;
+            };
+            if metrics.feedback_scores.is_empty() {
+                continue;
+            }
+            let Some(pattern) = store.get_pattern(&pattern_id)? else {
+                continue;
+            };
+
+            let (recency, _) = recency_score(pattern.timestamp, now);
+            components[0].push(metrics.avg_relevance_score);
+            components[1].push(recency);
+            components[2].push(usage_score(metrics.usage_count));
+            components[3].push(metrics.success_rate.unwrap_or(0.5));
+            feedback.push(metrics.avg_feedback());
+        }
+
+        if feedback.len() < 2 {
+            return Ok(RankerWeights::default());
+        }
+
+        let [similarity, recency, usage, success_rate] =
+            components.map(|values| pearson_correlation(&values, &feedback).max(0.0));
+        let total = similarity + recency + usage + success_rate;
+        if total <= f32::EPSILON {
+            return Ok(RankerWeights::default());
+        }
+
+        Ok(RankerWeights {
+            similarity: similarity / total,
+            recency: recency / total,
+            usage: usage / total,
+            success_rate: success_rate / total,
+        })
+    }
+
     /// Creates a new `RecommendationRanker` with custom, user-defined weights.
     ///
     /// The provided weights will be normalized to ensure they sum to 1.0.
@@ -118,62 +246,144 @@ impl<'a> RecommendationRanker<'a> {
     /// # Returns
     ///
     /// A `Result` containing a `Vec<Recommendation>` sorted by `final_score`.
+    ///
+    /// # Errors
+    ///
+    /// Metrics lookups that fail are treated as missing, so this currently always
+    /// returns `Ok`.
     pub fn rank(&self, results: Vec<SimilarityResult>) -> Result<Vec<Recommendation>> {
+        let k = results.len();
+        self.rank_top_k(results, k)
+    }
+
+    /// Ranks `results` and keeps only the `k` highest-scoring recommendations.
+    ///
+    /// Recommendations are maintained in a bounded `BinaryHeap`, so memory use is
+    /// O(k) regardless of how many candidates are passed in.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing at most `k` recommendations sorted by `final_score`.
+    ///
+    /// # Errors
+    ///
+    /// See [`RecommendationRanker::rank`].
+    #[tracing::instrument(
+        name = "ranker.rank",
+        skip_all,
+        fields(candidate_count = results.len(), result_count = tracing::field::Empty)
+    )]
+    pub fn rank_top_k(
+        &self,
+        results: Vec<SimilarityResult>,
+        k: usize,
+    ) -> Result<Vec<Recommendation>> {
         let now = Utc::now().timestamp();
 
-        let mut recommendations: Vec<Recommendation> = results
-            .into_iter()
-            .map(|result| {
-                // Calculate recency score using an exponential decay function.
-                let days_since = ((now - result.pattern.timestamp) as f32 / 86400.0).max(0.0);
-                let recency_score = (-0.01 * days_since).exp();
-
-                let (usage_score, usage_count, success_rate_score) = self
-                    .store
-                    .get_metrics(&result.pattern.id)
-                    .ok()
-                    .flatten()
-                    .map_or((0.0, 0, 0.5), |m| {
-                        (
-                            (m.usage_count as f32 / 100.0).min(1.0), // Normalize usage
-                            m.usage_count,
-                            m.success_rate.unwrap_or(0.5), // Default to neutral
-                        )
-                    });
-
-                let final_score = self.similarity_weight * result.score
-                    + self.recency_weight * recency_score
-                    + self.usage_weight * usage_score
-                    + self.success_rate_weight * success_rate_score;
-
-                let explanation = self.generate_explanation(
-                    &result.pattern,
-                    result.score,
-                    days_since.round() as i64,
-                    usage_count,
-                    success_rate_score,
-                );
-
-                Recommendation {
-                    pattern: result.pattern,
-                    similarity_score: result.score,
-                    recency_score,
-                    usage_score,
-                    final_score,
-                    explanation,
-                }
-            })
-            .collect();
+        let mut heap = BinaryHeap::with_capacity(k.saturating_add(1).min(results.len()));
+        for result in results {
+            heap.push(self.score(result, now));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // The reversed `Ord` makes ascending order descending by `final_score`.
+        let recommendations = heap.into_sorted_vec();
+
+        tracing::Span::current().record("result_count", recommendations.len());
+        Ok(recommendations)
+    }
+
+    /// Ranks `results`, giving up on the second half if time is running out.
+    ///
+    /// The first half of `results` is always scored. If by then more than 80% of
+    /// `budget` has elapsed, a warning is logged and the remaining results are
+    /// dropped; otherwise the rest are scored as in [`RecommendationRanker::rank`].
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the scored recommendations sorted by `final_score`.
+    ///
+    /// # Errors
+    ///
+    /// See [`RecommendationRanker::rank`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn rank_with_time_budget(
+        &self,
+        results: Vec<SimilarityResult>,
+        budget: Duration,
+    ) -> Result<Vec<Recommendation>> {
+        let start = Instant::now();
+        let now = Utc::now().timestamp();
+        let total = results.len();
+        let half = total.div_ceil(2);
+
+        let mut recommendations = Vec::with_capacity(total);
+        let mut results = results.into_iter();
+        for result in results.by_ref().take(half) {
+            recommendations.push(self.score(result, now));
+        }
 
-        recommendations.sort_by(|a, b| {
-            b.final_score
-                .partial_cmp(&a.final_score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        let elapsed = start.elapsed();
+        if elapsed > budget.mul_f32(0.8) {
+            tracing::warn!(
+                budget_ms = budget.as_millis() as u64,
+                elapsed_ms = elapsed.as_millis() as u64,
+                ranked = recommendations.len(),
+                skipped = total - recommendations.len(),
+                "ranking time budget exceeded; returning partial results"
+            );
+        } else {
+            recommendations.extend(results.map(|result| self.score(result, now)));
+        }
 
+        // The reversed `Ord` makes ascending order descending by `final_score`.
+        recommendations.sort();
         Ok(recommendations)
     }
 
+    /// Computes the weighted score and explanation for a single search result.
+    fn score(&self, result: SimilarityResult, now: i64) -> Recommendation {
+        let (recency_score, days_since) = recency_score(result.pattern.timestamp, now);
+
+        let (usage_score, usage_count, success_rate_score) = self
+            .store
+            .get_metrics(&result.pattern.id)
+            .ok()
+            .flatten()
+            .map_or((0.0, 0, 0.5), |m| {
+                (
+                    usage_score(m.usage_count),
+                    m.usage_count,
+                    m.success_rate.unwrap_or(0.5), // Default to neutral
+                )
+            });
+
+        let final_score = self.similarity_weight * result.score
+            + self.recency_weight * recency_score
+            + self.usage_weight * usage_score
+            + self.success_rate_weight * success_rate_score
+            - change_size_penalty(&result.pattern);
+
+        let explanation = self.generate_explanation(
+            &result.pattern,
+            result.score,
+            days_since.round() as i64,
+            usage_count,
+            success_rate_score,
+        );
+
+        Recommendation {
+            pattern: result.pattern,
+            similarity_score: result.score,
+            recency_score,
+            usage_score,
+            final_score,
+            explanation,
+        }
+    }
+
     /// Generates a human-readable explanation for a recommendation.
     fn generate_explanation(
         &self,
@@ -187,7 +397,7 @@ impl<'a> RecommendationRanker<'a> {
         let day_label = if days_ago == 1 { "day" } else { "days" };
 
         format!(
-            "Pattern from {} ({}): {} - Similarity: {:.1}%, Recency: {} {} ago, Usage: {} times, Success: {:.1}%",
+            "Pattern from {} ({}): {} - Similarity: {:.1}%, Recency: {} {} ago, Usage: {} times, Success: {:.1}%, Size: +{}/-{} lines",
             commit_short,
             pattern.tags.join(", "),
             pattern.description,
@@ -195,11 +405,117 @@ impl<'a> RecommendationRanker<'a> {
             days_ago,
             day_label,
             usage_count,
-            success_rate * 100.0
+            success_rate * 100.0,
+            pattern.lines_added,
+            pattern.lines_deleted
         )
     }
 }
 
+/// A ranked list of recommendations, with metrics for judging its quality.
+#[derive(Debug, Clone, Default)]
+pub struct RecommendationSet(pub Vec<Recommendation>);
+
+impl RecommendationSet {
+    /// Average pairwise cosine distance (`1.0 - cosine_similarity`) of the
+    /// recommended patterns' embeddings.
+    ///
+    /// Returns `0.0` for fewer than two recommendations and `1.0` when every
+    /// pair is orthogonal. Patterns without a stored embedding are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an embedding cannot be read from `store`.
+    pub fn intra_list_diversity(&self, store: &VectorStore) -> Result<f32> {
+        let mut embeddings = Vec::with_capacity(self.0.len());
+        for recommendation in &self.0 {
+            if let Some(embedding) = store.get_embedding(&recommendation.pattern.id)? {
+                embeddings.push(embedding);
+            }
+        }
+
+        let mut total_distance = 0.0;
+        let mut pairs = 0;
+        for (i, a) in embeddings.iter().enumerate() {
+            for b in &embeddings[i + 1..] {
+                total_distance += 1.0 - cosine_similarity(a, b);
+                pairs += 1;
+            }
+        }
+
+        if pairs == 0 {
+            return Ok(0.0);
+        }
+        Ok(total_distance / pairs as f32)
+    }
+
+    /// Fraction of `all_tags` carried by at least one recommended pattern.
+    ///
+    /// Returns `0.0` if `all_tags` is empty.
+    pub fn coverage_score(&self, all_tags: &HashSet<String>) -> f32 {
+        if all_tags.is_empty() {
+            return 0.0;
+        }
+        let covered: HashSet<&String> = self
+            .0
+            .iter()
+            .flat_map(|recommendation| &recommendation.pattern.tags)
+            .filter(|tag| all_tags.contains(*tag))
+            .collect();
+        covered.len() as f32 / all_tags.len() as f32
+    }
+}
+
+/// Scores the age of a pattern with exponential decay, returning the score and
+/// the age in days.
+fn recency_score(timestamp: i64, now: i64) -> (f32, f32) {
+    let days_since = ((now - timestamp) as f32 / 86400.0).max(0.0);
+    ((-0.01 * days_since).exp(), days_since)
+}
+
+/// Changes touching more lines than this are penalized by [`change_size_penalty`].
+const LARGE_CHANGE_LINES: u32 = 500;
+
+/// Subtracted from the final score of patterns larger than [`LARGE_CHANGE_LINES`].
+const LARGE_CHANGE_PENALTY: f32 = 0.05;
+
+/// A small penalty for very large changes, which make poor examples to follow.
+fn change_size_penalty(pattern: &Pattern) -> f32 {
+    if pattern.lines_added.saturating_add(pattern.lines_deleted) > LARGE_CHANGE_LINES {
+        LARGE_CHANGE_PENALTY
+    } else {
+        0.0
+    }
+}
+
+/// Normalizes a usage count to `[0.0, 1.0]`, saturating at 100 uses.
+fn usage_score(usage_count: u64) -> f32 {
+    (usage_count as f32 / 100.0).min(1.0)
+}
+
+/// Pearson correlation coefficient of `xs` and `ys`; `0.0` if either is constant.
+fn pearson_correlation(xs: &[f32], ys: &[f32]) -> f32 {
+    let n = xs.len().min(ys.len());
+    if n == 0 {
+        return 0.0;
+    }
+    let mean_x = xs[..n].iter().sum::<f32>() / n as f32;
+    let mean_y = ys[..n].iter().sum::<f32>() / n as f32;
+
+    let (mut covariance, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        covariance += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    if var_x <= f32::EPSILON || var_y <= f32::EPSILON {
+        return 0.0;
+    }
+    covariance / (var_x.sqrt() * var_y.sqrt())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,6 +531,12 @@ mod tests {
             commit_sha: format!("abcdef{}", id),
             timestamp,
             tags: vec!["rust".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
         }
     }
 
@@ -268,6 +590,151 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_rank_top_k_keeps_highest_scores() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        let now = Utc::now().timestamp();
+
+        let results: Vec<_> = (0..10)
+            .map(|i| {
+                let pattern = create_test_pattern(&i.to_string(), now);
+                create_similarity_result(pattern, i as f32 / 10.0)
+            })
+            .collect();
+
+        let ranker = RecommendationRanker::new(&store);
+        let top = ranker.rank_top_k(results.clone(), 3)?;
+        let ids: Vec<_> = top.iter().map(|rec| rec.pattern.id.as_str()).collect();
+        assert_eq!(ids, vec!["9", "8", "7"]);
+
+        let all = ranker.rank(results)?;
+        assert_eq!(all.len(), 10);
+        assert!(all.windows(2).all(|pair| pair[0].final_score >= pair[1].final_score));
+        assert!(ranker.rank_top_k(Vec::new(), 3)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rank_with_time_budget_stops_early() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        let now = Utc::now().timestamp();
+
+        let results: Vec<_> = (0..10)
+            .map(|i| {
+                let pattern = create_test_pattern(&i.to_string(), now);
+                create_similarity_result(pattern, i as f32 / 10.0)
+            })
+            .collect();
+
+        let ranker = RecommendationRanker::new(&store);
+        let partial = ranker.rank_with_time_budget(results.clone(), Duration::ZERO)?;
+        let ids: Vec<_> = partial.iter().map(|rec| rec.pattern.id.as_str()).collect();
+        assert_eq!(ids, vec!["4", "3", "2", "1", "0"]);
+
+        let full = ranker.rank_with_time_budget(results, Duration::from_secs(60))?;
+        assert_eq!(full.len(), 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calibrate_weights_from_feedback() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        let now = Utc::now().timestamp();
+
+        assert_eq!(
+            RecommendationRanker::calibrate_weights_from_feedback(&store)?,
+            RankerWeights::default()
+        );
+
+        // Same age, usage and success rate; only similarity tracks feedback.
+        let samples = [(0.1, -1), (0.3, -1), (0.5, 0), (0.7, 1), (0.9, 1)];
+        for (i, (relevance, feedback)) in samples.into_iter().enumerate() {
+            let pattern = create_test_pattern(&i.to_string(), now);
+            store.insert(&pattern, vec![0.5; 768])?;
+            let mut metrics = PerformanceMetrics::new();
+            metrics.record_recommendation(relevance, Some(feedback));
+            store.update_metrics(&pattern.id, metrics)?;
+        }
+
+        let weights = RecommendationRanker::calibrate_weights_from_feedback(&store)?;
+        assert!((weights.similarity - 1.0).abs() < 1e-6);
+        assert_eq!(weights.recency, 0.0);
+        assert_eq!(weights.usage, 0.0);
+        assert_eq!(weights.success_rate, 0.0);
+
+        let ranker = RecommendationRanker::from_weights(&store, weights);
+        assert!((ranker.similarity_weight - 1.0).abs() < 1e-6);
+
+        Ok(())
+    }
+
+    fn create_recommendation(pattern: Pattern) -> Recommendation {
+        Recommendation {
+            pattern,
+            similarity_score: 0.0,
+            recency_score: 0.0,
+            usage_score: 0.0,
+            final_score: 0.0,
+            explanation: String::new(),
+        }
+    }
+
+    fn unit_vector(axis: usize) -> Vec<f32> {
+        let mut vector = vec![0.0; crate::EMBEDDING_DIM];
+        vector[axis] = 1.0;
+        vector
+    }
+
+    #[test]
+    fn test_intra_list_diversity() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let patterns: Vec<Pattern> = (0..3)
+            .map(|i| create_test_pattern(&i.to_string(), Utc::now().timestamp()))
+            .collect();
+        store.insert(&patterns[0], unit_vector(0))?;
+        store.insert(&patterns[1], unit_vector(1))?;
+        store.insert(&patterns[2], unit_vector(0))?;
+
+        let set = |ids: &[usize]| {
+            RecommendationSet(
+                ids.iter()
+                    .map(|&i| create_recommendation(patterns[i].clone()))
+                    .collect(),
+            )
+        };
+
+        assert_eq!(set(&[]).intra_list_diversity(&store)?, 0.0);
+        assert_eq!(set(&[0]).intra_list_diversity(&store)?, 0.0);
+        assert!((set(&[0, 1]).intra_list_diversity(&store)? - 1.0).abs() < 1e-6);
+        assert!(set(&[0, 2]).intra_list_diversity(&store)?.abs() < 1e-6);
+        // Pairs (0,1) and (1,2) are orthogonal, (0,2) identical.
+        assert!((set(&[0, 1, 2]).intra_list_diversity(&store)? - 2.0 / 3.0).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_coverage_score() {
+        let mut auth = create_test_pattern("1", 0);
+        auth.tags = vec!["rust".to_string(), "auth".to_string()];
+        let mut cache = create_test_pattern("2", 0);
+        cache.tags = vec!["rust".to_string(), "cache".to_string()];
+        let set = RecommendationSet(vec![create_recommendation(auth), create_recommendation(cache)]);
+
+        let universe: HashSet<String> = ["rust", "auth", "cache", "database"]
+            .iter()
+            .map(|tag| tag.to_string())
+            .collect();
+        assert_eq!(set.coverage_score(&universe), 0.75);
+        assert_eq!(set.coverage_score(&HashSet::new()), 0.0);
+    }
+
     #[test]
     fn test_custom_weights() -> Result<()> {
         let dir = tempdir()?;
@@ -302,6 +769,37 @@ mod tests {
         assert!(explanation.contains("1 day ago"));
         assert!(explanation.contains("42 times"));
         assert!(explanation.contains("95.0%")); // Success rate
+        assert!(explanation.contains("+0/-0 lines"));
+    }
+
+    #[test]
+    fn test_large_changes_are_penalized() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        let ranker = RecommendationRanker::new(&store);
+        let now = Utc::now().timestamp();
+
+        let small = Pattern {
+            lines_added: 40,
+            lines_deleted: 10,
+            ..create_test_pattern("small", now)
+        };
+        let large = Pattern {
+            lines_added: 1800,
+            lines_deleted: 200,
+            ..create_test_pattern("large", now)
+        };
+        let recommendations = ranker.rank(vec![
+            create_similarity_result(large, 0.9),
+            create_similarity_result(small, 0.9),
+        ])?;
+
+        assert_eq!(recommendations[0].pattern.id, "small");
+        let gap = recommendations[0].final_score - recommendations[1].final_score;
+        assert!((gap - LARGE_CHANGE_PENALTY).abs() < 1e-6);
+        assert!(recommendations[1].explanation.contains("+1800/-200 lines"));
+
+        Ok(())
     }
 
     #[test]
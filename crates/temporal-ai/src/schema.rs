@@ -6,7 +6,11 @@
 //! stored in these tables. This module is the single source of truth for the
 //! on-disk data layout.
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::observability_aggregator::PatternMetrics;
+use crate::{Result, TemporalAIError};
 use chrono::Utc;
+use half::f16;
 use redb::TableDefinition;
 use serde::{Deserialize, Serialize};
 
@@ -15,8 +19,9 @@ use serde::{Deserialize, Serialize};
 /// Defines the main table for storing embedding vectors.
 ///
 /// - **Key**: The pattern ID (a SHA-256 hex string).
-/// - **Value**: A MessagePack-encoded `EmbeddingRecord`, which contains the
-///   high-dimensional vector and its precomputed L2 norm.
+/// - **Value**: An `EmbeddingRecord` encoded with [`EmbeddingRecord::encode`],
+///   either as MessagePack (`StorageMode::Float32`) or in the compact
+///   half-precision layout (`StorageMode::Float16`).
 pub const EMBEDDINGS: TableDefinition<&str, &[u8]> = TableDefinition::new("embeddings_v1");
 
 /// Defines the table for storing the detailed metadata of each pattern.
@@ -43,9 +48,93 @@ pub const FILE_PATH_INDEX: TableDefinition<&str, &str> = TableDefinition::new("f
 /// - **Value**: A JSON-encoded array of pattern IDs.
 pub const TAG_INDEX: TableDefinition<&str, &str> = TableDefinition::new("tag_idx_v1");
 
+/// An index to allow for efficient lookups of patterns by the commit they came from.
+///
+/// - **Key**: A full commit SHA.
+/// - **Value**: A JSON-encoded array of pattern IDs.
+pub const COMMIT_SHA_INDEX: TableDefinition<&str, &str> =
+    TableDefinition::new("commit_sha_idx_v1");
+
+/// An index to allow for efficient lookups of patterns by co-author.
+///
+/// - **Key**: A lowercased co-author email address.
+/// - **Value**: A JSON-encoded array of pattern IDs.
+pub const CO_AUTHOR_INDEX: TableDefinition<&str, &str> =
+    TableDefinition::new("co_author_idx_v1");
+
+/// Expiry times set with `VectorStore::set_ttl`, ordered for range scans.
+///
+/// - **Key**: The Unix timestamp (seconds) at which the patterns expire.
+/// - **Value**: A JSON-encoded array of pattern IDs.
+pub const TTL_INDEX: TableDefinition<i64, &str> = TableDefinition::new("ttl_idx_v1");
+
+/// Maps a pattern's content hash to the first pattern stored with it.
+///
+/// - **Key**: A `Pattern::content_hash` (hex SHA-256).
+/// - **Value**: The ID of the pattern that owns the hash.
+pub const CONTENT_HASH_INDEX: TableDefinition<&str, &str> =
+    TableDefinition::new("content_hash_idx_v1");
+
+/// Progress of `temporal-ai refresh`, so later runs only index new commits.
+///
+/// - **Key**: Always [`REFRESH_STATE_KEY`].
+/// - **Value**: A JSON-encoded `RefreshState` struct.
+pub const REFRESH_STATE: TableDefinition<&str, &str> = TableDefinition::new("refresh_state_v1");
+
+/// The single key used in the `REFRESH_STATE` table.
+pub const REFRESH_STATE_KEY: &str = "state";
+
+/// Store-wide settings that do not belong to any one pattern.
+///
+/// - **Key**: A setting name, e.g. [`SCHEMA_VERSION_KEY`].
+/// - **Value**: The setting's value as a string.
+pub const STORE_INFO: TableDefinition<&str, &str> = TableDefinition::new("store_info_v1");
+
+/// The `STORE_INFO` key holding the schema version of the stored records, in decimal.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
 
 // --- Data Structures ---
 
+/// Selects how an embedding vector is encoded in the `EMBEDDINGS` table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageMode {
+    /// Full-precision MessagePack encoding (~3 KB per 768-dim vector).
+    #[default]
+    Float32,
+    /// IEEE 754 half-precision components (~1.5 KB per 768-dim vector), with a
+    /// maximum per-component error of about 5e-4 for unit-normalized vectors.
+    Float16,
+}
+
+/// Selects the key under which [`crate::VectorStore`] stores a pattern.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdStrategy {
+    /// Keep `Pattern.id`, the SHA-256 of commit and description.
+    #[default]
+    Sha256,
+    /// Store patterns under [`crate::Pattern::ulid_id`], so keys sort by commit time.
+    Ulid,
+}
+
+impl StorageMode {
+    /// Returns the mode an `EMBEDDINGS` value was encoded with.
+    pub(crate) fn of_encoded(bytes: &[u8]) -> Self {
+        if bytes.starts_with(FLOAT16_MAGIC) {
+            Self::Float16
+        } else {
+            Self::Float32
+        }
+    }
+}
+
+/// Prefix that marks a `StorageMode::Float16` value. MessagePack-encoded
+/// records always start with an array marker, so the two layouts cannot collide.
+const FLOAT16_MAGIC: &[u8; 4] = b"F16\0";
+
+/// Length of the `StorageMode::Float16` header: magic, version, norm, created_at.
+const FLOAT16_HEADER_LEN: usize = FLOAT16_MAGIC.len() + 1 + 4 + 8;
+
 /// Represents the data structure that is stored in the `EMBEDDINGS` table.
 ///
 /// This struct holds the core vector data for a pattern, along with metadata
@@ -99,6 +188,93 @@ impl EmbeddingRecord {
         }
     }
 
+    /// Converts the vector to IEEE 754 half-precision, returned as little-endian bytes.
+    ///
+    /// The result is half the size of the `f32` vector, which makes it suitable for
+    /// network transfer. The norm is not included; pass it to
+    /// [`EmbeddingRecord::from_float16_bytes`] alongside the bytes.
+    pub fn to_float16_bytes(&self) -> Vec<u8> {
+        self.vector
+            .iter()
+            .flat_map(|&x| f16::from_f32(x).to_le_bytes())
+            .collect()
+    }
+
+    /// Reconstructs a record from bytes produced by [`EmbeddingRecord::to_float16_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::DimensionMismatch` if `bytes` does not hold exactly
+    /// [`crate::EMBEDDING_DIM`] half-precision values.
+    pub fn from_float16_bytes(bytes: &[u8], norm: f32) -> Result<Self> {
+        if bytes.len() != crate::EMBEDDING_DIM * 2 {
+            return Err(TemporalAIError::DimensionMismatch {
+                expected: crate::EMBEDDING_DIM,
+                actual: bytes.len() / 2,
+            });
+        }
+
+        let vector = bytes
+            .chunks_exact(2)
+            .map(|pair| f16::from_le_bytes([pair[0], pair[1]]).to_f32())
+            .collect();
+
+        Ok(Self {
+            vector,
+            norm,
+            created_at: Utc::now().timestamp(),
+            version: crate::SCHEMA_VERSION,
+        })
+    }
+
+    /// Encodes the record for the `EMBEDDINGS` table.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::SerializationError` if MessagePack encoding fails.
+    pub fn encode(&self, mode: StorageMode) -> Result<Vec<u8>> {
+        match mode {
+            StorageMode::Float32 => Ok(rmp_serde::to_vec(self)?),
+            StorageMode::Float16 => {
+                let mut bytes = Vec::with_capacity(FLOAT16_HEADER_LEN + self.vector.len() * 2);
+                bytes.extend_from_slice(FLOAT16_MAGIC);
+                bytes.push(self.version);
+                bytes.extend_from_slice(&self.norm.to_le_bytes());
+                bytes.extend_from_slice(&self.created_at.to_le_bytes());
+                bytes.extend_from_slice(&self.to_float16_bytes());
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Decodes a value from the `EMBEDDINGS` table, detecting its `StorageMode`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::SerializationError` if the bytes are neither a
+    /// MessagePack record nor a well-formed Float16 record.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let Some(rest) = bytes.strip_prefix(FLOAT16_MAGIC) else {
+            return Ok(rmp_serde::from_slice(bytes)?);
+        };
+
+        if bytes.len() < FLOAT16_HEADER_LEN {
+            return Err(TemporalAIError::SerializationError(
+                "Truncated float16 embedding header".to_string(),
+            ));
+        }
+
+        let version = rest[0];
+        let norm = f32::from_le_bytes([rest[1], rest[2], rest[3], rest[4]]);
+        let mut created_at = [0u8; 8];
+        created_at.copy_from_slice(&rest[5..13]);
+
+        let mut record = Self::from_float16_bytes(&bytes[FLOAT16_HEADER_LEN..], norm)?;
+        record.version = version;
+        record.created_at = i64::from_le_bytes(created_at);
+        Ok(record)
+    }
+
     /// Computes the L2 norm of a vector slice.
     fn compute_norm(vec: &[f32]) -> f32 {
         vec.iter().map(|x| x * x).sum::<f32>().sqrt()
@@ -111,6 +287,18 @@ impl EmbeddingRecord {
         }
         self.vector.iter().map(|x| x / self.norm).collect()
     }
+
+    /// Computes the cosine similarity to `other` using both stored norms.
+    ///
+    /// Unlike [`crate::similarity::cosine_similarity`], this only needs a dot
+    /// product per pair. Returns `0.0` if either norm is zero or the vectors have
+    /// different lengths.
+    pub fn cosine_to(&self, other: &EmbeddingRecord) -> f32 {
+        if self.norm == 0.0 || other.norm == 0.0 || self.vector.len() != other.vector.len() {
+            return 0.0;
+        }
+        crate::similarity::dot_product_simd(&self.vector, &other.vector) / (self.norm * other.norm)
+    }
 }
 
 /// A struct for tracking the performance and usage metrics of a pattern.
@@ -153,6 +341,29 @@ impl PerformanceMetrics {
         Self::default()
     }
 
+    /// Creates metrics seeded from observability data.
+    ///
+    /// Equivalent to [`PerformanceMetrics::new`] followed by
+    /// [`PerformanceMetrics::apply_observability_update`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_observability(obs: &PatternMetrics) -> Self {
+        let mut metrics = Self::new();
+        metrics.apply_observability_update(obs);
+        metrics
+    }
+
+    /// Overwrites the observability-derived fields with values from `obs`.
+    ///
+    /// `success_rate`, `avg_latency_ms` and `error_rate` are replaced. Locally
+    /// tracked fields such as `usage_count` and `feedback_scores` are left
+    /// untouched.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn apply_observability_update(&mut self, obs: &PatternMetrics) {
+        self.success_rate = Some(obs.success_rate);
+        self.avg_latency_ms = Some(obs.avg_latency_ms);
+        self.error_rate = Some(obs.error_rate);
+    }
+
     /// Updates the metrics after a pattern has been recommended.
     ///
     /// This method increments the usage count, updates the last recommended time,
@@ -184,11 +395,73 @@ impl PerformanceMetrics {
         let sum: i32 = self.feedback_scores.iter().map(|&x| x as i32).sum();
         sum as f32 / self.feedback_scores.len() as f32
     }
+
+    /// Combines the metrics recorded for the same pattern by two different stores.
+    ///
+    /// - `usage_count` is summed.
+    /// - `avg_relevance_score` is averaged, weighted by each side's `usage_count`.
+    /// - `last_recommended` takes the later of the two timestamps.
+    /// - `feedback_scores` are concatenated and capped at [`MAX_FEEDBACK_SCORES`].
+    /// - The observability fields are averaged over whichever sides are `Some`,
+    ///   and stay `None` only if both are `None`.
+    pub fn merge(&self, other: &PerformanceMetrics) -> PerformanceMetrics {
+        let usage_count = self.usage_count + other.usage_count;
+        let avg_relevance_score = if usage_count == 0 {
+            (self.avg_relevance_score + other.avg_relevance_score) / 2.0
+        } else {
+            (self.avg_relevance_score * self.usage_count as f32
+                + other.avg_relevance_score * other.usage_count as f32)
+                / usage_count as f32
+        };
+
+        let mut feedback_scores = self.feedback_scores.clone();
+        feedback_scores.extend_from_slice(&other.feedback_scores);
+        feedback_scores.truncate(MAX_FEEDBACK_SCORES);
+
+        PerformanceMetrics {
+            usage_count,
+            avg_relevance_score,
+            last_recommended: self.last_recommended.max(other.last_recommended),
+            feedback_scores,
+            success_rate: average_some(self.success_rate, other.success_rate),
+            avg_latency_ms: average_some(self.avg_latency_ms, other.avg_latency_ms),
+            error_rate: average_some(self.error_rate, other.error_rate),
+        }
+    }
+}
+
+/// Where the last `temporal-ai refresh` run stopped.
+///
+/// Stored in the `REFRESH_STATE` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefreshState {
+    /// The `HEAD` commit at the end of the last run; later runs index only
+    /// commits after it.
+    pub last_indexed_sha: String,
+
+    /// The Unix timestamp at which the last run finished.
+    pub last_run_at: i64,
+
+    /// The number of patterns indexed across all runs.
+    pub total_indexed: u64,
+}
+
+/// The maximum number of feedback scores kept by [`PerformanceMetrics::merge`].
+pub const MAX_FEEDBACK_SCORES: usize = 1000;
+
+/// Averages two optional values, ignoring any that are `None`.
+fn average_some(a: Option<f32>, b: Option<f32>) -> Option<f32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some((a + b) / 2.0),
+        (Some(v), None) | (None, Some(v)) => Some(v),
+        (None, None) => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     fn create_test_vector() -> Vec<f32> {
         let mut vec = vec![0.0; crate::EMBEDDING_DIM];
@@ -204,6 +477,22 @@ mod tests {
         assert!((record.norm - 5.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_cosine_to_matches_cosine_similarity() {
+        let a = EmbeddingRecord::new(create_test_vector());
+        let mut vector = vec![0.0; crate::EMBEDDING_DIM];
+        vector[0] = 1.0;
+        vector[2] = 1.0;
+        let b = EmbeddingRecord::new(vector);
+
+        let expected = crate::similarity::cosine_similarity(&a.vector, &b.vector);
+        assert!((a.cosine_to(&b) - expected).abs() < 1e-6);
+        assert!((a.cosine_to(&a) - 1.0).abs() < 1e-6);
+
+        let zero = EmbeddingRecord::new(vec![0.0; crate::EMBEDDING_DIM]);
+        assert_eq!(a.cosine_to(&zero), 0.0);
+    }
+
     #[test]
     fn test_embedding_record_normalized() {
         let vector = create_test_vector();
@@ -227,4 +516,137 @@ mod tests {
 
         assert!((metrics.avg_feedback() - 0.0).abs() < 0.001); // (1 + -1) / 2
     }
+
+    #[test]
+    fn test_performance_metrics_merge() {
+        let a = PerformanceMetrics {
+            usage_count: 3,
+            avg_relevance_score: 0.9,
+            last_recommended: 100,
+            feedback_scores: vec![1, 1],
+            success_rate: Some(0.8),
+            avg_latency_ms: None,
+            error_rate: None,
+        };
+        let b = PerformanceMetrics {
+            usage_count: 1,
+            avg_relevance_score: 0.5,
+            last_recommended: 200,
+            feedback_scores: vec![-1],
+            success_rate: Some(0.6),
+            avg_latency_ms: Some(40.0),
+            error_rate: None,
+        };
+
+        let merged = a.merge(&b);
+        assert_eq!(merged.usage_count, 4);
+        assert!((merged.avg_relevance_score - 0.8).abs() < 0.001); // (0.9 * 3 + 0.5) / 4
+        assert_eq!(merged.last_recommended, 200);
+        assert_eq!(merged.feedback_scores, vec![1, 1, -1]);
+        assert!((merged.success_rate.unwrap() - 0.7).abs() < 0.001);
+        assert_eq!(merged.avg_latency_ms, Some(40.0));
+        assert_eq!(merged.error_rate, None);
+    }
+
+    #[test]
+    fn test_performance_metrics_merge_caps_feedback() {
+        let a = PerformanceMetrics {
+            feedback_scores: vec![1; MAX_FEEDBACK_SCORES],
+            ..Default::default()
+        };
+        let b = PerformanceMetrics {
+            feedback_scores: vec![-1; 10],
+            ..Default::default()
+        };
+
+        let merged = a.merge(&b);
+        assert_eq!(merged.feedback_scores.len(), MAX_FEEDBACK_SCORES);
+        assert!(merged.feedback_scores.iter().all(|&fb| fb == 1));
+    }
+
+    #[test]
+    fn test_storage_modes_round_trip_through_decode() {
+        let record = EmbeddingRecord::new(create_test_vector());
+
+        let full = EmbeddingRecord::decode(&record.encode(StorageMode::Float32).unwrap()).unwrap();
+        assert_eq!(full.vector, record.vector);
+
+        let compact_bytes = record.encode(StorageMode::Float16).unwrap();
+        assert_eq!(compact_bytes.len(), FLOAT16_HEADER_LEN + crate::EMBEDDING_DIM * 2);
+        let compact = EmbeddingRecord::decode(&compact_bytes).unwrap();
+        assert_eq!(compact.vector, record.vector);
+        assert_eq!(compact.norm, record.norm);
+        assert_eq!(compact.created_at, record.created_at);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn create_pattern_metrics() -> PatternMetrics {
+        PatternMetrics {
+            pattern_id: "abc123".to_string(),
+            avg_latency_ms: 45.5,
+            error_rate: 0.25,
+            success_rate: 0.75,
+            recommendation_count: 40,
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_from_observability_sets_observed_fields() {
+        let metrics = PerformanceMetrics::from_observability(&create_pattern_metrics());
+
+        assert_eq!(metrics.success_rate, Some(0.75));
+        assert_eq!(metrics.avg_latency_ms, Some(45.5));
+        assert_eq!(metrics.error_rate, Some(0.25));
+        assert_eq!(metrics.usage_count, 0);
+        assert!(metrics.feedback_scores.is_empty());
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_apply_observability_update_keeps_local_fields() {
+        let mut metrics = PerformanceMetrics::new();
+        metrics.record_recommendation(0.9, Some(1));
+        metrics.record_recommendation(0.7, Some(-1));
+        metrics.success_rate = Some(0.1);
+
+        metrics.apply_observability_update(&create_pattern_metrics());
+
+        assert_eq!(metrics.success_rate, Some(0.75));
+        assert_eq!(metrics.avg_latency_ms, Some(45.5));
+        assert_eq!(metrics.error_rate, Some(0.25));
+        assert_eq!(metrics.usage_count, 2);
+        assert_eq!(metrics.feedback_scores, vec![1, -1]);
+        assert!((metrics.avg_relevance_score - 0.8).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_from_float16_bytes_rejects_wrong_length() {
+        let err = EmbeddingRecord::from_float16_bytes(&[0u8; 10], 1.0).unwrap_err();
+        assert!(matches!(
+            err,
+            TemporalAIError::DimensionMismatch { actual: 5, .. }
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_float16_round_trip_error_is_small(
+            vector in proptest::collection::vec(-1.0f32..1.0, crate::EMBEDDING_DIM)
+        ) {
+            let record = EmbeddingRecord::new(vector);
+            let decoded =
+                EmbeddingRecord::from_float16_bytes(&record.to_float16_bytes(), record.norm)
+                    .unwrap();
+
+            let max_error = record
+                .vector
+                .iter()
+                .zip(&decoded.vector)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0f32, f32::max);
+            prop_assert!(max_error < 0.001, "max error {} too large", max_error);
+            prop_assert_eq!(decoded.norm, record.norm);
+        }
+    }
 }
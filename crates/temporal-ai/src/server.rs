@@ -0,0 +1,258 @@
+//! This module provides a lightweight REST API over a [`VectorStore`], enabled by
+//! the `http` feature and started with `temporal-ai serve --http`.
+//!
+//! The server is built on `axum`, and every request and response type derives
+//! `utoipa::ToSchema` so that the OpenAPI document served at `GET /openapi.json`
+//! is generated from the same definitions the handlers use.
+//!
+//! | Method   | Path                 | Description                                |
+//! | -------- | -------------------- | ------------------------------------------ |
+//! | `POST`   | `/v1/recommend`      | Rank patterns for a query embedding        |
+//! | `GET`    | `/v1/patterns/{id}`  | Fetch a single pattern                     |
+//! | `DELETE` | `/v1/patterns/{id}`  | Remove a pattern                           |
+//! | `GET`    | `/v1/stats`          | Pattern count and database size            |
+//! | `GET`    | `/openapi.json`      | OpenAPI 3 specification for the above      |
+
+use crate::pattern_extractor::Pattern;
+use crate::ranker::{Recommendation, RecommendationRanker};
+use crate::similarity::{SearchFilters, SimilaritySearch};
+use crate::vector_store::VectorStore;
+use crate::{Result, TemporalAIError, EMBEDDING_DIM};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use utoipa::{OpenApi, ToSchema};
+
+/// The OpenAPI document describing the REST API.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "temporal-ai", description = "Pattern recommendation REST API"),
+    paths(recommend, get_pattern, delete_pattern, stats)
+)]
+pub struct ApiDoc;
+
+/// The body of a `POST /v1/recommend` request.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct RecommendRequest {
+    /// The query embedding. Must have [`EMBEDDING_DIM`] components.
+    pub embedding: Vec<f32>,
+    /// The number of recommendations to return. Defaults to 5.
+    #[serde(default = "default_top")]
+    pub top: usize,
+    /// Optional filters applied before ranking.
+    #[serde(default)]
+    pub filters: RecommendFilters,
+}
+
+fn default_top() -> usize {
+    5
+}
+
+/// The subset of [`SearchFilters`] that can be set over HTTP.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct RecommendFilters {
+    /// Only return results with at least this similarity score.
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    /// A glob pattern that at least one affected file path must match.
+    #[serde(default)]
+    pub file_path_glob: Option<String>,
+    /// Tags that all returned patterns must have.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only return patterns committed at or after this Unix timestamp.
+    #[serde(default)]
+    pub since_timestamp: Option<i64>,
+    /// Only return patterns committed at or after this (possibly abbreviated) commit.
+    #[serde(default)]
+    pub since_commit_sha: Option<String>,
+}
+
+impl From<RecommendFilters> for SearchFilters {
+    fn from(filters: RecommendFilters) -> Self {
+        SearchFilters {
+            min_score: filters.min_score,
+            file_path_glob: filters.file_path_glob,
+            tags: filters.tags,
+            since_timestamp: filters.since_timestamp,
+            since_commit_sha: filters.since_commit_sha,
+            ..Default::default()
+        }
+    }
+}
+
+/// The body of a successful `POST /v1/recommend` response.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecommendResponse {
+    /// Recommendations sorted by `final_score`, highest first.
+    pub recommendations: Vec<Recommendation>,
+}
+
+/// The body of a `GET /v1/stats` response.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatsResponse {
+    /// The number of patterns in the store.
+    pub total_patterns: u64,
+    /// The size of the database in bytes.
+    pub size_bytes: u64,
+}
+
+/// The body of every error response.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    /// A human-readable description of the failure.
+    pub error: String,
+}
+
+/// An error returned by a handler, rendered as an [`ErrorResponse`].
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl From<TemporalAIError> for ApiError {
+    fn from(err: TemporalAIError) -> Self {
+        let status = match err {
+            TemporalAIError::PatternNotFound(_) => StatusCode::NOT_FOUND,
+            TemporalAIError::DimensionMismatch { .. } => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            message: err.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(ErrorResponse {
+                error: self.message,
+            }),
+        )
+            .into_response()
+    }
+}
+
+type AppState = Arc<VectorStore>;
+
+/// Builds the `axum` router for the REST API.
+///
+/// The returned router is fully configured and can be served directly or
+/// driven in tests with `tower::ServiceExt::oneshot`.
+pub fn router(store: Arc<VectorStore>) -> Router {
+    Router::new()
+        .route("/v1/recommend", post(recommend))
+        .route("/v1/patterns/:id", get(get_pattern).delete(delete_pattern))
+        .route("/v1/stats", get(stats))
+        .route("/openapi.json", get(openapi))
+        .with_state(store)
+}
+
+/// Serves the REST API on `addr` until the process is terminated.
+///
+/// # Errors
+///
+/// Returns an error if the address cannot be bound or the server fails.
+pub async fn serve(store: Arc<VectorStore>, addr: SocketAddr) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(store)).await?;
+    Ok(())
+}
+
+/// Rank stored patterns against a query embedding.
+#[utoipa::path(
+    post,
+    path = "/v1/recommend",
+    request_body = RecommendRequest,
+    responses(
+        (status = 200, description = "Ranked recommendations", body = RecommendResponse),
+        (status = 400, description = "Embedding has the wrong dimension", body = ErrorResponse)
+    )
+)]
+async fn recommend(
+    State(store): State<AppState>,
+    Json(request): Json<RecommendRequest>,
+) -> std::result::Result<Json<RecommendResponse>, ApiError> {
+    if request.embedding.len() != EMBEDDING_DIM {
+        return Err(TemporalAIError::DimensionMismatch {
+            expected: EMBEDDING_DIM,
+            actual: request.embedding.len(),
+        }
+        .into());
+    }
+
+    let filters = SearchFilters::from(request.filters);
+    let search = SimilaritySearch::new(&store);
+    let results = search.search_filtered(&request.embedding, request.top * 2, &filters)?;
+
+    let ranker = RecommendationRanker::new(&store);
+    let mut recommendations = ranker.rank(results)?;
+    recommendations.truncate(request.top);
+
+    Ok(Json(RecommendResponse { recommendations }))
+}
+
+/// Fetch a single pattern by ID.
+#[utoipa::path(
+    get,
+    path = "/v1/patterns/{id}",
+    params(("id" = String, Path, description = "Pattern ID")),
+    responses(
+        (status = 200, description = "The pattern", body = Pattern),
+        (status = 404, description = "No pattern with this ID", body = ErrorResponse)
+    )
+)]
+async fn get_pattern(
+    State(store): State<AppState>,
+    Path(id): Path<String>,
+) -> std::result::Result<Json<Pattern>, ApiError> {
+    match store.get_pattern(&id)? {
+        Some(pattern) => Ok(Json(pattern)),
+        None => Err(TemporalAIError::PatternNotFound(id).into()),
+    }
+}
+
+/// Remove a pattern by ID.
+#[utoipa::path(
+    delete,
+    path = "/v1/patterns/{id}",
+    params(("id" = String, Path, description = "Pattern ID")),
+    responses(
+        (status = 501, description = "Deletion is not supported by the store yet", body = ErrorResponse)
+    )
+)]
+async fn delete_pattern(Path(id): Path<String>) -> ApiError {
+    // `VectorStore` has no delete operation yet; answer explicitly rather than
+    // pretending the pattern was removed.
+    ApiError {
+        status: StatusCode::NOT_IMPLEMENTED,
+        message: format!("Deleting patterns is not supported yet: {}", id),
+    }
+}
+
+/// Report pattern count and database size.
+#[utoipa::path(
+    get,
+    path = "/v1/stats",
+    responses((status = 200, description = "Store statistics", body = StatsResponse))
+)]
+async fn stats(
+    State(store): State<AppState>,
+) -> std::result::Result<Json<StatsResponse>, ApiError> {
+    Ok(Json(StatsResponse {
+        total_patterns: store.count()?,
+        size_bytes: store.size()?,
+    }))
+}
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
@@ -8,10 +8,13 @@
 //! calculations for improved performance.
 
 use crate::pattern_extractor::Pattern;
+use crate::schema::EmbeddingRecord;
+use crate::synonyms::TagSynonyms;
 use crate::vector_store::VectorStore;
 use crate::{Result, TemporalAIError};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::Arc;
 
 /// Represents a single result from a similarity search.
 ///
@@ -52,23 +55,200 @@ impl Ord for SimilarityResult {
     }
 }
 
+/// How a query embedding is compared with stored embeddings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SimilarityMetric {
+    /// Cosine similarity, which ignores embedding magnitude.
+    #[default]
+    Cosine,
+    /// The raw dot product, which favours embeddings with larger magnitude.
+    DotProduct,
+    /// Euclidean distance `d`, converted to a similarity as `1 / (1 + d)`.
+    L2Distance,
+}
+
+impl SimilarityMetric {
+    /// Scores `embedding` against `query`; higher is more similar.
+    ///
+    /// Vectors of different lengths score `0.0`.
+    pub fn score(self, query: &[f32], embedding: &[f32]) -> f32 {
+        match self {
+            SimilarityMetric::Cosine => cosine_similarity(query, embedding),
+            SimilarityMetric::DotProduct => {
+                if query.len() != embedding.len() {
+                    return 0.0;
+                }
+                dot_product_simd(query, embedding)
+            }
+            SimilarityMetric::L2Distance => 1.0 / (1.0 + l2_distance(query, embedding)),
+        }
+    }
+
+    /// Scores `embedding` against `query`, reusing their stored norms for cosine.
+    pub fn score_records(self, query: &EmbeddingRecord, embedding: &EmbeddingRecord) -> f32 {
+        match self {
+            SimilarityMetric::Cosine => query.cosine_to(embedding),
+            _ => self.score(&query.vector, &embedding.vector),
+        }
+    }
+}
+
+/// How [`SearchFilters::dedup_by_commit`] picks one result per commit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DedupStrategy {
+    /// Keep the result with the highest similarity score.
+    #[default]
+    KeepHighestScore,
+    /// Keep the result whose pattern has the latest timestamp, breaking ties
+    /// by score.
+    KeepMostRecent,
+}
+
+impl DedupStrategy {
+    /// Returns `true` if `candidate` should replace `current` as the representative.
+    fn prefers(self, candidate: &SimilarityResult, current: &SimilarityResult) -> bool {
+        let by_score = candidate.score > current.score || current.score.is_nan();
+        match self {
+            DedupStrategy::KeepHighestScore => by_score,
+            DedupStrategy::KeepMostRecent => {
+                match candidate.pattern.timestamp.cmp(&current.pattern.timestamp) {
+                    Ordering::Greater => true,
+                    Ordering::Less => false,
+                    Ordering::Equal => by_score,
+                }
+            }
+        }
+    }
+}
+
+/// How the tags in [`SearchFilters::tags`] are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TagFilterMode {
+    /// Match patterns carrying at least one of the tags (union).
+    Any,
+    /// Match only patterns carrying every tag (intersection).
+    #[default]
+    All,
+}
+
 /// A set of filters to constrain a similarity search.
 ///
 /// This struct allows for more sophisticated queries by enabling callers to
 /// filter the search space based on criteria like score thresholds, file paths,
 /// tags, and timestamps.
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct SearchFilters {
     /// If set, only results with a score greater than or equal to this value
     /// will be returned.
     pub min_score: Option<f32>,
     /// A glob pattern to filter patterns by their affected file paths.
     pub file_path_glob: Option<String>,
-    /// A list of tags that all returned patterns must have.
+    /// Tags that returned patterns must have, combined as set by `tag_filter_mode`.
     pub tags: Vec<String>,
+    /// Whether a pattern must carry all of `tags` or any one of them. Defaults
+    /// to [`TagFilterMode::All`].
+    pub tag_filter_mode: TagFilterMode,
+    /// Exact file paths that every returned pattern must have touched, e.g.
+    /// both `src/auth/mod.rs` and `tests/auth_test.rs`.
+    pub require_all_file_paths: Vec<String>,
     /// If set, only patterns with a timestamp greater than or equal to this
     /// value will be returned.
     pub since_timestamp: Option<i64>,
+    /// If set, only patterns committed at or after this commit will be returned.
+    /// The SHA may be abbreviated and is resolved to a timestamp via any stored
+    /// pattern from that commit. When `since_timestamp` is also set, the later
+    /// of the two bounds applies.
+    pub since_commit_sha: Option<String>,
+    /// The maximum number of concurrent store reads issued by
+    /// [`SimilaritySearch::search_filtered_async`]. Defaults to 8.
+    pub max_concurrent_reads: usize,
+    /// If `true`, at most one result is returned per `commit_sha`, chosen by
+    /// `dedup_strategy`, so a single large commit cannot fill every top-k slot.
+    pub dedup_by_commit: bool,
+    /// The strategy used when `dedup_by_commit` is set.
+    pub dedup_strategy: DedupStrategy,
+    /// If `true`, each result is followed by the patterns linked to it via
+    /// `VectorStore::link_patterns`, scored against the same query. Related
+    /// patterns are added after top-k selection and are not filtered.
+    pub include_related: bool,
+    /// The metric used to score candidates. Defaults to cosine similarity.
+    pub metric: SimilarityMetric,
+    /// If set, the top-k results are picked by Maximum Marginal Relevance with
+    /// this trade-off between relevance (`1.0`) and diversity (`0.0`). See
+    /// [`SimilaritySearch::search_mmr`].
+    pub lambda: Option<f32>,
+}
+
+impl SearchFilters {
+    /// Returns the number of filters that narrow the candidate set.
+    ///
+    /// `max_concurrent_reads` only tunes execution and is not counted.
+    pub fn active_count(&self) -> usize {
+        usize::from(self.min_score.is_some())
+            + usize::from(self.file_path_glob.is_some())
+            + usize::from(!self.tags.is_empty())
+            + usize::from(!self.require_all_file_paths.is_empty())
+            + usize::from(self.since_timestamp.is_some())
+            + usize::from(self.since_commit_sha.is_some())
+    }
+}
+
+impl Default for SearchFilters {
+    fn default() -> Self {
+        Self {
+            min_score: None,
+            file_path_glob: None,
+            tags: Vec::new(),
+            tag_filter_mode: TagFilterMode::default(),
+            require_all_file_paths: Vec::new(),
+            since_timestamp: None,
+            since_commit_sha: None,
+            max_concurrent_reads: 8,
+            dedup_by_commit: false,
+            dedup_strategy: DedupStrategy::default(),
+            include_related: false,
+            metric: SimilarityMetric::default(),
+            lambda: None,
+        }
+    }
+}
+
+/// Every stored embedding and its pattern metadata, held in memory.
+///
+/// Built by [`SimilaritySearch::warm_cache`] and searched with
+/// [`SimilaritySearch::search_from_cache`], so a batch of queries reads the
+/// store once instead of once per query. The cache is a snapshot: patterns
+/// inserted, removed or expired afterwards are not reflected until it is rebuilt.
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingCache {
+    entries: HashMap<String, (EmbeddingRecord, Pattern)>,
+}
+
+impl EmbeddingCache {
+    /// Returns the number of cached patterns.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no patterns are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the cached embedding record and pattern for `pattern_id`.
+    pub fn get(&self, pattern_id: &str) -> Option<&(EmbeddingRecord, Pattern)> {
+        self.entries.get(pattern_id)
+    }
+
+    /// Timestamp of the lowest-ID pattern whose `commit_sha` starts with `sha`,
+    /// matching [`VectorStore::commit_timestamp`].
+    fn commit_timestamp(&self, sha: &str) -> Option<i64> {
+        self.entries
+            .iter()
+            .filter(|(_, (_, pattern))| pattern.commit_sha.starts_with(sha))
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, (_, pattern))| pattern.timestamp)
+    }
 }
 
 /// The main engine for performing similarity searches.
@@ -77,6 +257,15 @@ pub struct SearchFilters {
 /// provides the methods to execute searches against it.
 pub struct SimilaritySearch<'a> {
     store: &'a VectorStore,
+    synonyms: Option<Arc<TagSynonyms>>,
+    early_exit: Option<EarlyExit>,
+}
+
+/// When [`SimilaritySearch::search_filtered`] may stop scanning early.
+#[derive(Debug, Clone, Copy)]
+struct EarlyExit {
+    min_scan_fraction: f32,
+    window_size: usize,
 }
 
 impl<'a> SimilaritySearch<'a> {
@@ -86,7 +275,40 @@ impl<'a> SimilaritySearch<'a> {
     ///
     /// * `store` - A reference to the `VectorStore` to be searched.
     pub fn new(store: &'a VectorStore) -> Self {
-        Self { store }
+        Self {
+            store,
+            synonyms: None,
+            early_exit: None,
+        }
+    }
+
+    /// Expands each tag in `SearchFilters::tags` to its synonyms before the
+    /// tag index is queried.
+    ///
+    /// A pattern then matches a requested tag if it carries the tag or any of
+    /// its synonyms; multiple requested tags are still combined according to
+    /// `SearchFilters::tag_filter_mode`.
+    pub fn with_synonyms(mut self, synonyms: Arc<TagSynonyms>) -> Self {
+        self.synonyms = Some(synonyms);
+        self
+    }
+
+    /// Lets [`SimilaritySearch::search_filtered`] stop scanning once the top-k
+    /// results are unlikely to change.
+    ///
+    /// After at least `min_scan_fraction` (e.g. `0.1`) of the candidates have
+    /// been scanned, the scan stops as soon as `window_size` consecutive
+    /// candidates have scored below the lowest result kept so far. This is a
+    /// heuristic: a better match later in the scan is missed, so it only pays
+    /// off when patterns are stored roughly in descending order of relevance
+    /// to typical queries. It has no effect with `SearchFilters::dedup_by_commit`,
+    /// and disables the parallel scan of the `rayon` feature.
+    pub fn with_early_exit(mut self, min_scan_fraction: f32, window_size: usize) -> Self {
+        self.early_exit = Some(EarlyExit {
+            min_scan_fraction,
+            window_size: window_size.max(1),
+        });
+        self
     }
 
     /// Finds the top `k` most similar patterns to a query embedding, without filters.
@@ -99,6 +321,10 @@ impl<'a> SimilaritySearch<'a> {
     /// # Returns
     ///
     /// A `Result` containing a `Vec<SimilarityResult>` sorted by score in descending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
     pub fn search(&self, query_embedding: &[f32], k: usize) -> Result<Vec<SimilarityResult>> {
         self.search_filtered(query_embedding, k, &SearchFilters::default())
     }
@@ -107,6 +333,13 @@ impl<'a> SimilaritySearch<'a> {
     ///
     /// This method first narrows down the search space based on the provided filters
     /// and then performs the similarity calculation on the candidate patterns.
+    /// Patterns whose TTL has passed are pruned from the store beforehand (see
+    /// [`VectorStore::prune_expired`]).
+    ///
+    /// With the `rayon` feature, candidates are loaded and scored on rayon's
+    /// global thread pool, which relies on `VectorStore` being `Send + Sync`.
+    /// Results are the same as the sequential scan, except that the order of
+    /// equally scored patterns may differ.
     ///
     /// # Arguments
     ///
@@ -117,84 +350,502 @@ impl<'a> SimilaritySearch<'a> {
     /// # Returns
     ///
     /// A `Result` containing a `Vec<SimilarityResult>` sorted by score.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::PatternNotFound` if `filters.since_commit_sha` does
+    /// not match any stored pattern, or an error if pruning expired patterns fails.
+    #[tracing::instrument(
+        name = "similarity.search_filtered",
+        skip_all,
+        fields(
+            k,
+            embedding_dim = query_embedding.len(),
+            filter_count = filters.active_count(),
+            result_count = tracing::field::Empty,
+        )
+    )]
     pub fn search_filtered(
         &self,
         query_embedding: &[f32],
         k: usize,
         filters: &SearchFilters,
     ) -> Result<Vec<SimilarityResult>> {
+        self.store.prune_expired()?;
+        let since_timestamp = self.resolve_since_timestamp(filters)?;
         let pattern_ids = self.get_candidate_pattern_ids(filters)?;
+        let query = query_record(query_embedding);
 
-        // A min-heap is used to efficiently keep track of the top k results.
-        let mut heap = BinaryHeap::with_capacity(k + 1);
+        #[cfg(not(feature = "rayon"))]
+        let collector = self.scan(pattern_ids, &query, k, filters, since_timestamp)?;
 
-        for pattern_id in pattern_ids {
-            let (embedding, pattern) = match self.store.get_embedding_and_pattern(&pattern_id)? {
-                Some(data) => data,
-                None => continue,
+        // Each rayon worker keeps its own top-k collector; they are merged once
+        // every candidate has been scored. Early exit needs candidates in order.
+        #[cfg(feature = "rayon")]
+        let collector = if self.early_exit.is_some() {
+            self.scan(pattern_ids, &query, k, filters, since_timestamp)?
+        } else {
+            use rayon::iter::{ParallelBridge, ParallelIterator};
+
+            let store = self.store;
+            pattern_ids
+                .par_bridge()
+                .try_fold(
+                    || ResultCollector::new(k, filters),
+                    |mut collector, pattern_id| -> Result<ResultCollector> {
+                        if let Some(result) =
+                            score_stored(store, &query, filters, since_timestamp, pattern_id?)?
+                        {
+                            collector.push(result);
+                        }
+                        Ok(collector)
+                    },
+                )
+                .try_reduce(
+                    || ResultCollector::new(k, filters),
+                    |collector, other| Ok(collector.merge(other)),
+                )?
+        };
+
+        let results = self.finish_results(&query, k, filters, collector)?;
+        tracing::Span::current().record("result_count", results.len());
+        Ok(results)
+    }
+
+    /// Asynchronous variant of [`SimilaritySearch::search_filtered`] for I/O-bound stores.
+    ///
+    /// Each `get_embedding_record_and_pattern` lookup runs on Tokio's blocking pool via
+    /// `spawn_blocking`, with at most `filters.max_concurrent_reads` lookups in
+    /// flight at once. Completed lookups are scored as they arrive, so memory use
+    /// stays bounded by the concurrency limit plus the top-k heap. This pays off
+    /// when the database lives on a networked filesystem, where each read is
+    /// dominated by latency rather than CPU.
+    ///
+    /// Returns the same results as `search_filtered` for the same inputs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a read fails, a blocking task panics, or
+    /// `filters.since_commit_sha` cannot be resolved.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn search_filtered_async(
+        &self,
+        query_embedding: &[f32],
+        k: usize,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SimilarityResult>> {
+        use futures::stream::{FuturesUnordered, StreamExt};
+
+        self.store.prune_expired()?;
+        let since_timestamp = self.resolve_since_timestamp(filters)?;
+        let mut pattern_ids = self.get_candidate_pattern_ids(filters)?;
+        let query = query_record(query_embedding);
+        let max_in_flight = filters.max_concurrent_reads.max(1);
+
+        let mut collector = ResultCollector::new(k, filters);
+        let mut reads = FuturesUnordered::new();
+
+        loop {
+            while reads.len() < max_in_flight {
+                let Some(pattern_id) = pattern_ids.next().transpose()? else {
+                    break;
+                };
+                let store = self.store.clone();
+                reads.push(tokio::task::spawn_blocking(move || {
+                    let data = store.get_embedding_record_and_pattern(&pattern_id);
+                    (pattern_id, data)
+                }));
+            }
+
+            let Some(joined) = reads.next().await else {
+                break;
             };
+            let (pattern_id, data) = joined.map_err(std::io::Error::from)?;
+            let Some((embedding, pattern)) = data? else {
+                continue;
+            };
+
+            if let Some(result) = score_candidate(
+                &query,
+                filters,
+                since_timestamp,
+                pattern_id,
+                &embedding,
+                pattern,
+            ) {
+                collector.push(result);
+            }
+        }
+
+        self.finish_results(&query, k, filters, collector)
+    }
+
+    /// Loads every stored embedding and pattern into an [`EmbeddingCache`].
+    ///
+    /// Reads the whole store in a single transaction. Expired patterns are
+    /// pruned first, as in [`SimilaritySearch::search_filtered`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if pruning or the read fails, or a stored value cannot
+    /// be decoded.
+    pub fn warm_cache(&self) -> Result<EmbeddingCache> {
+        self.store.prune_expired()?;
+        let entries = self
+            .store
+            .all_embedding_records_and_patterns()?
+            .into_iter()
+            .map(|(pattern_id, record, pattern)| (pattern_id, (record, pattern)))
+            .collect();
+        Ok(EmbeddingCache { entries })
+    }
+
+    /// Variant of [`SimilaritySearch::search_filtered`] that never touches the store.
+    ///
+    /// Embeddings and patterns come from `cache`, and filters are evaluated
+    /// against the cached metadata instead of the store's indexes, so repeated
+    /// queries pay no transaction or deserialization cost. Returns the same
+    /// results as `search_filtered` as long as the store has not changed since
+    /// the cache was built.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::PatternNotFound` if `filters.since_commit_sha`
+    /// does not match any cached pattern, or an error if `filters.file_path_glob`
+    /// is not a valid glob.
+    pub fn search_from_cache(
+        &self,
+        cache: &EmbeddingCache,
+        query_embedding: &[f32],
+        k: usize,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SimilarityResult>> {
+        let since_timestamp = match &filters.since_commit_sha {
+            Some(sha) => {
+                let commit_time = cache.commit_timestamp(sha).ok_or_else(|| {
+                    TemporalAIError::PatternNotFound(format!("no pattern for commit {}", sha))
+                })?;
+                Some(filters.since_timestamp.map_or(commit_time, |t| t.max(commit_time)))
+            }
+            None => filters.since_timestamp,
+        };
+        let glob_pattern = filters
+            .file_path_glob
+            .as_deref()
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| {
+                TemporalAIError::IoError(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    e.to_string(),
+                ))
+            })?;
+        let tag_groups: Vec<Vec<String>> = filters
+            .tags
+            .iter()
+            .map(|tag| match &self.synonyms {
+                Some(synonyms) => synonyms.expand(tag),
+                None => vec![tag.clone()],
+            })
+            .collect();
+        let query = query_record(query_embedding);
 
-            if let Some(since) = filters.since_timestamp {
-                if pattern.timestamp < since {
-                    continue;
+        let mut collector = ResultCollector::new(k, filters);
+
+        for (pattern_id, (embedding, pattern)) in &cache.entries {
+            let has_tag = |group: &Vec<String>| pattern.tags.iter().any(|tag| group.contains(tag));
+            let has_tags = match filters.tag_filter_mode {
+                _ if tag_groups.is_empty() => true,
+                TagFilterMode::Any => tag_groups.iter().any(has_tag),
+                TagFilterMode::All => tag_groups.iter().all(has_tag),
+            };
+            // The tag index takes precedence over the glob, as in `search_filtered`.
+            let matches_glob = match &glob_pattern {
+                Some(glob) if tag_groups.is_empty() => {
+                    pattern.file_paths.iter().any(|p| glob.matches(p))
                 }
+                _ => true,
+            };
+            let has_required_paths = filters
+                .require_all_file_paths
+                .iter()
+                .all(|path| pattern.file_paths.contains(path));
+            if !(has_tags && matches_glob && has_required_paths) {
+                continue;
+            }
+
+            // Only candidates that pass the filters pay for cloning the pattern.
+            if let Some(score) =
+                candidate_score(&query, filters, since_timestamp, embedding, pattern)
+            {
+                collector.push(SimilarityResult {
+                    pattern_id: pattern_id.clone(),
+                    score,
+                    pattern: pattern.clone(),
+                });
             }
+        }
+
+        let lookup = |pattern_id: &str| Ok(cache.get(pattern_id).cloned());
+        let results = rerank_if_requested(k, filters, collector.finish(), lookup)?;
+        expand_related(&query, filters, results, lookup)
+    }
+
+    /// Finds `k` relevant but mutually diverse patterns using Maximum Marginal
+    /// Relevance.
+    ///
+    /// The `4 × k` most similar patterns are fetched as candidates. Results are
+    /// then picked one at a time, each maximizing
+    /// `lambda * sim(doc, query) - (1 - lambda) * max_sim(doc, selected)`, so a
+    /// near-duplicate of an already selected pattern is passed over in favour
+    /// of a slightly less similar but different one. `lambda = 1.0` returns the
+    /// same results as [`SimilaritySearch::search`]; `lambda = 0.5` balances
+    /// relevance and diversity.
+    ///
+    /// Results are in selection order and keep their query similarity as
+    /// `score`, so scores are not necessarily descending. Equivalent to
+    /// [`SimilaritySearch::search_filtered`] with `SearchFilters::lambda` set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be read.
+    pub fn search_mmr(
+        &self,
+        query_embedding: &[f32],
+        k: usize,
+        lambda: f32,
+    ) -> Result<Vec<SimilarityResult>> {
+        let filters = SearchFilters {
+            lambda: Some(lambda),
+            ..Default::default()
+        };
+        self.search_filtered(query_embedding, k, &filters)
+    }
 
-            let score = cosine_similarity(query_embedding, &embedding);
+    /// Finds the top `k` most similar patterns for each of several query embeddings.
+    ///
+    /// Every stored pattern is read once, in a single read transaction, and
+    /// scored against all `queries`, so related queries share the I/O of one
+    /// scan instead of paying for one each. Returns one result list per query,
+    /// in the order of `queries`, each matching what
+    /// [`SimilaritySearch::search`] returns for that query alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if pruning expired patterns or the read fails, or a
+    /// stored value cannot be decoded.
+    pub fn search_batch(
+        &self,
+        queries: &[Vec<f32>],
+        k: usize,
+    ) -> Result<Vec<Vec<SimilarityResult>>> {
+        self.store.prune_expired()?;
+        let queries: Vec<EmbeddingRecord> =
+            queries.iter().map(|query| query_record(query)).collect();
+        let mut heaps: Vec<BinaryHeap<SimilarityResult>> = queries
+            .iter()
+            .map(|_| BinaryHeap::with_capacity(k + 1))
+            .collect();
 
-            if let Some(min_score) = filters.min_score {
-                if score < min_score {
-                    continue;
+        self.store
+            .for_each_embedding_record_and_pattern(|pattern_id, embedding, pattern| {
+                for (query, heap) in queries.iter().zip(&mut heaps) {
+                    let score = SimilarityMetric::Cosine.score_records(query, &embedding);
+                    // Only clone the pattern for queries whose top k it enters.
+                    let enters_top_k = heap.len() < k
+                        || heap.peek().is_some_and(|lowest| score > lowest.score);
+                    if !enters_top_k {
+                        continue;
+                    }
+                    push_top_k(
+                        heap,
+                        SimilarityResult {
+                            pattern_id: pattern_id.clone(),
+                            score,
+                            pattern: pattern.clone(),
+                        },
+                        k,
+                    );
                 }
+            })?;
+
+        Ok(heaps.into_iter().map(into_sorted_results).collect())
+    }
+
+    /// Finds approximately the top `k` patterns using an HNSW `index`.
+    ///
+    /// The index supplies `10 × k` candidates, which are then re-scored with
+    /// exact cosine similarity against their stored embeddings, so the scores
+    /// match those of [`SimilaritySearch::search`]. Patterns removed from the
+    /// store since the index was built are skipped; patterns inserted since
+    /// are not found until it is rebuilt with [`VectorStore::build_hnsw_index`].
+    ///
+    /// Requires the `hnsw` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a candidate cannot be read from the store.
+    #[cfg(feature = "hnsw")]
+    pub fn search_hnsw(
+        &self,
+        query_embedding: &[f32],
+        k: usize,
+        index: &crate::hnsw::HnswIndex,
+    ) -> Result<Vec<SimilarityResult>> {
+        let query = query_record(query_embedding);
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+
+        for (pattern_id, _) in index.search(
+            query_embedding,
+            k.saturating_mul(crate::hnsw::CANDIDATE_MULTIPLIER),
+        ) {
+            let Some((embedding, pattern)) =
+                self.store.get_embedding_record_and_pattern(&pattern_id)?
+            else {
+                continue;
+            };
+            let score = SimilarityMetric::Cosine.score_records(&query, &embedding);
+            push_top_k(
+                &mut heap,
+                SimilarityResult {
+                    pattern_id,
+                    score,
+                    pattern,
+                },
+                k,
+            );
+        }
+
+        Ok(into_sorted_results(heap))
+    }
+
+    /// Scores `pattern_ids` in order, stopping early if configured.
+    fn scan(
+        &self,
+        pattern_ids: CandidateIds<'a>,
+        query: &EmbeddingRecord,
+        k: usize,
+        filters: &SearchFilters,
+        since_timestamp: Option<i64>,
+    ) -> Result<ResultCollector> {
+        let mut collector = ResultCollector::new(k, filters);
+        let early_exit = match self.early_exit {
+            Some(early_exit) => {
+                let total = pattern_ids.total()? as f32;
+                let min_scanned = (total * early_exit.min_scan_fraction).ceil() as usize;
+                Some((early_exit.window_size, min_scanned))
             }
+            None => None,
+        };
 
-            heap.push(SimilarityResult {
-                pattern_id: pattern_id.clone(),
-                score,
-                pattern,
-            });
+        let mut scanned = 0;
+        let mut below_streak = 0;
+        for pattern_id in pattern_ids {
+            scanned += 1;
+            let Some(result) =
+                score_stored(self.store, query, filters, since_timestamp, pattern_id?)?
+            else {
+                continue;
+            };
 
-            if heap.len() > k {
-                heap.pop();
+            let Some((window_size, min_scanned)) = early_exit else {
+                collector.push(result);
+                continue;
+            };
+            match collector.lowest_kept_score() {
+                Some(lowest) if result.score < lowest => below_streak += 1,
+                _ => below_streak = 0,
+            }
+            collector.push(result);
+            if scanned >= min_scanned && below_streak >= window_size {
+                tracing::debug!(scanned, "similarity scan exited early");
+                break;
             }
         }
 
-        let mut results: Vec<_> = heap.into_vec();
-        results.sort_by(|a, b| {
-            let a_nan = a.score.is_nan();
-            let b_nan = b.score.is_nan();
+        Ok(collector)
+    }
+
+    /// Produces the final result list, applying MMR and expanding related
+    /// patterns if requested.
+    fn finish_results(
+        &self,
+        query: &EmbeddingRecord,
+        k: usize,
+        filters: &SearchFilters,
+        collector: ResultCollector,
+    ) -> Result<Vec<SimilarityResult>> {
+        let lookup = |pattern_id: &str| self.store.get_embedding_record_and_pattern(pattern_id);
+        let results = rerank_if_requested(k, filters, collector.finish(), lookup)?;
+        expand_related(query, filters, results, lookup)
+    }
+
+    /// Combines `since_timestamp` and `since_commit_sha` into a single lower bound.
+    fn resolve_since_timestamp(&self, filters: &SearchFilters) -> Result<Option<i64>> {
+        let Some(sha) = &filters.since_commit_sha else {
+            return Ok(filters.since_timestamp);
+        };
+
+        let commit_time = self.store.commit_timestamp(sha)?.ok_or_else(|| {
+            TemporalAIError::PatternNotFound(format!("no pattern for commit {}", sha))
+        })?;
+
+        Ok(Some(filters.since_timestamp.map_or(commit_time, |t| t.max(commit_time))))
+    }
+
+    /// Retrieves the candidate pattern IDs based on the applied filters.
+    fn get_candidate_pattern_ids(&self, filters: &SearchFilters) -> Result<CandidateIds<'a>> {
+        let Some((first, rest)) = filters.require_all_file_paths.split_first() else {
+            return self.get_tag_or_glob_candidates(filters);
+        };
+
+        let mut required: HashSet<String> =
+            self.store.find_by_file_path(first)?.into_iter().collect();
+        for file_path in rest {
+            let next = self.store.find_by_file_path(file_path)?;
+            required.retain(|id| next.contains(id));
+        }
 
-            match (a_nan, b_nan) {
-                (true, true) => Ordering::Equal,
-                (true, false) => Ordering::Greater,
-                (false, true) => Ordering::Less,
-                (false, false) => b
-                    .score
-                    .partial_cmp(&a.score)
-                    .unwrap_or(Ordering::Equal),
+        let candidates = match self.get_tag_or_glob_candidates(filters)? {
+            CandidateIds::Listed(ids) => ids.filter(|id| required.contains(id)).collect(),
+            // Without a tag or glob filter every pattern is a candidate, so the
+            // required file paths alone decide.
+            CandidateIds::Paged { .. } => {
+                let mut ids: Vec<String> = required.into_iter().collect();
+                ids.sort_unstable();
+                ids
             }
-        });
-        Ok(results)
+        };
+        Ok(CandidateIds::Listed(candidates.into_iter()))
     }
 
-    /// Retrieves a list of candidate pattern IDs based on the applied filters.
-    fn get_candidate_pattern_ids(&self, filters: &SearchFilters) -> Result<Vec<String>> {
+    /// Candidate IDs from the tag index, or else the file path glob.
+    fn get_tag_or_glob_candidates(&self, filters: &SearchFilters) -> Result<CandidateIds<'a>> {
         if !filters.tags.is_empty() {
-            let mut tags_iter = filters.tags.iter();
-            if let Some(first_tag) = tags_iter.next() {
-                let mut candidates: HashSet<String> =
-                    self.store.find_by_tag(first_tag)?.into_iter().collect();
-
-                for tag in tags_iter {
-                    let next: HashSet<String> =
-                        self.store.find_by_tag(tag)?.into_iter().collect();
-                    candidates.retain(|id| next.contains(id));
+            let mut tag_sets = filters
+                .tags
+                .iter()
+                .map(|tag| self.find_by_tag_or_synonym(tag))
+                .collect::<Result<Vec<_>>>()?;
+
+            let candidates: HashSet<String> = match filters.tag_filter_mode {
+                TagFilterMode::Any => tag_sets.into_iter().flatten().collect(),
+                TagFilterMode::All => {
+                    // Filtering the smallest set against the others keeps the
+                    // intersection proportional to the rarest tag.
+                    tag_sets.sort_by_key(HashSet::len);
+                    let mut sets = tag_sets.into_iter();
+                    let mut smallest = sets.next().unwrap_or_default();
+                    for set in sets {
+                        smallest.retain(|id| set.contains(id));
+                    }
+                    smallest
                 }
+            };
 
-                let mut intersected: Vec<String> = candidates.into_iter().collect();
-                intersected.sort_unstable();
-                return Ok(intersected);
-            }
+            let mut candidates: Vec<String> = candidates.into_iter().collect();
+            candidates.sort_unstable();
+            return Ok(CandidateIds::Listed(candidates.into_iter()));
         }
 
         if let Some(glob_str) = &filters.file_path_glob {
@@ -204,84 +855,614 @@ impl<'a> SimilaritySearch<'a> {
                     e.to_string(),
                 ))
             })?;
+            // Every match starts with the glob's literal directory prefix, so the
+            // file path index can narrow the scan before the glob is applied.
+            let pattern_ids = match literal_dir_prefix(glob_str) {
+                Some(prefix) => {
+                    CandidateIds::Listed(self.store.find_by_file_path_prefix(prefix)?.into_iter())
+                }
+                None => CandidateIds::paged(self.store),
+            };
             let mut candidates = vec![];
-            for pattern_id in self.store.list_patterns()? {
+            for pattern_id in pattern_ids {
+                let pattern_id = pattern_id?;
                 if let Some(pattern) = self.store.get_pattern(&pattern_id)? {
                     if pattern.file_paths.iter().any(|p| glob_pattern.matches(p)) {
                         candidates.push(pattern_id);
                     }
                 }
             }
-            return Ok(candidates);
+            return Ok(CandidateIds::Listed(candidates.into_iter()));
         }
 
-        self.store.list_patterns()
+        Ok(CandidateIds::paged(self.store))
+    }
+
+    /// IDs of patterns tagged with `tag` or, if synonyms are set, any synonym of it.
+    fn find_by_tag_or_synonym(&self, tag: &str) -> Result<HashSet<String>> {
+        let tags = match &self.synonyms {
+            Some(synonyms) => synonyms.expand(tag),
+            None => vec![tag.to_string()],
+        };
+
+        let mut ids = HashSet::new();
+        for tag in tags {
+            ids.extend(self.store.find_by_tag(&tag)?);
+        }
+        Ok(ids)
     }
 }
 
-/// Calculates the cosine similarity between two vector slices.
+/// Number of pattern IDs read per page when every stored pattern is a candidate.
+const CANDIDATE_PAGE_SIZE: usize = 1024;
+
+/// Candidate pattern IDs for a search.
 ///
-/// Cosine similarity measures the cosine of the angle between two vectors,
-/// producing a value between -1 and 1. For non-negative vectors, the range is
-/// 0 to 1. A value of 1 means the vectors are identical in orientation.
-pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
-    }
+/// IDs narrowed down by an index are held in memory; when no filter narrows the
+/// search, IDs are read from the store one page at a time.
+enum CandidateIds<'a> {
+    Listed(std::vec::IntoIter<String>),
+    Paged {
+        store: &'a VectorStore,
+        page: std::vec::IntoIter<String>,
+        cursor: Option<String>,
+        done: bool,
+    },
+}
 
-    let dot_product = dot_product_simd(a, b);
-    let norm_a = l2_norm(a);
-    let norm_b = l2_norm(b);
+impl<'a> CandidateIds<'a> {
+    /// The number of candidate IDs, counted before iteration starts.
+    fn total(&self) -> Result<usize> {
+        match self {
+            Self::Listed(ids) => Ok(ids.len()),
+            Self::Paged { store, .. } => Ok(store.count()? as usize),
+        }
+    }
 
-    if norm_a == 0.0 || norm_b == 0.0 {
-        return 0.0;
+    /// Every pattern ID in `store`, read in pages of [`CANDIDATE_PAGE_SIZE`].
+    fn paged(store: &'a VectorStore) -> Self {
+        Self::Paged {
+            store,
+            page: Vec::new().into_iter(),
+            cursor: None,
+            done: false,
+        }
     }
-    dot_product / (norm_a * norm_b)
 }
 
-/// A platform-specific dispatcher for dot product calculation, using SIMD where available.
-#[cfg(target_arch = "x86_64")]
-fn dot_product_simd(a: &[f32], b: &[f32]) -> f32 {
-    if is_x86_feature_detected!("avx") {
-        unsafe { dot_product_avx(a, b) }
-    } else {
-        dot_product_fallback(a, b)
+impl Iterator for CandidateIds<'_> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Listed(ids) => ids.next().map(Ok),
+            Self::Paged {
+                store,
+                page,
+                cursor,
+                done,
+            } => loop {
+                if let Some(pattern_id) = page.next() {
+                    return Some(Ok(pattern_id));
+                }
+                if *done {
+                    return None;
+                }
+                match store.list_patterns_paginated(cursor.take(), CANDIDATE_PAGE_SIZE) {
+                    Ok((ids, next_cursor)) => {
+                        *done = next_cursor.is_none();
+                        *cursor = next_cursor;
+                        *page = ids.into_iter();
+                    }
+                    Err(e) => {
+                        *done = true;
+                        return Some(Err(e));
+                    }
+                }
+            },
+        }
     }
 }
 
-#[cfg(not(target_arch = "x86_64"))]
-fn dot_product_simd(a: &[f32], b: &[f32]) -> f32 {
-    dot_product_fallback(a, b)
-}
+/// Follows each result with its related patterns if `filters.include_related` is set.
+///
+/// `lookup` fetches a related pattern's embedding and metadata.
+fn expand_related(
+    query: &EmbeddingRecord,
+    filters: &SearchFilters,
+    results: Vec<SimilarityResult>,
+    lookup: impl Fn(&str) -> Result<Option<(EmbeddingRecord, Pattern)>>,
+) -> Result<Vec<SimilarityResult>> {
+    if !filters.include_related {
+        return Ok(results);
+    }
 
-/// An AVX-accelerated dot product implementation (unsafe).
-#[cfg(target_arch = "x86_64")]
-#[target_feature(enable = "avx")]
-unsafe fn dot_product_avx(a: &[f32], b: &[f32]) -> f32 {
-    use std::arch::x86_64::*;
-    let mut sum = _mm256_setzero_ps();
-    for (a_chunk, b_chunk) in a.chunks_exact(8).zip(b.chunks_exact(8)) {
-        let va = _mm256_loadu_ps(a_chunk.as_ptr());
-        let vb = _mm256_loadu_ps(b_chunk.as_ptr());
-        sum = _mm256_add_ps(sum, _mm256_mul_ps(va, vb));
+    let mut seen: HashSet<String> = results.iter().map(|r| r.pattern_id.clone()).collect();
+    let mut expanded = Vec::with_capacity(results.len());
+
+    for result in results {
+        let related_ids = result.pattern.related_ids.clone();
+        expanded.push(result);
+
+        for pattern_id in related_ids {
+            if !seen.insert(pattern_id.clone()) {
+                continue;
+            }
+            if let Some((embedding, pattern)) = lookup(&pattern_id)? {
+                expanded.push(SimilarityResult {
+                    score: filters.metric.score_records(query, &embedding),
+                    pattern_id,
+                    pattern,
+                });
+            }
+        }
     }
-    let mut result = [0.0f32; 8];
-    _mm256_storeu_ps(result.as_mut_ptr(), sum);
-    result.iter().sum::<f32>() + a.chunks_exact(8).remainder().iter().zip(b.chunks_exact(8).remainder()).map(|(x, y)| x * y).sum::<f32>()
-}
 
-/// A portable, non-SIMD fallback for dot product calculation.
-fn dot_product_fallback(a: &[f32], b: &[f32]) -> f32 {
-    a.iter().zip(b).map(|(x, y)| x * y).sum()
+    Ok(expanded)
 }
 
-/// Calculates the L2 norm (Euclidean length) of a vector.
-fn l2_norm(vec: &[f32]) -> f32 {
-    vec.iter().map(|x| x * x).sum::<f32>().sqrt()
-}
+/// Number of candidates fetched per requested result when `SearchFilters::lambda` is set.
+const MMR_CANDIDATE_MULTIPLIER: usize = 4;
 
-#[cfg(test)]
-mod tests {
+/// Picks `k` of `results` by Maximum Marginal Relevance if `filters.lambda` is set.
+///
+/// `lookup` fetches each candidate's embedding for the pairwise similarities;
+/// candidates it cannot find are dropped.
+fn rerank_if_requested(
+    k: usize,
+    filters: &SearchFilters,
+    results: Vec<SimilarityResult>,
+    lookup: impl Fn(&str) -> Result<Option<(EmbeddingRecord, Pattern)>>,
+) -> Result<Vec<SimilarityResult>> {
+    let Some(lambda) = filters.lambda else {
+        return Ok(results);
+    };
+
+    let mut candidates = Vec::with_capacity(results.len());
+    for result in results {
+        if let Some((embedding, _)) = lookup(&result.pattern_id)? {
+            candidates.push((result, embedding));
+        }
+    }
+
+    // The highest similarity of each remaining candidate to any selected result.
+    let mut redundancy = vec![0.0f32; candidates.len()];
+    let mut selected = Vec::with_capacity(k.min(candidates.len()));
+
+    while selected.len() < k && !candidates.is_empty() {
+        let marginal_relevance =
+            |i: usize| lambda * candidates[i].0.score - (1.0 - lambda) * redundancy[i];
+        // Candidates arrive sorted by score, so ties go to the more relevant one.
+        let mut best = 0;
+        for i in 1..candidates.len() {
+            if marginal_relevance(i) > marginal_relevance(best) {
+                best = i;
+            }
+        }
+
+        let (result, embedding) = candidates.remove(best);
+        redundancy.remove(best);
+        for ((_, other), max_sim) in candidates.iter().zip(&mut redundancy) {
+            let sim = filters.metric.score_records(&embedding, other);
+            *max_sim = if selected.is_empty() { sim } else { max_sim.max(sim) };
+        }
+        selected.push(result);
+    }
+
+    Ok(selected)
+}
+
+/// Returns the part of `glob` up to the last `/` before its first wildcard, if any.
+fn literal_dir_prefix(glob: &str) -> Option<&str> {
+    let wildcard = glob.find(['*', '?', '[']).unwrap_or(glob.len());
+    let separator = glob[..wildcard].rfind('/')?;
+    Some(&glob[..=separator])
+}
+
+/// Scores a single candidate, returning `None` if it is excluded by `filters`.
+fn score_candidate(
+    query: &EmbeddingRecord,
+    filters: &SearchFilters,
+    since_timestamp: Option<i64>,
+    pattern_id: String,
+    embedding: &EmbeddingRecord,
+    pattern: Pattern,
+) -> Option<SimilarityResult> {
+    let score = candidate_score(query, filters, since_timestamp, embedding, &pattern)?;
+    Some(SimilarityResult {
+        pattern_id,
+        score,
+        pattern,
+    })
+}
+
+/// Loads `pattern_id` from `store` and scores it, or returns `None` if the
+/// pattern is gone or excluded by `filters`.
+fn score_stored(
+    store: &VectorStore,
+    query: &EmbeddingRecord,
+    filters: &SearchFilters,
+    since_timestamp: Option<i64>,
+    pattern_id: String,
+) -> Result<Option<SimilarityResult>> {
+    let Some((embedding, pattern)) = store.get_embedding_record_and_pattern(&pattern_id)? else {
+        return Ok(None);
+    };
+    Ok(score_candidate(
+        query,
+        filters,
+        since_timestamp,
+        pattern_id,
+        &embedding,
+        pattern,
+    ))
+}
+
+/// The candidate's score, or `None` if it is excluded by `filters`.
+fn candidate_score(
+    query: &EmbeddingRecord,
+    filters: &SearchFilters,
+    since_timestamp: Option<i64>,
+    embedding: &EmbeddingRecord,
+    pattern: &Pattern,
+) -> Option<f32> {
+    if let Some(since) = since_timestamp {
+        if pattern.timestamp < since {
+            return None;
+        }
+    }
+
+    let score = filters.metric.score_records(query, embedding);
+
+    if let Some(min_score) = filters.min_score {
+        if score < min_score {
+            return None;
+        }
+    }
+
+    Some(score)
+}
+
+/// Accumulates scored candidates into the final top-k list.
+///
+/// Without deduplication, a min-heap keeps only the best `k` results. With
+/// `dedup_by_commit`, one representative per commit is kept until all
+/// candidates are seen, and top-k selection happens in [`ResultCollector::finish`].
+struct ResultCollector {
+    k: usize,
+    // A min-heap is used to efficiently keep track of the top k results.
+    heap: BinaryHeap<SimilarityResult>,
+    by_commit: Option<(DedupStrategy, HashMap<String, SimilarityResult>)>,
+}
+
+impl ResultCollector {
+    fn new(k: usize, filters: &SearchFilters) -> Self {
+        // MMR needs a wider pool of candidates to choose a diverse top k from.
+        let k = match filters.lambda {
+            Some(_) => k.saturating_mul(MMR_CANDIDATE_MULTIPLIER),
+            None => k,
+        };
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+            by_commit: filters
+                .dedup_by_commit
+                .then(|| (filters.dedup_strategy, HashMap::new())),
+        }
+    }
+
+    fn push(&mut self, result: SimilarityResult) {
+        let Some((strategy, by_commit)) = &mut self.by_commit else {
+            push_top_k(&mut self.heap, result, self.k);
+            return;
+        };
+
+        match by_commit.get_mut(&result.pattern.commit_sha) {
+            Some(current) => {
+                if strategy.prefers(&result, current) {
+                    *current = result;
+                }
+            }
+            None => {
+                by_commit.insert(result.pattern.commit_sha.clone(), result);
+            }
+        }
+    }
+
+    /// The lowest score that a new result must beat to be kept, once `k`
+    /// results are held; `None` while deduplicating by commit.
+    fn lowest_kept_score(&self) -> Option<f32> {
+        if self.by_commit.is_some() || self.heap.len() < self.k {
+            return None;
+        }
+        self.heap.peek().map(|result| result.score)
+    }
+
+    /// Folds the results kept by `other` into `self`, as if they had been pushed here.
+    #[cfg(feature = "rayon")]
+    fn merge(mut self, other: ResultCollector) -> Self {
+        let deduped = other
+            .by_commit
+            .into_iter()
+            .flat_map(|(_, by_commit)| by_commit.into_values());
+        for result in other.heap.into_vec().into_iter().chain(deduped) {
+            self.push(result);
+        }
+        self
+    }
+
+    fn finish(mut self) -> Vec<SimilarityResult> {
+        if let Some((_, by_commit)) = self.by_commit.take() {
+            for result in by_commit.into_values() {
+                push_top_k(&mut self.heap, result, self.k);
+            }
+        }
+        into_sorted_results(self.heap)
+    }
+}
+
+/// Pushes `result` onto the min-heap, evicting the lowest score once it holds `k` items.
+fn push_top_k(heap: &mut BinaryHeap<SimilarityResult>, result: SimilarityResult, k: usize) {
+    heap.push(result);
+    if heap.len() > k {
+        heap.pop();
+    }
+}
+
+/// Drains the heap into a vector sorted by descending score, with NaN scores last.
+fn into_sorted_results(heap: BinaryHeap<SimilarityResult>) -> Vec<SimilarityResult> {
+    let mut results: Vec<_> = heap.into_vec();
+    results.sort_by(|a, b| {
+        let a_nan = a.score.is_nan();
+        let b_nan = b.score.is_nan();
+
+        match (a_nan, b_nan) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => b
+                .score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal),
+        }
+    });
+    results
+}
+
+/// Calculates the cosine similarity between two vector slices.
+///
+/// Cosine similarity measures the cosine of the angle between two vectors,
+/// producing a value between -1 and 1. For non-negative vectors, the range is
+/// 0 to 1. A value of 1 means the vectors are identical in orientation.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot_product = dot_product_simd(a, b);
+    let norm_a = l2_norm(a);
+    let norm_b = l2_norm(b);
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot_product / (norm_a * norm_b)
+}
+
+/// Calculates the Euclidean (L2) distance between two vector slices.
+///
+/// Returns `f32::INFINITY` when the slices have different lengths.
+pub fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return f32::INFINITY;
+    }
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// Largest number of patterns [`export_similarity_matrix`] includes.
+pub const MAX_MATRIX_PATTERNS: usize = 500;
+
+/// Pairwise cosine similarities between stored patterns, for visualization tools.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SimilarityMatrix {
+    /// Pattern IDs, in row and column order.
+    pub ids: Vec<String>,
+    /// `matrix[i][j]` is the cosine similarity between `ids[i]` and `ids[j]`.
+    pub matrix: Vec<Vec<f32>>,
+}
+
+impl SimilarityMatrix {
+    /// Renders the matrix as CSV: a header row of IDs, then one row per pattern
+    /// starting with its ID.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("id");
+        for id in &self.ids {
+            csv.push(',');
+            csv.push_str(id);
+        }
+        csv.push('\n');
+
+        for (id, row) in self.ids.iter().zip(&self.matrix) {
+            csv.push_str(id);
+            for value in row {
+                csv.push(',');
+                csv.push_str(&value.to_string());
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Computes the pairwise cosine similarity of up to `max_patterns` stored patterns.
+///
+/// Patterns are taken in ID order. `max_patterns` is capped at
+/// [`MAX_MATRIX_PATTERNS`], with a warning, because the matrix grows
+/// quadratically.
+///
+/// # Errors
+///
+/// Returns an error if the store cannot be read.
+pub fn export_similarity_matrix(
+    store: &VectorStore,
+    max_patterns: usize,
+) -> Result<SimilarityMatrix> {
+    let limit = if max_patterns > MAX_MATRIX_PATTERNS {
+        tracing::warn!(max_patterns, limit = MAX_MATRIX_PATTERNS, "similarity matrix truncated");
+        MAX_MATRIX_PATTERNS
+    } else {
+        max_patterns
+    };
+
+    let mut ids = Vec::new();
+    let mut embeddings = Vec::new();
+    for pattern_id in store.list_patterns()? {
+        if ids.len() == limit {
+            break;
+        }
+        if let Some(embedding) = store.get_embedding(&pattern_id)? {
+            ids.push(pattern_id);
+            embeddings.push(embedding);
+        }
+    }
+
+    let n = embeddings.len();
+    let mut matrix = vec![vec![0.0; n]; n];
+    for (i, a) in embeddings.iter().enumerate() {
+        for (j, b) in embeddings.iter().enumerate().skip(i) {
+            let score = cosine_similarity(a, b);
+            matrix[i][j] = score;
+            matrix[j][i] = score;
+        }
+    }
+
+    Ok(SimilarityMatrix { ids, matrix })
+}
+
+/// Wraps a query embedding in a record so its norm is computed once per search.
+fn query_record(query_embedding: &[f32]) -> EmbeddingRecord {
+    EmbeddingRecord {
+        vector: query_embedding.to_vec(),
+        norm: l2_norm(query_embedding),
+        created_at: 0,
+        version: crate::SCHEMA_VERSION,
+    }
+}
+
+/// A platform-specific dispatcher for dot product calculation, using SIMD where available.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn dot_product_simd(a: &[f32], b: &[f32]) -> f32 {
+    // Builds with `-C target-feature=+avx512f` skip the runtime check.
+    if cfg!(target_feature = "avx512f") || is_x86_feature_detected!("avx512f") {
+        // SAFETY: AVX-512F support was verified at compile time or just above.
+        unsafe { dot_product_avx512(a, b) }
+    } else if is_x86_feature_detected!("avx") {
+        // SAFETY: AVX support was verified at runtime just above.
+        unsafe { dot_product_avx(a, b) }
+    } else {
+        dot_product_fallback(a, b)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) fn dot_product_simd(a: &[f32], b: &[f32]) -> f32 {
+    // NEON is part of the baseline aarch64 target, so the runtime check is
+    // normally compiled out.
+    if cfg!(target_feature = "neon") || std::arch::is_aarch64_feature_detected!("neon") {
+        // SAFETY: NEON support was verified at compile time or just above.
+        unsafe { dot_product_neon(a, b) }
+    } else {
+        dot_product_fallback(a, b)
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub(crate) fn dot_product_simd(a: &[f32], b: &[f32]) -> f32 {
+    dot_product_fallback(a, b)
+}
+
+/// An AVX-accelerated dot product implementation (unsafe).
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports AVX, e.g. with
+/// `is_x86_feature_detected!("avx")`. Loads are unaligned, so the slices need no
+/// particular alignment.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn dot_product_avx(a: &[f32], b: &[f32]) -> f32 {
+    use std::arch::x86_64::*;
+    let mut sum = _mm256_setzero_ps();
+    for (a_chunk, b_chunk) in a.chunks_exact(8).zip(b.chunks_exact(8)) {
+        let va = _mm256_loadu_ps(a_chunk.as_ptr());
+        let vb = _mm256_loadu_ps(b_chunk.as_ptr());
+        sum = _mm256_add_ps(sum, _mm256_mul_ps(va, vb));
+    }
+    let mut result = [0.0f32; 8];
+    _mm256_storeu_ps(result.as_mut_ptr(), sum);
+    result.iter().sum::<f32>() + a.chunks_exact(8).remainder().iter().zip(b.chunks_exact(8).remainder()).map(|(x, y)| x * y).sum::<f32>()
+}
+
+/// An AVX-512F-accelerated dot product implementation (unsafe).
+///
+/// Processes 16 floats per iteration with fused multiply-add.
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports AVX-512F, e.g. with
+/// `is_x86_feature_detected!("avx512f")`. Loads are unaligned, so the slices
+/// need no particular alignment.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f")]
+unsafe fn dot_product_avx512(a: &[f32], b: &[f32]) -> f32 {
+    use std::arch::x86_64::*;
+    let mut sum = _mm512_setzero_ps();
+    for (a_chunk, b_chunk) in a.chunks_exact(16).zip(b.chunks_exact(16)) {
+        let va = _mm512_loadu_ps(a_chunk.as_ptr());
+        let vb = _mm512_loadu_ps(b_chunk.as_ptr());
+        sum = _mm512_fmadd_ps(va, vb, sum);
+    }
+    _mm512_reduce_add_ps(sum)
+        + dot_product_fallback(a.chunks_exact(16).remainder(), b.chunks_exact(16).remainder())
+}
+
+/// A NEON-accelerated dot product implementation (unsafe).
+///
+/// # Safety
+///
+/// The caller must ensure the CPU supports NEON, e.g. with
+/// `is_aarch64_feature_detected!("neon")`. Loads are unaligned, so the slices
+/// need no particular alignment.
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn dot_product_neon(a: &[f32], b: &[f32]) -> f32 {
+    use std::arch::aarch64::*;
+    let mut sum = vdupq_n_f32(0.0);
+    for (a_chunk, b_chunk) in a.chunks_exact(4).zip(b.chunks_exact(4)) {
+        let va = vld1q_f32(a_chunk.as_ptr());
+        let vb = vld1q_f32(b_chunk.as_ptr());
+        sum = vaddq_f32(sum, vmulq_f32(va, vb));
+    }
+    vaddvq_f32(sum)
+        + dot_product_fallback(a.chunks_exact(4).remainder(), b.chunks_exact(4).remainder())
+}
+
+/// A portable, non-SIMD fallback for dot product calculation.
+fn dot_product_fallback(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Calculates the L2 norm (Euclidean length) of a vector.
+fn l2_norm(vec: &[f32]) -> f32 {
+    vec.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
     use crate::pattern_extractor::Pattern;
     use tempfile::tempdir;
@@ -294,6 +1475,12 @@ mod tests {
             commit_sha: format!("commit{}", id),
             timestamp: 1234567890,
             tags: vec!["rust".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
         }
     }
 
@@ -315,6 +1502,205 @@ mod tests {
         assert!((score - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_export_similarity_matrix() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let mut x = vec![0.0; 768];
+        x[0] = 1.0;
+        let mut xy = vec![0.0; 768];
+        xy[0] = 1.0;
+        xy[1] = 1.0;
+        let mut y = vec![0.0; 768];
+        y[1] = 2.0;
+        for (id, embedding) in [("a", x), ("b", xy), ("c", y)] {
+            store.insert(&create_test_pattern(id), embedding)?;
+        }
+
+        let export = export_similarity_matrix(&store, 10)?;
+        assert_eq!(export.ids, vec!["a", "b", "c"]);
+        for (i, row) in export.matrix.iter().enumerate() {
+            assert!((row[i] - 1.0).abs() < 1e-6);
+            for (j, value) in row.iter().enumerate() {
+                assert_eq!(*value, export.matrix[j][i]);
+            }
+        }
+        assert!((export.matrix[0][1] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert_eq!(export.matrix[0][2], 0.0);
+
+        let csv = export.to_csv();
+        assert_eq!(csv.lines().next(), Some("id,a,b,c"));
+        assert_eq!(csv.lines().count(), 4);
+
+        assert_eq!(export_similarity_matrix(&store, 2)?.ids, vec!["a", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_filter_expands_synonyms() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        for (id, tag) in [("1", "jwt"), ("2", "auth"), ("3", "database")] {
+            let pattern = Pattern {
+                tags: vec![tag.to_string()],
+                ..create_test_pattern(id)
+            };
+            store.insert(&pattern, vec![0.5; 768])?;
+        }
+
+        let filters = SearchFilters {
+            tags: vec!["authentication".to_string()],
+            ..Default::default()
+        };
+        let query = vec![0.5; 768];
+
+        let plain = SimilaritySearch::new(&store);
+        assert!(plain.search_filtered(&query, 10, &filters)?.is_empty());
+
+        let synonyms = Arc::new(TagSynonyms::default());
+        let expanded = SimilaritySearch::new(&store).with_synonyms(synonyms);
+        let mut ids: Vec<_> = expanded
+            .search_filtered(&query, 10, &filters)?
+            .into_iter()
+            .map(|result| result.pattern_id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["1", "2"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_filter_modes() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        for (id, tags) in [
+            ("1", vec!["rust", "feat"]),
+            ("2", vec!["rust"]),
+            ("3", vec!["feat"]),
+            ("4", vec!["docs"]),
+        ] {
+            let pattern = Pattern {
+                tags: tags.into_iter().map(String::from).collect(),
+                ..create_test_pattern(id)
+            };
+            store.insert(&pattern, vec![0.5; 768])?;
+        }
+
+        let search = SimilaritySearch::new(&store);
+        let cache = search.warm_cache()?;
+        let query = vec![0.5; 768];
+
+        for (mode, expected) in [
+            (TagFilterMode::All, vec!["1"]),
+            (TagFilterMode::Any, vec!["1", "2", "3"]),
+        ] {
+            let filters = SearchFilters {
+                tags: vec!["rust".to_string(), "feat".to_string()],
+                tag_filter_mode: mode,
+                ..Default::default()
+            };
+            let ids = |results: Vec<SimilarityResult>| {
+                let mut ids: Vec<_> = results.into_iter().map(|r| r.pattern_id).collect();
+                ids.sort();
+                ids
+            };
+            assert_eq!(ids(search.search_filtered(&query, 10, &filters)?), expected);
+            assert_eq!(
+                ids(search.search_from_cache(&cache, &query, 10, &filters)?),
+                expected
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_require_all_file_paths() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        let patterns = [
+            ("1", vec!["src/auth/mod.rs", "tests/auth_test.rs", "README.md"]),
+            ("2", vec!["src/auth/mod.rs"]),
+            ("3", vec!["tests/auth_test.rs"]),
+        ];
+        for (id, file_paths) in patterns {
+            let pattern = Pattern {
+                file_paths: file_paths.into_iter().map(String::from).collect(),
+                ..create_test_pattern(id)
+            };
+            store.insert(&pattern, vec![0.5; 768])?;
+        }
+
+        let filters = SearchFilters {
+            require_all_file_paths: vec![
+                "src/auth/mod.rs".to_string(),
+                "tests/auth_test.rs".to_string(),
+            ],
+            ..Default::default()
+        };
+        let query = vec![0.5; 768];
+        let results = SimilaritySearch::new(&store).search_filtered(&query, 10, &filters)?;
+
+        let ids: Vec<_> = results.iter().map(|result| result.pattern_id.as_str()).collect();
+        assert_eq!(ids, vec!["1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_literal_dir_prefix() {
+        assert_eq!(literal_dir_prefix("crates/temporal-ai/**/*.rs"), Some("crates/temporal-ai/"));
+        assert_eq!(literal_dir_prefix("src/lib.rs"), Some("src/"));
+        assert_eq!(literal_dir_prefix("src*/lib.rs"), None);
+        assert_eq!(literal_dir_prefix("**/*.rs"), None);
+    }
+
+    #[test]
+    fn test_l2_distance() {
+        assert!((l2_distance(&[0.0, 0.0], &[3.0, 4.0]) - 5.0).abs() < 0.001);
+        assert_eq!(l2_distance(&[1.0, 2.0], &[1.0, 2.0]), 0.0);
+        assert!(l2_distance(&[1.0], &[1.0, 2.0]).is_infinite());
+        assert_eq!(SimilarityMetric::L2Distance.score(&[1.0], &[1.0, 2.0]), 0.0);
+    }
+
+    #[test]
+    fn test_metrics_rank_by_magnitude_differently() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let embedding = |x: f32, y: f32| {
+            let mut embedding = vec![0.0; 768];
+            embedding[0] = x;
+            embedding[1] = y;
+            embedding
+        };
+        // Same direction as the query but short, long and off-axis, and close.
+        store.insert(&create_test_pattern("short"), embedding(0.5, 0.0))?;
+        store.insert(&create_test_pattern("long"), embedding(3.0, 1.0))?;
+        store.insert(&create_test_pattern("close"), embedding(1.2, 0.3))?;
+
+        let query = embedding(1.0, 0.0);
+        let search = SimilaritySearch::new(&store);
+        let ranking = |metric: SimilarityMetric| -> Result<Vec<String>> {
+            let filters = SearchFilters {
+                metric,
+                ..Default::default()
+            };
+            Ok(search
+                .search_filtered(&query, 3, &filters)?
+                .into_iter()
+                .map(|r| r.pattern_id)
+                .collect())
+        };
+
+        assert_eq!(ranking(SimilarityMetric::Cosine)?, ["short", "close", "long"]);
+        assert_eq!(ranking(SimilarityMetric::DotProduct)?, ["long", "close", "short"]);
+        assert_eq!(ranking(SimilarityMetric::L2Distance)?, ["close", "short", "long"]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_similarity_search() -> Result<()> {
         let dir = tempdir()?;
@@ -375,6 +1761,411 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_search_since_commit_sha() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        for (id, timestamp) in [("1", 100), ("2", 200), ("3", 300)] {
+            let pattern = Pattern {
+                timestamp,
+                ..create_test_pattern(id)
+            };
+            store.insert(&pattern, vec![0.5; 768])?;
+        }
+
+        let search = SimilaritySearch::new(&store);
+        let filters = SearchFilters {
+            since_commit_sha: Some("commit2".to_string()),
+            ..Default::default()
+        };
+        let mut ids: Vec<_> = search
+            .search_filtered(&[1.0; 768], 10, &filters)?
+            .into_iter()
+            .map(|r| r.pattern_id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["2", "3"]);
+
+        let filters = SearchFilters {
+            since_commit_sha: Some("missing".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            search.search_filtered(&[1.0; 768], 10, &filters),
+            Err(TemporalAIError::PatternNotFound(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unfiltered_search_reads_every_page() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let count = CANDIDATE_PAGE_SIZE + 3;
+        let records: Vec<(Pattern, Vec<f32>)> = (0..count)
+            .map(|i| (create_test_pattern(&format!("p{:05}", i)), vec![0.1; 768]))
+            .collect();
+        store.insert_batch(&records)?;
+
+        let search = SimilaritySearch::new(&store);
+        let results = search.search_filtered(&[0.1; 768], count, &SearchFilters::default())?;
+        assert_eq!(results.len(), count);
+
+        let filters = SearchFilters {
+            file_path_glob: Some("**/p0000*.rs".to_string()),
+            ..Default::default()
+        };
+        let results = search.search_filtered(&[0.1; 768], count, &filters)?;
+        assert_eq!(results.len(), 10);
+
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_search_filtered_async_matches_sync() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        for i in 0..20 {
+            let mut embedding = vec![0.1; 768];
+            embedding[i] = 1.0 + i as f32;
+            store.insert(&create_test_pattern(&i.to_string()), embedding)?;
+        }
+
+        let mut query = vec![0.0; 768];
+        query[12] = 1.0;
+
+        let search = SimilaritySearch::new(&store);
+        let filters = SearchFilters {
+            max_concurrent_reads: 3,
+            ..Default::default()
+        };
+        let sync_ids: Vec<_> = search
+            .search_filtered(&query, 5, &filters)?
+            .into_iter()
+            .map(|r| r.pattern_id)
+            .collect();
+        let async_ids: Vec<_> = search
+            .search_filtered_async(&query, 5, &filters)
+            .await?
+            .into_iter()
+            .map(|r| r.pattern_id)
+            .collect();
+
+        assert_eq!(async_ids.len(), 5);
+        assert_eq!(async_ids[0], "12");
+        assert_eq!(sync_ids, async_ids);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_early_exit_stops_after_window() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        // IDs are scanned in key order, and scores fall as the IDs grow, except
+        // for one strong match at the very end.
+        for i in 0..100 {
+            let mut embedding = vec![0.0; 768];
+            embedding[0] = 1.0;
+            embedding[1] = i as f32 / 10.0;
+            store.insert(&create_test_pattern(&format!("{i:03}")), embedding)?;
+        }
+        let mut embedding = vec![0.0; 768];
+        embedding[0] = 1.0;
+        embedding[1] = 0.05;
+        store.insert(&create_test_pattern("999"), embedding)?;
+
+        let mut query = vec![0.0; 768];
+        query[0] = 1.0;
+        let ids = |results: Vec<SimilarityResult>| {
+            results
+                .into_iter()
+                .map(|r| r.pattern_id)
+                .collect::<Vec<_>>()
+        };
+
+        let full = ids(SimilaritySearch::new(&store).search(&query, 3)?);
+        assert_eq!(full, ["000", "999", "001"]);
+
+        let early = SimilaritySearch::new(&store).with_early_exit(0.1, 5);
+        assert_eq!(ids(early.search(&query, 3)?), ["000", "001", "002"]);
+
+        // With the whole store as the minimum fraction, nothing is skipped.
+        let exhaustive = SimilaritySearch::new(&store).with_early_exit(1.0, 5);
+        assert_eq!(ids(exhaustive.search(&query, 3)?), full);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_batch_matches_individual_searches() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        for i in 0..11 {
+            let embedding: Vec<f32> = (0..768)
+                .map(|d| ((i * 7 + d * 3) % 11) as f32 / 11.0)
+                .collect();
+            store.insert(&create_test_pattern(&i.to_string()), embedding)?;
+        }
+
+        let queries: Vec<Vec<f32>> = (0..3)
+            .map(|q| (0..768).map(|d| ((d + q * 5) % 13) as f32 / 13.0).collect())
+            .collect();
+        let search = SimilaritySearch::new(&store);
+        let batched = search.search_batch(&queries, 5)?;
+
+        assert_eq!(batched.len(), queries.len());
+        for (query, results) in queries.iter().zip(&batched) {
+            let expected = search.search(query, 5)?;
+            let ids = |results: &[SimilarityResult]| {
+                results
+                    .iter()
+                    .map(|r| r.pattern_id.clone())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(ids(results), ids(&expected));
+        }
+        assert!(search.search_batch(&[], 5)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_from_cache_matches_store() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        for i in 0..20 {
+            let mut embedding = vec![0.1; 768];
+            embedding[i] = 1.0 + i as f32;
+            store.insert(&create_test_pattern(&i.to_string()), embedding)?;
+        }
+
+        let mut query = vec![0.0; 768];
+        query[12] = 1.0;
+
+        let search = SimilaritySearch::new(&store);
+        let cache = search.warm_cache()?;
+        assert_eq!(cache.len(), 20);
+
+        let ids = |results: Vec<SimilarityResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.pattern_id).collect()
+        };
+        for filters in [
+            SearchFilters::default(),
+            SearchFilters {
+                file_path_glob: Some("src/1*.rs".to_string()),
+                ..Default::default()
+            },
+            SearchFilters {
+                require_all_file_paths: vec!["src/3.rs".to_string()],
+                ..Default::default()
+            },
+        ] {
+            let cached = ids(search.search_from_cache(&cache, &query, 5, &filters)?);
+            assert_eq!(cached, ids(search.search_filtered(&query, 5, &filters)?));
+        }
+
+        Ok(())
+    }
+
+    fn insert_commit_group(store: &VectorStore) -> Result<()> {
+        // Three patterns from one commit, the first being the closest match.
+        for (id, weight, timestamp) in [("a1", 1.0, 100), ("a2", 0.9, 300), ("a3", 0.8, 200)] {
+            let pattern = Pattern {
+                commit_sha: "commitA".to_string(),
+                timestamp,
+                ..create_test_pattern(id)
+            };
+            let mut embedding = vec![0.0; 768];
+            embedding[0] = weight;
+            embedding[1] = 1.0 - weight;
+            store.insert(&pattern, embedding)?;
+        }
+
+        let mut embedding = vec![0.0; 768];
+        embedding[0] = 0.5;
+        embedding[1] = 0.5;
+        store.insert(&create_test_pattern("b1"), embedding)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedup_keep_highest_score() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        insert_commit_group(&store)?;
+
+        let mut query = vec![0.0; 768];
+        query[0] = 1.0;
+
+        let search = SimilaritySearch::new(&store);
+        let filters = SearchFilters {
+            dedup_by_commit: true,
+            dedup_strategy: DedupStrategy::KeepHighestScore,
+            ..Default::default()
+        };
+        let results = search.search_filtered(&query, 2, &filters)?;
+        let ids: Vec<_> = results.iter().map(|r| r.pattern_id.as_str()).collect();
+        assert_eq!(ids, vec!["a1", "b1"]);
+
+        let undeduped = search.search_filtered(&query, 2, &SearchFilters::default())?;
+        assert!(undeduped
+            .iter()
+            .all(|r| r.pattern.commit_sha == "commitA"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedup_keep_most_recent() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        insert_commit_group(&store)?;
+
+        let mut query = vec![0.0; 768];
+        query[0] = 1.0;
+
+        let search = SimilaritySearch::new(&store);
+        let filters = SearchFilters {
+            dedup_by_commit: true,
+            dedup_strategy: DedupStrategy::KeepMostRecent,
+            ..Default::default()
+        };
+        let results = search.search_filtered(&query, 5, &filters)?;
+        let ids: Vec<_> = results.iter().map(|r| r.pattern_id.as_str()).collect();
+        assert_eq!(ids, vec!["a2", "b1"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_mmr_skips_near_duplicates() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        for (id, axis, weight) in [("a1", 1, 0.1), ("a2", 1, 0.12), ("b1", 2, 0.5)] {
+            let mut embedding = vec![0.0; 768];
+            embedding[0] = 1.0;
+            embedding[axis] = weight;
+            store.insert(&create_test_pattern(id), embedding)?;
+        }
+
+        let mut query = vec![0.0; 768];
+        query[0] = 1.0;
+        let search = SimilaritySearch::new(&store);
+        let ids = |results: Vec<SimilarityResult>| {
+            results
+                .into_iter()
+                .map(|r| r.pattern_id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(search.search_mmr(&query, 2, 1.0)?), ["a1", "a2"]);
+        assert_eq!(
+            ids(search.search_mmr(&query, 2, 1.0)?),
+            ids(search.search(&query, 2)?)
+        );
+        assert_eq!(ids(search.search_mmr(&query, 2, 0.5)?), ["a1", "b1"]);
+
+        let cache = search.warm_cache()?;
+        let filters = SearchFilters {
+            lambda: Some(0.5),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(search.search_from_cache(&cache, &query, 2, &filters)?),
+            ["a1", "b1"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_include_related() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let mut close = vec![0.0; 768];
+        close[0] = 1.0;
+        let mut far = vec![0.0; 768];
+        far[1] = 1.0;
+        store.insert(&create_test_pattern("hit"), close.clone())?;
+        store.insert(&create_test_pattern("runner_up"), close)?;
+        store.insert(&create_test_pattern("linked"), far)?;
+        store.link_patterns("hit", "linked")?;
+
+        let mut query = vec![0.0; 768];
+        query[0] = 1.0;
+        let search = SimilaritySearch::new(&store);
+
+        let plain = search.search_filtered(&query, 1, &SearchFilters::default())?;
+        assert_eq!(plain.len(), 1);
+
+        let filters = SearchFilters {
+            include_related: true,
+            ..Default::default()
+        };
+        let expanded = search.search_filtered(&query, 2, &filters)?;
+        let ids: Vec<_> = expanded.iter().map(|r| r.pattern_id.as_str()).collect();
+        assert_eq!(ids.len(), 3);
+        assert!(ids.contains(&"linked"));
+        let linked = expanded.iter().find(|r| r.pattern_id == "linked").unwrap();
+        assert!(linked.score.abs() < 1e-6);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_scan_matches_sequential() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        for i in 0..200 {
+            let pattern = Pattern {
+                commit_sha: format!("commit{}", i % 40),
+                ..create_test_pattern(&i.to_string())
+            };
+            let mut embedding = vec![0.0; 768];
+            embedding[0] = 1.0;
+            embedding[1] = i as f32 / 200.0;
+            store.insert(&pattern, embedding)?;
+        }
+
+        let mut query = vec![0.0; 768];
+        query[1] = 1.0;
+        let search = SimilaritySearch::new(&store);
+        let sequential = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .expect("failed to build thread pool");
+
+        for dedup_by_commit in [false, true] {
+            let filters = SearchFilters {
+                dedup_by_commit,
+                ..Default::default()
+            };
+            let expected = sequential.install(|| search.search_filtered(&query, 10, &filters))?;
+            let actual = search.search_filtered(&query, 10, &filters)?;
+
+            let ids = |results: &[SimilarityResult]| {
+                results
+                    .iter()
+                    .map(|r| r.pattern_id.clone())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(ids(&actual), ids(&expected));
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_dot_product_fallback() {
         let a = vec![1.0, 2.0, 3.0];
@@ -383,6 +2174,37 @@ mod tests {
         assert_eq!(result, 32.0); // 1*4 + 2*5 + 3*6 = 32
     }
 
+    #[test]
+    fn test_dot_product_simd_matches_fallback() {
+        // 771 components leave a remainder after both 4- and 8-wide chunks.
+        let a: Vec<f32> = (0..771).map(|i| (i as f32 * 0.37).sin()).collect();
+        let b: Vec<f32> = (0..771).map(|i| (i as f32 * 0.11).cos()).collect();
+        let expected = dot_product_fallback(&a, &b);
+        assert!((dot_product_simd(&a, &b) - expected).abs() < 1e-3);
+        assert_eq!(dot_product_simd(&a[..3], &b[..3]), dot_product_fallback(&a[..3], &b[..3]));
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_x86_dot_product_paths_agree() {
+        // Small integers keep every partial sum exact, so summation order
+        // cannot change the result. 771 components leave a remainder after
+        // 8- and 16-wide chunks.
+        let a: Vec<f32> = (0..771).map(|i| (i % 9) as f32 - 4.0).collect();
+        let b: Vec<f32> = (0..771).map(|i| (i % 7) as f32 - 3.0).collect();
+        let expected = dot_product_fallback(&a, &b);
+
+        if is_x86_feature_detected!("avx") {
+            // SAFETY: AVX support was verified just above.
+            assert_eq!(unsafe { dot_product_avx(&a, &b) }, expected);
+        }
+        if is_x86_feature_detected!("avx512f") {
+            // SAFETY: AVX-512F support was verified just above.
+            assert_eq!(unsafe { dot_product_avx512(&a, &b) }, expected);
+        }
+        assert_eq!(dot_product_simd(&a, &b), expected);
+    }
+
     #[test]
     fn test_l2_norm() {
         let v = vec![3.0, 4.0];
@@ -0,0 +1,218 @@
+//! Recommendation snapshots for catching ranking regressions
+//!
+//! A [`RecommendationSnapshot`] records the pattern IDs and final scores of a
+//! query's top recommendations, without descriptions, so it can be checked in
+//! as a JSON fixture. After changing ranker weights or scoring, re-running the
+//! query and calling [`RecommendationSnapshot::compare`] reports which
+//! recommendations disappeared, appeared or moved by more than a tolerance.
+//!
+//! `temporal-ai snapshot create` and `temporal-ai snapshot test` wrap this for
+//! the live database.
+
+use crate::ranker::Recommendation;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// Number of recommendations a snapshot keeps.
+pub const SNAPSHOT_SIZE: usize = 10;
+
+/// One recommendation in a snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// ID of the recommended pattern
+    pub pattern_id: String,
+    /// The recommendation's `final_score`
+    pub score: f32,
+}
+
+/// The top recommendations for a query at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecommendationSnapshot {
+    /// Query text the recommendations were produced for
+    pub query: String,
+    /// Recommendations in rank order, at most [`SNAPSHOT_SIZE`]
+    pub entries: Vec<SnapshotEntry>,
+}
+
+/// A difference between a stored snapshot and fresh recommendations
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotMismatch {
+    /// A pattern in the snapshot is no longer recommended
+    Missing {
+        /// ID of the pattern
+        pattern_id: String,
+    },
+    /// A pattern is recommended that the snapshot does not contain
+    Unexpected {
+        /// ID of the pattern
+        pattern_id: String,
+    },
+    /// A pattern's score moved by more than the tolerance
+    ScoreChanged {
+        /// ID of the pattern
+        pattern_id: String,
+        /// Score in the snapshot
+        expected: f32,
+        /// Score now
+        actual: f32,
+    },
+}
+
+impl fmt::Display for SnapshotMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { pattern_id } => write!(f, "{} is no longer recommended", pattern_id),
+            Self::Unexpected { pattern_id } => write!(f, "{} is newly recommended", pattern_id),
+            Self::ScoreChanged {
+                pattern_id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} scored {:.4}, expected {:.4}",
+                pattern_id, actual, expected
+            ),
+        }
+    }
+}
+
+impl RecommendationSnapshot {
+    /// Capture the first [`SNAPSHOT_SIZE`] of `recommendations` for `query`
+    pub fn new(query: impl Into<String>, recommendations: &[Recommendation]) -> Self {
+        Self {
+            query: query.into(),
+            entries: recommendations
+                .iter()
+                .take(SNAPSHOT_SIZE)
+                .map(|rec| SnapshotEntry {
+                    pattern_id: rec.pattern.id.clone(),
+                    score: rec.final_score,
+                })
+                .collect(),
+        }
+    }
+
+    /// Write the snapshot to `path` as pretty-printed JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`RecommendationSnapshot::save`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a snapshot.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// List the differences between this snapshot and `actual`
+    ///
+    /// The same pattern IDs must appear in both, in any order, and each score
+    /// may differ by at most `tolerance`. An empty result means `actual` matches.
+    pub fn compare(&self, actual: &RecommendationSnapshot, tolerance: f32) -> Vec<SnapshotMismatch> {
+        let mut mismatches = Vec::new();
+
+        for expected in &self.entries {
+            match actual
+                .entries
+                .iter()
+                .find(|entry| entry.pattern_id == expected.pattern_id)
+            {
+                None => mismatches.push(SnapshotMismatch::Missing {
+                    pattern_id: expected.pattern_id.clone(),
+                }),
+                Some(entry) if (entry.score - expected.score).abs() > tolerance => {
+                    mismatches.push(SnapshotMismatch::ScoreChanged {
+                        pattern_id: expected.pattern_id.clone(),
+                        expected: expected.score,
+                        actual: entry.score,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        for entry in &actual.entries {
+            if !self
+                .entries
+                .iter()
+                .any(|expected| expected.pattern_id == entry.pattern_id)
+            {
+                mismatches.push(SnapshotMismatch::Unexpected {
+                    pattern_id: entry.pattern_id.clone(),
+                });
+            }
+        }
+
+        mismatches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn snapshot(entries: &[(&str, f32)]) -> RecommendationSnapshot {
+        RecommendationSnapshot {
+            query: "add auth middleware".to_string(),
+            entries: entries
+                .iter()
+                .map(|(id, score)| SnapshotEntry {
+                    pattern_id: id.to_string(),
+                    score: *score,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_compare_within_tolerance() {
+        let expected = snapshot(&[("a", 0.9), ("b", 0.8)]);
+        let reordered = snapshot(&[("b", 0.83), ("a", 0.88)]);
+        assert!(expected.compare(&reordered, 0.05).is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_mismatches() {
+        let expected = snapshot(&[("a", 0.9), ("b", 0.8)]);
+        let actual = snapshot(&[("a", 0.7), ("c", 0.6)]);
+
+        assert_eq!(
+            expected.compare(&actual, 0.05),
+            vec![
+                SnapshotMismatch::ScoreChanged {
+                    pattern_id: "a".to_string(),
+                    expected: 0.9,
+                    actual: 0.7,
+                },
+                SnapshotMismatch::Missing {
+                    pattern_id: "b".to_string(),
+                },
+                SnapshotMismatch::Unexpected {
+                    pattern_id: "c".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("snapshot.json");
+        let original = snapshot(&[("a", 0.9)]);
+
+        original.save(&path)?;
+        assert_eq!(RecommendationSnapshot::load(&path)?, original);
+        Ok(())
+    }
+}
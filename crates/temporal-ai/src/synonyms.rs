@@ -0,0 +1,135 @@
+//! Tag synonyms for query expansion
+//!
+//! Patterns are tagged with whatever vocabulary their commit used, so a tag
+//! filter for `authentication` would miss patterns tagged `auth` or `jwt`.
+//! [`TagSynonyms`] groups interchangeable tags; [`SimilaritySearch`] expands each
+//! requested tag to its whole group when given one via
+//! [`SimilaritySearch::with_synonyms`].
+//!
+//! [`SimilaritySearch`]: crate::similarity::SimilaritySearch
+//! [`SimilaritySearch::with_synonyms`]: crate::similarity::SimilaritySearch::with_synonyms
+
+use crate::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Built-in groups of interchangeable tags
+const BUILT_IN_GROUPS: &[&[&str]] = &[
+    &["authentication", "auth", "jwt", "oauth", "login"],
+    &["authorization", "authz", "permissions", "rbac"],
+    &["database", "db", "storage", "sql"],
+    &["cache", "caching"],
+    &["config", "configuration", "settings"],
+    &["test", "tests", "testing"],
+    &["docs", "documentation"],
+    &["performance", "perf", "optimization"],
+    &["logging", "logs"],
+    &["tracing", "telemetry", "observability"],
+    &["deployment", "deploy", "release"],
+];
+
+/// Symmetric map from a tag to the tags that mean the same thing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagSynonyms {
+    /// Lowercased tag to its synonyms, excluding the tag itself
+    pub synonyms: HashMap<String, Vec<String>>,
+}
+
+impl Default for TagSynonyms {
+    /// The built-in synonym groups (e.g. `authentication`, `auth` and `jwt`)
+    fn default() -> Self {
+        let mut synonyms = Self::empty();
+        for group in BUILT_IN_GROUPS {
+            synonyms.add_group(group);
+        }
+        synonyms
+    }
+}
+
+impl TagSynonyms {
+    /// Create a synonym map without the built-in groups
+    pub fn empty() -> Self {
+        Self {
+            synonyms: HashMap::new(),
+        }
+    }
+
+    /// Load synonyms from a JSON object mapping a tag to its synonyms
+    ///
+    /// Each entry is treated as a group, so `{"k8s": ["kubernetes"]}` also makes
+    /// `kubernetes` expand to `k8s`. The built-in groups are not included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a JSON object of
+    /// string arrays.
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let entries: HashMap<String, Vec<String>> = serde_json::from_str(&json)?;
+
+        let mut synonyms = Self::empty();
+        for (tag, others) in entries {
+            let group: Vec<&str> = std::iter::once(tag.as_str())
+                .chain(others.iter().map(String::as_str))
+                .collect();
+            synonyms.add_group(&group);
+        }
+        Ok(synonyms)
+    }
+
+    /// Make every tag in `group` a synonym of every other
+    pub fn add_group(&mut self, group: &[&str]) {
+        let group: Vec<String> = group.iter().map(|tag| tag.to_lowercase()).collect();
+        for tag in &group {
+            let entry = self.synonyms.entry(tag.clone()).or_default();
+            for other in &group {
+                if other != tag && !entry.contains(other) {
+                    entry.push(other.clone());
+                }
+            }
+        }
+    }
+
+    /// Return `tag` followed by its synonyms
+    pub fn expand(&self, tag: &str) -> Vec<String> {
+        let tag = tag.to_lowercase();
+        let mut expanded = vec![tag.clone()];
+        if let Some(others) = self.synonyms.get(&tag) {
+            expanded.extend(others.iter().cloned());
+        }
+        expanded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_built_in_groups_are_symmetric() {
+        let synonyms = TagSynonyms::default();
+        assert_eq!(
+            synonyms.expand("Authentication"),
+            vec!["authentication", "auth", "jwt", "oauth", "login"]
+        );
+        assert!(synonyms.expand("jwt").contains(&"authentication".to_string()));
+        assert_eq!(synonyms.expand("rust"), vec!["rust"]);
+    }
+
+    #[test]
+    fn test_from_json_file() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("synonyms.json");
+        std::fs::write(&path, r#"{"k8s": ["kubernetes", "helm"]}"#)?;
+
+        let synonyms = TagSynonyms::from_json_file(&path)?;
+        assert_eq!(synonyms.expand("k8s"), vec!["k8s", "kubernetes", "helm"]);
+        assert_eq!(synonyms.expand("helm"), vec!["helm", "k8s", "kubernetes"]);
+        assert_eq!(synonyms.expand("auth"), vec!["auth"]);
+
+        std::fs::write(&path, "[1, 2]")?;
+        assert!(TagSynonyms::from_json_file(&path).is_err());
+        Ok(())
+    }
+}
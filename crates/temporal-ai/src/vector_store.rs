@@ -1,29 +1,154 @@
 //! Vector storage using redb embedded database
 
+use crate::import::PatternRecord;
 use crate::pattern_extractor::Pattern;
 use crate::schema::{
-    EmbeddingRecord, PerformanceMetrics, EMBEDDINGS, FILE_PATH_INDEX, METADATA, METRICS, TAG_INDEX,
+    EmbeddingRecord, IdStrategy, PerformanceMetrics, RefreshState, StorageMode,
+    CONTENT_HASH_INDEX, CO_AUTHOR_INDEX, COMMIT_SHA_INDEX, EMBEDDINGS, FILE_PATH_INDEX, METADATA,
+    METRICS, REFRESH_STATE, REFRESH_STATE_KEY, SCHEMA_VERSION_KEY, STORE_INFO, TAG_INDEX,
+    TTL_INDEX,
 };
-use crate::Result;
-use redb::{Database, ReadableTable, Table};
-use std::collections::HashSet;
-use std::path::Path;
+use crate::{Result, TemporalAIError, EMBEDDING_DIM};
+use chrono::Utc;
+use redb::backends::InMemoryBackend;
+use redb::{
+    Database, ReadTransaction, ReadableTable, ReadableTableMetadata, StorageBackend, Table,
+    TableDefinition, TableHandle, WriteTransaction,
+};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Statistics reported by [`VectorStore::warm_up`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WarmUpStats {
+    /// Number of embedding records touched
+    pub embeddings_read: usize,
+    /// Total size of the raw records in bytes
+    pub bytes_read: u64,
+    /// Wall-clock time spent reading
+    pub elapsed_ms: u64,
+}
+
+/// Direction for [`VectorStore::list_patterns_sorted_by_timestamp`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Oldest first
+    #[default]
+    Ascending,
+    /// Newest first
+    Descending,
+}
+
+/// The only part of a stored [`Pattern`] needed to sort by time
+#[derive(serde::Deserialize)]
+struct PatternTimestamp {
+    timestamp: i64,
+}
+
+/// First bytes of every file written by [`VectorStore::backup`]
+pub const BACKUP_MAGIC: &[u8; 8] = b"TAIBACKP";
+
+/// Version of the [`VectorStore::backup`] file layout
+pub const BACKUP_FORMAT_VERSION: u8 = 2;
+
+/// Number of tables stored in a backup, in the order they are written
+const BACKUP_TABLE_COUNT: u32 = 11;
+
+/// Schema version of databases created before the version was recorded
+const UNRECORDED_SCHEMA_VERSION: u8 = 1;
+
+/// Outcome of [`VectorStore::compact`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Size of the database file before compaction
+    pub original_size_bytes: u64,
+    /// Size of the rebuilt database file
+    pub compacted_size_bytes: u64,
+    /// `original_size_bytes - compacted_size_bytes`; negative if the copy grew
+    pub space_saved_bytes: i64,
+}
+
+/// Outcome of [`VectorStore::upsert`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertResult {
+    /// No pattern was stored under the ID, so it was inserted
+    Inserted,
+    /// An existing pattern was replaced and its index entries brought up to date
+    Updated,
+}
 
 /// Vector store for embeddings and metadata
+///
+/// Cloning is cheap and yields another handle to the same database, which lets
+/// reads be moved onto worker threads.
+#[derive(Clone)]
 pub struct VectorStore {
-    db: Database,
+    db: Arc<Database>,
+    path: Option<PathBuf>,
+    id_strategy: IdStrategy,
 }
 
 impl VectorStore {
     /// Open or create database
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent directory cannot be created or the file is not
+    /// a valid redb database.
     pub fn open(db_path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_id_strategy(db_path, IdStrategy::Sha256)
+    }
+
+    /// Open or create database, storing new patterns under keys chosen by `strategy`
+    ///
+    /// With [`IdStrategy::Ulid`], `insert` and `insert_batch` store each pattern
+    /// under [`Pattern::ulid_id`] (patterns whose ID is already a ULID keep it), so
+    /// [`VectorStore::list_patterns`] returns IDs in commit order. Existing SHA-256
+    /// keys are not touched; see [`VectorStore::migrate_ids_to_ulid`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent directory cannot be created or the file is not
+    /// a valid redb database.
+    pub fn open_with_id_strategy(db_path: impl AsRef<Path>, strategy: IdStrategy) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.as_ref().parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        let db = Database::create(db_path)?;
+        let db = Database::create(db_path.as_ref())?;
+        let mut store = Self::from_database(db)?;
+        store.path = Some(db_path.as_ref().to_path_buf());
+        store.id_strategy = strategy;
+        Ok(store)
+    }
+
+    /// Load a database image into memory
+    ///
+    /// `bytes` must be the contents of a redb file written by [`VectorStore::open`].
+    /// Nothing touches the filesystem, which makes this usable from `wasm32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid redb database image.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let backend = InMemoryBackend::new();
+        backend.set_len(bytes.len() as u64)?;
+        backend.write(0, bytes)?;
+
+        let db = Database::builder().create_with_backend(backend)?;
+        Self::from_database(db)
+    }
 
+    /// Wrap an already opened database, creating any missing tables
+    fn from_database(db: Database) -> Result<Self> {
         // Initialize tables
         let write_txn = db.begin_write()?;
         {
@@ -32,64 +157,306 @@ impl VectorStore {
             let _ = write_txn.open_table(METRICS)?;
             let _ = write_txn.open_table(FILE_PATH_INDEX)?;
             let _ = write_txn.open_table(TAG_INDEX)?;
+            let _ = write_txn.open_table(COMMIT_SHA_INDEX)?;
+            let _ = write_txn.open_table(CO_AUTHOR_INDEX)?;
+            let _ = write_txn.open_table(TTL_INDEX)?;
+            let _ = write_txn.open_table(CONTENT_HASH_INDEX)?;
+            let _ = write_txn.open_table(REFRESH_STATE)?;
+
+            // A new database holds records of the current schema version
+            let mut store_info = write_txn.open_table(STORE_INFO)?;
+            let is_new = write_txn.open_table(METADATA)?.is_empty()?;
+            if is_new && store_info.get(SCHEMA_VERSION_KEY)?.is_none() {
+                let version = crate::SCHEMA_VERSION.to_string();
+                store_info.insert(SCHEMA_VERSION_KEY, version.as_str())?;
+            }
         }
         write_txn.commit()?;
 
-        Ok(Self { db })
+        Ok(Self {
+            db: Arc::new(db),
+            path: None,
+            id_strategy: IdStrategy::default(),
+        })
+    }
+
+    /// The ID under which `insert` stores `pattern` with this store's `IdStrategy`
+    pub fn storage_id(&self, pattern: &Pattern) -> String {
+        self.keyed(pattern).id.clone()
+    }
+
+    /// Return `pattern` with its ID rewritten according to the store's `IdStrategy`
+    fn keyed<'p>(&self, pattern: &'p Pattern) -> Cow<'p, Pattern> {
+        match self.id_strategy {
+            IdStrategy::Ulid if !Pattern::is_ulid_id(&pattern.id) => Cow::Owned(Pattern {
+                id: pattern.ulid_id(),
+                ..pattern.clone()
+            }),
+            _ => Cow::Borrowed(pattern),
+        }
     }
 
     /// Insert pattern with embedding
-    pub fn insert(&self, pattern: &Pattern, embedding: Vec<f32>) -> Result<()> {
+    ///
+    /// Returns the ID the pattern is stored under. See
+    /// [`VectorStore::insert_with_mode`] for how content hashes are deduplicated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern cannot be serialized or the write fails.
+    pub fn insert(&self, pattern: &Pattern, embedding: Vec<f32>) -> Result<String> {
+        self.insert_with_mode(pattern, embedding, StorageMode::Float32)
+    }
+
+    /// Insert pattern with embedding, encoding the vector according to `mode`
+    ///
+    /// `StorageMode::Float16` halves the on-disk size of the embedding at the cost
+    /// of half-precision components. Reads detect the mode automatically.
+    ///
+    /// If `pattern.content_hash` is set and a pattern with a different ID already
+    /// owns that hash, nothing is written and the existing ID is returned, so the
+    /// same change indexed from several repositories is stored once. Otherwise
+    /// the ID the pattern was stored under is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern cannot be serialized or the write fails.
+    #[tracing::instrument(
+        name = "vector_store.insert",
+        skip_all,
+        fields(pattern_id = %pattern.id, embedding_dim = embedding.len())
+    )]
+    pub fn insert_with_mode(
+        &self,
+        pattern: &Pattern,
+        embedding: Vec<f32>,
+        mode: StorageMode,
+    ) -> Result<String> {
+        let pattern = self.keyed(pattern);
         let embedding_record = EmbeddingRecord::new(embedding);
 
         let write_txn = self.db.begin_write()?;
+        if let Some(hash) = &pattern.content_hash {
+            let existing = write_txn
+                .open_table(CONTENT_HASH_INDEX)?
+                .get(hash.as_str())?
+                .map(|id| id.value().to_string());
+            if let Some(existing) = existing.filter(|id| *id != pattern.id) {
+                write_txn.abort()?;
+                return Ok(existing);
+            }
+        }
+        Self::write_pattern(&write_txn, &pattern, &embedding_record, mode)?;
+        write_txn.commit()?;
 
-        {
-            // Store embedding
-            let mut embeddings_table = write_txn.open_table(EMBEDDINGS)?;
-            let embedding_bytes = rmp_serde::to_vec(&embedding_record)?;
-            embeddings_table.insert(pattern.id.as_str(), embedding_bytes.as_slice())?;
+        Ok(pattern.id.clone())
+    }
 
-            // Store metadata
-            let mut metadata_table = write_txn.open_table(METADATA)?;
-            let metadata_json = serde_json::to_string(pattern)?;
-            metadata_table.insert(pattern.id.as_str(), metadata_json.as_str())?;
+    /// Insert `pattern` if no embedding is stored under its ID yet
+    ///
+    /// The existence check and the insert run in one write transaction, so when
+    /// several threads or `refresh` runs index the same pattern, exactly one
+    /// insert wins and the stored record is never overwritten. Returns `true` if
+    /// the pattern was inserted and `false` if it already existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern cannot be serialized or the write fails.
+    pub fn insert_or_ignore(&self, pattern: &Pattern, embedding: Vec<f32>) -> Result<bool> {
+        let pattern = self.keyed(pattern);
+        let embedding_record = EmbeddingRecord::new(embedding);
 
-            // Initialize metrics
-            let mut metrics_table = write_txn.open_table(METRICS)?;
-            let metrics = PerformanceMetrics::new();
-            let metrics_json = serde_json::to_string(&metrics)?;
-            metrics_table.insert(pattern.id.as_str(), metrics_json.as_str())?;
+        let write_txn = self.db.begin_write()?;
+        let exists = write_txn
+            .open_table(EMBEDDINGS)?
+            .get(pattern.id.as_str())?
+            .is_some();
+        if exists {
+            write_txn.abort()?;
+            return Ok(false);
+        }
+
+        Self::write_pattern(&write_txn, &pattern, &embedding_record, StorageMode::Float32)?;
+        write_txn.commit()?;
+
+        Ok(true)
+    }
+
+    /// Insert `pattern`, or replace the pattern already stored under its ID
+    ///
+    /// When replacing, index entries for file paths, tags, co-authors, the commit
+    /// SHA and the content hash that the new version no longer has are removed,
+    /// and entries for the new values are added, all in one write transaction.
+    /// The stored performance metrics and TTL are kept. Unlike
+    /// [`VectorStore::insert`], the content hash is not used to deduplicate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored pattern cannot be decoded, the pattern
+    /// cannot be serialized, or the write fails.
+    pub fn upsert(&self, pattern: &Pattern, embedding: Vec<f32>) -> Result<UpsertResult> {
+        let pattern = self.keyed(pattern);
+        let pattern_id = pattern.id.as_str();
+        let embedding_record = EmbeddingRecord::new(embedding);
 
-            // Update file path index
+        let write_txn = self.db.begin_write()?;
+        let old = write_txn
+            .open_table(METADATA)?
+            .get(pattern_id)?
+            .map(|json| serde_json::from_str::<Pattern>(json.value()))
+            .transpose()?;
+        let old_metrics = write_txn
+            .open_table(METRICS)?
+            .get(pattern_id)?
+            .map(|json| json.value().to_string());
+
+        if let Some(old) = &old {
             let mut file_path_index = write_txn.open_table(FILE_PATH_INDEX)?;
-            for file_path in &pattern.file_paths {
-                Self::update_index_entry(
-                    &mut file_path_index,
-                    file_path.as_str(),
-                    pattern.id.as_str(),
-                )?;
-            }
+            Self::remove_stale_index_entries(
+                &mut file_path_index,
+                &old.file_paths,
+                &pattern.file_paths,
+                pattern_id,
+            )?;
 
-            // Update tag index
             let mut tag_index = write_txn.open_table(TAG_INDEX)?;
-            for tag in &pattern.tags {
-                Self::update_index_entry(&mut tag_index, tag.as_str(), pattern.id.as_str())?;
+            Self::remove_stale_index_entries(&mut tag_index, &old.tags, &pattern.tags, pattern_id)?;
+
+            let mut co_author_index = write_txn.open_table(CO_AUTHOR_INDEX)?;
+            Self::remove_stale_index_entries(
+                &mut co_author_index,
+                &old.co_authors,
+                &pattern.co_authors,
+                pattern_id,
+            )?;
+
+            if old.commit_sha != pattern.commit_sha {
+                let mut commit_index = write_txn.open_table(COMMIT_SHA_INDEX)?;
+                Self::remove_index_entry(&mut commit_index, old.commit_sha.as_str(), pattern_id)?;
+            }
+
+            let stale_hash = old
+                .content_hash
+                .as_ref()
+                .filter(|hash| pattern.content_hash.as_ref() != Some(*hash));
+            if let Some(hash) = stale_hash {
+                let mut content_hash_index = write_txn.open_table(CONTENT_HASH_INDEX)?;
+                let owned = content_hash_index
+                    .get(hash.as_str())?
+                    .is_some_and(|id| id.value() == pattern_id);
+                if owned {
+                    content_hash_index.remove(hash.as_str())?;
+                }
             }
         }
 
+        Self::write_pattern(&write_txn, &pattern, &embedding_record, StorageMode::Float32)?;
+        if let Some(metrics_json) = old_metrics {
+            write_txn
+                .open_table(METRICS)?
+                .insert(pattern_id, metrics_json.as_str())?;
+        }
         write_txn.commit()?;
 
+        Ok(if old.is_some() {
+            UpsertResult::Updated
+        } else {
+            UpsertResult::Inserted
+        })
+    }
+
+    /// Remove `pattern_id` from the entries of `old_keys` missing from `new_keys`
+    fn remove_stale_index_entries(
+        table: &mut Table<&str, &str>,
+        old_keys: &[String],
+        new_keys: &[String],
+        pattern_id: &str,
+    ) -> Result<()> {
+        for key in old_keys.iter().filter(|key| !new_keys.contains(key)) {
+            Self::remove_index_entry(table, key.as_str(), pattern_id)?;
+        }
+        Ok(())
+    }
+
+    /// Write a pattern's embedding, metadata, fresh metrics and index entries
+    fn write_pattern(
+        write_txn: &WriteTransaction,
+        pattern: &Pattern,
+        embedding_record: &EmbeddingRecord,
+        mode: StorageMode,
+    ) -> Result<()> {
+        // Store embedding
+        let mut embeddings_table = write_txn.open_table(EMBEDDINGS)?;
+        let embedding_bytes = embedding_record.encode(mode)?;
+        embeddings_table.insert(pattern.id.as_str(), embedding_bytes.as_slice())?;
+
+        // Store metadata
+        let mut metadata_table = write_txn.open_table(METADATA)?;
+        let metadata_json = serde_json::to_string(pattern)?;
+        metadata_table.insert(pattern.id.as_str(), metadata_json.as_str())?;
+
+        // Initialize metrics
+        let mut metrics_table = write_txn.open_table(METRICS)?;
+        let metrics = PerformanceMetrics::new();
+        let metrics_json = serde_json::to_string(&metrics)?;
+        metrics_table.insert(pattern.id.as_str(), metrics_json.as_str())?;
+
+        // Update file path index
+        let mut file_path_index = write_txn.open_table(FILE_PATH_INDEX)?;
+        for file_path in &pattern.file_paths {
+            Self::update_index_entry(
+                &mut file_path_index,
+                file_path.as_str(),
+                pattern.id.as_str(),
+            )?;
+        }
+
+        // Update tag index
+        let mut tag_index = write_txn.open_table(TAG_INDEX)?;
+        for tag in &pattern.tags {
+            Self::update_index_entry(&mut tag_index, tag.as_str(), pattern.id.as_str())?;
+        }
+
+        // Update commit index
+        let mut commit_index = write_txn.open_table(COMMIT_SHA_INDEX)?;
+        Self::update_index_entry(
+            &mut commit_index,
+            pattern.commit_sha.as_str(),
+            pattern.id.as_str(),
+        )?;
+
+        // Update co-author index
+        let mut co_author_index = write_txn.open_table(CO_AUTHOR_INDEX)?;
+        for email in &pattern.co_authors {
+            Self::update_index_entry(
+                &mut co_author_index,
+                email.as_str(),
+                pattern.id.as_str(),
+            )?;
+        }
+
+        // Claim the content hash unless another pattern already owns it
+        if let Some(hash) = &pattern.content_hash {
+            let mut content_hash_index = write_txn.open_table(CONTENT_HASH_INDEX)?;
+            if content_hash_index.get(hash.as_str())?.is_none() {
+                content_hash_index.insert(hash.as_str(), pattern.id.as_str())?;
+            }
+        }
+
         Ok(())
     }
 
     /// Retrieve embedding by pattern ID
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the stored record cannot be decoded.
     pub fn get_embedding(&self, pattern_id: &str) -> Result<Option<Vec<f32>>> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(EMBEDDINGS)?;
 
         if let Some(bytes) = table.get(pattern_id)? {
-            let record: EmbeddingRecord = rmp_serde::from_slice(bytes.value())?;
+            let record = EmbeddingRecord::decode(bytes.value())?;
             Ok(Some(record.vector))
         } else {
             Ok(None)
@@ -97,6 +464,10 @@ impl VectorStore {
     }
 
     /// Get pattern metadata by ID
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the stored pattern cannot be decoded.
     pub fn get_pattern(&self, pattern_id: &str) -> Result<Option<Pattern>> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(METADATA)?;
@@ -110,6 +481,10 @@ impl VectorStore {
     }
 
     /// Get performance metrics by ID
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the stored metrics cannot be decoded.
     pub fn get_metrics(&self, pattern_id: &str) -> Result<Option<PerformanceMetrics>> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(METRICS)?;
@@ -122,10 +497,28 @@ impl VectorStore {
         }
     }
     /// Fetch embedding and metadata together
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or a stored value cannot be decoded.
     pub fn get_embedding_and_pattern(
         &self,
         pattern_id: &str,
     ) -> Result<Option<(Vec<f32>, Pattern)>> {
+        Ok(self
+            .get_embedding_record_and_pattern(pattern_id)?
+            .map(|(record, pattern)| (record.vector, pattern)))
+    }
+
+    /// Fetch the full embedding record, including its stored norm, and metadata
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or a stored value cannot be decoded.
+    pub fn get_embedding_record_and_pattern(
+        &self,
+        pattern_id: &str,
+    ) -> Result<Option<(EmbeddingRecord, Pattern)>> {
         let read_txn = self.db.begin_read()?;
         let embeddings = read_txn.open_table(EMBEDDINGS)?;
         let metadata = read_txn.open_table(METADATA)?;
@@ -140,13 +533,60 @@ impl VectorStore {
             None => return Ok(None),
         };
 
-        let record: EmbeddingRecord = rmp_serde::from_slice(embedding_bytes.value())?;
+        let record = EmbeddingRecord::decode(embedding_bytes.value())?;
         let pattern: Pattern = serde_json::from_str(pattern_json.value())?;
 
-        Ok(Some((record.vector, pattern)))
+        Ok(Some((record, pattern)))
+    }
+
+    /// Read every stored embedding record with its metadata in one transaction
+    ///
+    /// Entries are returned in pattern ID order; embeddings without metadata are
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or a stored value cannot be decoded.
+    pub fn all_embedding_records_and_patterns(
+        &self,
+    ) -> Result<Vec<(String, EmbeddingRecord, Pattern)>> {
+        let mut entries = Vec::new();
+        self.for_each_embedding_record_and_pattern(|id, record, pattern| {
+            entries.push((id, record, pattern));
+        })?;
+        Ok(entries)
+    }
+
+    /// Visit every stored embedding record with its metadata in one transaction
+    ///
+    /// Like [`VectorStore::all_embedding_records_and_patterns`], but without
+    /// holding every entry in memory at once.
+    pub(crate) fn for_each_embedding_record_and_pattern(
+        &self,
+        mut visit: impl FnMut(String, EmbeddingRecord, Pattern),
+    ) -> Result<()> {
+        let read_txn = self.db.begin_read()?;
+        let embeddings = read_txn.open_table(EMBEDDINGS)?;
+        let metadata = read_txn.open_table(METADATA)?;
+
+        for item in embeddings.iter()? {
+            let (id, bytes) = item?;
+            let Some(pattern_json) = metadata.get(id.value())? else {
+                continue;
+            };
+            let record = EmbeddingRecord::decode(bytes.value())?;
+            let pattern: Pattern = serde_json::from_str(pattern_json.value())?;
+            visit(id.value().to_string(), record, pattern);
+        }
+
+        Ok(())
     }
 
     /// Get all pattern IDs (for full scan)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails.
     pub fn list_patterns(&self) -> Result<Vec<String>> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(METADATA)?;
@@ -162,7 +602,108 @@ impl VectorStore {
         Ok(pattern_ids)
     }
 
+    /// List up to `limit` pattern IDs after `cursor`, in ID order
+    ///
+    /// `cursor` is the last ID of the previous page, or `None` for the first
+    /// page. The returned cursor is the last ID of this page when more IDs
+    /// follow, and `None` once the end is reached. A `limit` of zero is treated
+    /// as one so that every call makes progress.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails.
+    pub fn list_patterns_paginated(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<String>, Option<String>)> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(METADATA)?;
+
+        let limit = limit.max(1);
+        let start = match cursor.as_deref() {
+            Some(last_seen) => Bound::Excluded(last_seen),
+            None => Bound::Unbounded,
+        };
+
+        let mut pattern_ids = Vec::with_capacity(limit);
+        let mut has_more = false;
+        for item in table.range::<&str>((start, Bound::Unbounded))? {
+            let (key, _) = item?;
+            if pattern_ids.len() == limit {
+                has_more = true;
+                break;
+            }
+            pattern_ids.push(key.value().to_string());
+        }
+
+        let next_cursor = if has_more { pattern_ids.last().cloned() } else { None };
+        Ok((pattern_ids, next_cursor))
+    }
+
+    /// List pattern IDs by commit timestamp, keeping at most `limit`
+    ///
+    /// Only the `timestamp` field of each stored pattern is deserialized. Patterns
+    /// with the same timestamp are ordered by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or a stored pattern cannot be decoded.
+    pub fn list_patterns_sorted_by_timestamp(
+        &self,
+        order: SortOrder,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(METADATA)?;
+
+        let mut entries = Vec::new();
+        for item in table.iter()? {
+            let (key, value) = item?;
+            let PatternTimestamp { timestamp } = serde_json::from_str(value.value())?;
+            entries.push((timestamp, key.value().to_string()));
+        }
+
+        entries.sort_unstable();
+        if order == SortOrder::Descending {
+            entries.reverse();
+        }
+
+        Ok(entries
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|(_, id)| id)
+            .collect())
+    }
+
+    /// Look up the timestamp of a commit from any pattern extracted from it
+    ///
+    /// `sha` may be abbreviated; the first pattern whose `commit_sha` starts with it
+    /// is used. Returns `None` if no stored pattern comes from that commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or a stored pattern cannot be decoded.
+    pub fn commit_timestamp(&self, sha: &str) -> Result<Option<i64>> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(METADATA)?;
+
+        for item in table.iter()? {
+            let (_, json) = item?;
+            let pattern: Pattern = serde_json::from_str(json.value())?;
+            if pattern.commit_sha.starts_with(sha) {
+                return Ok(Some(pattern.timestamp));
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Find patterns by file path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the index entry cannot be decoded.
     pub fn find_by_file_path(&self, file_path: &str) -> Result<Vec<String>> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(FILE_PATH_INDEX)?;
@@ -175,7 +716,35 @@ impl VectorStore {
         }
     }
 
+    /// Find patterns touching any file path that starts with `prefix`
+    ///
+    /// Scans `FILE_PATH_INDEX` from `prefix` onwards and returns the sorted union
+    /// of the matching entries' pattern IDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or an index entry cannot be decoded.
+    pub fn find_by_file_path_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(FILE_PATH_INDEX)?;
+
+        let mut pattern_ids = BTreeSet::new();
+        for item in table.range(prefix..)? {
+            let (key, value) = item?;
+            if !key.value().starts_with(prefix) {
+                break;
+            }
+            pattern_ids.extend(serde_json::from_str::<Vec<String>>(value.value())?);
+        }
+
+        Ok(pattern_ids.into_iter().collect())
+    }
+
     /// Find patterns by tag
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the index entry cannot be decoded.
     pub fn find_by_tag(&self, tag: &str) -> Result<Vec<String>> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(TAG_INDEX)?;
@@ -188,7 +757,137 @@ impl VectorStore {
         }
     }
 
+    /// Find patterns extracted from a commit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the index entry cannot be decoded.
+    pub fn find_by_commit_sha(&self, sha: &str) -> Result<Vec<String>> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(COMMIT_SHA_INDEX)?;
+
+        if let Some(json) = table.get(sha)? {
+            let pattern_ids: Vec<String> = serde_json::from_str(json.value())?;
+            Ok(pattern_ids)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Find the pattern that owns a content hash
+    ///
+    /// Returns the ID of the first pattern stored with `hash` as its
+    /// [`Pattern::content_hash`], if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails.
+    pub fn find_by_content_hash(&self, hash: &str) -> Result<Option<String>> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(CONTENT_HASH_INDEX)?;
+        Ok(table.get(hash)?.map(|id| id.value().to_string()))
+    }
+
+    /// Find patterns co-authored by an email address (case-insensitive)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the index entry cannot be decoded.
+    pub fn find_by_co_author(&self, email: &str) -> Result<Vec<String>> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(CO_AUTHOR_INDEX)?;
+
+        if let Some(json) = table.get(email.to_lowercase().as_str())? {
+            let pattern_ids: Vec<String> = serde_json::from_str(json.value())?;
+            Ok(pattern_ids)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Count patterns extracted from a commit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the index entry cannot be decoded.
+    pub fn count_by_commit_sha(&self, sha: &str) -> Result<usize> {
+        Ok(self.find_by_commit_sha(sha)?.len())
+    }
+
+    /// Link two patterns by adding each ID to the other's `related_ids`
+    ///
+    /// Both metadata entries are rewritten in a single write transaction. Linking
+    /// is idempotent, and linking a pattern to itself is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::PatternNotFound` if either ID is not stored, or an
+    /// error if the write fails.
+    pub fn link_patterns(&self, id1: &str, id2: &str) -> Result<()> {
+        if id1 == id2 {
+            return Ok(());
+        }
+
+        let write_txn = self.db.begin_write()?;
+
+        {
+            let mut metadata = write_txn.open_table(METADATA)?;
+
+            let mut first = Self::load_pattern(&metadata, id1)?;
+            let mut second = Self::load_pattern(&metadata, id2)?;
+
+            for (pattern, other) in [(&mut first, id2), (&mut second, id1)] {
+                if !pattern.related_ids.iter().any(|id| id == other) {
+                    pattern.related_ids.push(other.to_string());
+                }
+            }
+
+            let first_json = serde_json::to_string(&first)?;
+            let second_json = serde_json::to_string(&second)?;
+            metadata.insert(id1, first_json.as_str())?;
+            metadata.insert(id2, second_json.as_str())?;
+        }
+
+        write_txn.commit()?;
+
+        Ok(())
+    }
+
+    /// Read a pattern from the metadata table inside a write transaction
+    fn load_pattern(table: &Table<&str, &str>, pattern_id: &str) -> Result<Pattern> {
+        let json = table
+            .get(pattern_id)?
+            .ok_or_else(|| TemporalAIError::PatternNotFound(pattern_id.to_string()))?;
+        Ok(serde_json::from_str(json.value())?)
+    }
+
+    /// Fetch the patterns linked to `pattern_id`
+    ///
+    /// Related IDs that no longer resolve to a stored pattern are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or a stored pattern cannot be decoded.
+    pub fn get_related_patterns(&self, pattern_id: &str) -> Result<Vec<Pattern>> {
+        let pattern = self
+            .get_pattern(pattern_id)?
+            .ok_or_else(|| TemporalAIError::PatternNotFound(pattern_id.to_string()))?;
+
+        let mut related = Vec::with_capacity(pattern.related_ids.len());
+        for id in &pattern.related_ids {
+            if let Some(pattern) = self.get_pattern(id)? {
+                related.push(pattern);
+            }
+        }
+
+        Ok(related)
+    }
+
     /// Update performance metrics
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metrics cannot be serialized or the write fails.
     pub fn update_metrics(&self, pattern_id: &str, metrics: PerformanceMetrics) -> Result<()> {
         let write_txn = self.db.begin_write()?;
 
@@ -203,67 +902,86 @@ impl VectorStore {
         Ok(())
     }
 
-    fn update_index_entry(
-        table: &mut Table<&str, &str>,
-        key: &str,
-        pattern_id: &str,
-    ) -> Result<()> {
-        let (mut ids, is_new) = {
-            let existing = table.get(key)?;
-            if let Some(value) = existing {
-                (serde_json::from_str::<HashSet<String>>(value.value())?, false)
-            } else {
-                (HashSet::new(), true)
-            }
-        };
+    /// Read the state recorded by the last `refresh` run, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the stored state cannot be decoded.
+    pub fn get_refresh_state(&self) -> Result<Option<RefreshState>> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(REFRESH_STATE)?;
 
-        let inserted = ids.insert(pattern_id.to_string());
-        if inserted || is_new {
-            let mut sorted: Vec<_> = ids.into_iter().collect();
-            sorted.sort();
-            let updated = serde_json::to_string(&sorted)?;
-            table.insert(key, updated.as_str())?;
+        match table.get(REFRESH_STATE_KEY)? {
+            Some(json) => Ok(Some(serde_json::from_str(json.value())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Record the state of a finished `refresh` run, replacing the previous one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state cannot be serialized or the write fails.
+    pub fn set_refresh_state(&self, state: RefreshState) -> Result<()> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(REFRESH_STATE)?;
+            let json = serde_json::to_string(&state)?;
+            table.insert(REFRESH_STATE_KEY, json.as_str())?;
         }
+        write_txn.commit()?;
 
         Ok(())
     }
 
-    /// Batch insert for efficiency
-    pub fn insert_batch(&self, records: &[(Pattern, Vec<f32>)]) -> Result<()> {
+    /// Expire `pattern_id` at `expiry` (Unix seconds)
+    ///
+    /// Replaces any expiry set earlier. Expired patterns are removed by
+    /// [`VectorStore::prune_expired`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::PatternNotFound` if the pattern is not stored, or
+    /// an error if the write fails.
+    pub fn set_ttl(&self, pattern_id: &str, expiry: i64) -> Result<()> {
         let write_txn = self.db.begin_write()?;
 
         {
-            let mut embeddings_table = write_txn.open_table(EMBEDDINGS)?;
-            let mut metadata_table = write_txn.open_table(METADATA)?;
-            let mut metrics_table = write_txn.open_table(METRICS)?;
-            let mut file_path_index = write_txn.open_table(FILE_PATH_INDEX)?;
-            let mut tag_index = write_txn.open_table(TAG_INDEX)?;
-
-            for (pattern, embedding) in records {
-                let embedding_record = EmbeddingRecord::new(embedding.clone());
-                let embedding_bytes = rmp_serde::to_vec(&embedding_record)?;
-                embeddings_table.insert(pattern.id.as_str(), embedding_bytes.as_slice())?;
-
-                let metadata_json = serde_json::to_string(pattern)?;
-                metadata_table.insert(pattern.id.as_str(), metadata_json.as_str())?;
+            let metadata = write_txn.open_table(METADATA)?;
+            if metadata.get(pattern_id)?.is_none() {
+                return Err(TemporalAIError::PatternNotFound(pattern_id.to_string()));
+            }
 
-                let metrics = PerformanceMetrics::new();
-                let metrics_json = serde_json::to_string(&metrics)?;
-                metrics_table.insert(pattern.id.as_str(), metrics_json.as_str())?;
+            let mut ttl_index = write_txn.open_table(TTL_INDEX)?;
+            let mut entries = Vec::new();
+            for item in ttl_index.iter()? {
+                let (key, value) = item?;
+                entries.push((key.value(), value.value().to_string()));
+            }
 
-                // Update indexes
-                for file_path in &pattern.file_paths {
-                    Self::update_index_entry(
-                        &mut file_path_index,
-                        file_path.as_str(),
-                        pattern.id.as_str(),
-                    )?;
+            let mut ids_at_expiry = Vec::new();
+            for (key, value) in entries {
+                let mut ids: Vec<String> = serde_json::from_str(&value)?;
+                if key == expiry {
+                    ids_at_expiry = ids;
+                    continue;
                 }
-
-                for tag in &pattern.tags {
-                    Self::update_index_entry(&mut tag_index, tag.as_str(), pattern.id.as_str())?;
+                let before = ids.len();
+                ids.retain(|id| id != pattern_id);
+                if ids.is_empty() {
+                    ttl_index.remove(key)?;
+                } else if ids.len() != before {
+                    let updated = serde_json::to_string(&ids)?;
+                    ttl_index.insert(key, updated.as_str())?;
                 }
             }
+
+            if !ids_at_expiry.iter().any(|id| id == pattern_id) {
+                ids_at_expiry.push(pattern_id.to_string());
+                ids_at_expiry.sort();
+            }
+            let updated = serde_json::to_string(&ids_at_expiry)?;
+            ttl_index.insert(expiry, updated.as_str())?;
         }
 
         write_txn.commit()?;
@@ -271,33 +989,1072 @@ impl VectorStore {
         Ok(())
     }
 
-    /// Get database size estimate
-    pub fn size(&self) -> Result<u64> {
-        // Estimate based on pattern count
-        let patterns = self.list_patterns()?;
-        // Rough estimate: 3KB per embedding + 500B metadata
-        Ok(patterns.len() as u64 * 3500)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::tempdir;
+    /// Delete every pattern whose expiry has passed
+    ///
+    /// All expired patterns are removed together with their embeddings, metrics
+    /// and index entries in a single transaction. Returns the number of patterns
+    /// deleted. When nothing has expired no write transaction is started, so
+    /// this is cheap enough to call before every search.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a stored value cannot be decoded or the write fails;
+    /// nothing is deleted in that case.
+    pub fn prune_expired(&self) -> Result<usize> {
+        let now = Utc::now().timestamp();
 
-    fn create_test_pattern() -> Pattern {
-        Pattern {
-            id: "test123".to_string(),
-            description: "Test pattern".to_string(),
-            file_paths: vec!["src/main.rs".to_string()],
-            commit_sha: "abc123".to_string(),
-            timestamp: 1234567890,
-            tags: vec!["rust".to_string(), "test".to_string()],
+        {
+            let read_txn = self.db.begin_read()?;
+            let ttl_index = read_txn.open_table(TTL_INDEX)?;
+            if ttl_index.range(..=now)?.next().is_none() {
+                return Ok(0);
+            }
         }
-    }
 
-    #[test]
-    fn test_vector_store_create() -> Result<()> {
+        let write_txn = self.db.begin_write()?;
+        let mut pruned = 0;
+
+        {
+            let mut ttl_index = write_txn.open_table(TTL_INDEX)?;
+            let mut expired = Vec::new();
+            for item in ttl_index.range(..=now)? {
+                let (key, value) = item?;
+                expired.push((key.value(), value.value().to_string()));
+            }
+
+            for (key, value) in expired {
+                ttl_index.remove(key)?;
+                for pattern_id in serde_json::from_str::<Vec<String>>(&value)? {
+                    if Self::remove_pattern(&write_txn, &pattern_id)? {
+                        pruned += 1;
+                    }
+                }
+            }
+        }
+
+        write_txn.commit()?;
+
+        Ok(pruned)
+    }
+
+    /// Delete a pattern with its embedding, metrics and index entries
+    ///
+    /// The file path, tag, commit SHA, co-author, content hash and TTL index
+    /// entries of the pattern are updated in the same write transaction, and
+    /// index keys left without patterns are removed. Returns `false` if the
+    /// pattern was not stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a stored value cannot be decoded or the write fails;
+    /// nothing is deleted in that case.
+    pub fn delete_pattern(&self, pattern_id: &str) -> Result<bool> {
+        let write_txn = self.db.begin_write()?;
+
+        let deleted = Self::remove_pattern(&write_txn, pattern_id)?;
+        if deleted {
+            let mut ttl_index = write_txn.open_table(TTL_INDEX)?;
+            let mut entries = Vec::new();
+            for item in ttl_index.iter()? {
+                let (key, value) = item?;
+                entries.push((key.value(), value.value().to_string()));
+            }
+
+            for (key, value) in entries {
+                let mut ids: Vec<String> = serde_json::from_str(&value)?;
+                let before = ids.len();
+                ids.retain(|id| id != pattern_id);
+                if ids.is_empty() {
+                    ttl_index.remove(key)?;
+                } else if ids.len() != before {
+                    let updated = serde_json::to_string(&ids)?;
+                    ttl_index.insert(key, updated.as_str())?;
+                }
+            }
+        }
+
+        write_txn.commit()?;
+
+        Ok(deleted)
+    }
+
+    /// Remove a pattern and its index entries inside a write transaction
+    ///
+    /// Returns `false` if the pattern was not stored.
+    fn remove_pattern(write_txn: &WriteTransaction, pattern_id: &str) -> Result<bool> {
+        let pattern = {
+            let mut metadata = write_txn.open_table(METADATA)?;
+            let json = metadata.remove(pattern_id)?.map(|json| json.value().to_string());
+            match json {
+                Some(json) => serde_json::from_str::<Pattern>(&json)?,
+                None => return Ok(false),
+            }
+        };
+
+        write_txn.open_table(EMBEDDINGS)?.remove(pattern_id)?;
+        write_txn.open_table(METRICS)?.remove(pattern_id)?;
+
+        let mut file_path_index = write_txn.open_table(FILE_PATH_INDEX)?;
+        for file_path in &pattern.file_paths {
+            Self::remove_index_entry(&mut file_path_index, file_path.as_str(), pattern_id)?;
+        }
+
+        let mut tag_index = write_txn.open_table(TAG_INDEX)?;
+        for tag in &pattern.tags {
+            Self::remove_index_entry(&mut tag_index, tag.as_str(), pattern_id)?;
+        }
+
+        let mut commit_index = write_txn.open_table(COMMIT_SHA_INDEX)?;
+        Self::remove_index_entry(&mut commit_index, pattern.commit_sha.as_str(), pattern_id)?;
+
+        let mut co_author_index = write_txn.open_table(CO_AUTHOR_INDEX)?;
+        for email in &pattern.co_authors {
+            Self::remove_index_entry(&mut co_author_index, email.as_str(), pattern_id)?;
+        }
+
+        if let Some(hash) = &pattern.content_hash {
+            let mut content_hash_index = write_txn.open_table(CONTENT_HASH_INDEX)?;
+            let owned = content_hash_index
+                .get(hash.as_str())?
+                .is_some_and(|id| id.value() == pattern_id);
+            if owned {
+                content_hash_index.remove(hash.as_str())?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Remove patterns whose content duplicates an earlier pattern
+    ///
+    /// Patterns are grouped by [`Pattern::content_hash`], computing it for
+    /// patterns stored before the field existed. In each group the pattern with
+    /// the earliest timestamp (then lowest ID) is kept and the rest are deleted
+    /// with their embeddings, metrics and index entries. The content hash index
+    /// is rebuilt for the kept patterns. Runs in a single transaction and returns
+    /// the number of patterns removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a stored pattern cannot be decoded or the write fails;
+    /// nothing is removed in that case.
+    pub fn dedup_by_content_hash(&self) -> Result<usize> {
+        let write_txn = self.db.begin_write()?;
+        let mut removed = 0;
+
+        {
+            let mut patterns = Vec::new();
+            for item in write_txn.open_table(METADATA)?.iter()? {
+                let (_, json) = item?;
+                patterns.push(serde_json::from_str::<Pattern>(json.value())?);
+            }
+            patterns.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+
+            let mut owners: HashMap<String, String> = HashMap::new();
+            for mut pattern in patterns {
+                let hash = pattern
+                    .content_hash
+                    .clone()
+                    .unwrap_or_else(|| pattern.compute_content_hash());
+                if owners.contains_key(&hash) {
+                    if Self::remove_pattern(&write_txn, &pattern.id)? {
+                        removed += 1;
+                    }
+                    continue;
+                }
+
+                if pattern.content_hash.is_none() {
+                    pattern.content_hash = Some(hash.clone());
+                    let metadata_json = serde_json::to_string(&pattern)?;
+                    write_txn
+                        .open_table(METADATA)?
+                        .insert(pattern.id.as_str(), metadata_json.as_str())?;
+                }
+                owners.insert(hash, pattern.id);
+            }
+
+            let mut content_hash_index = write_txn.open_table(CONTENT_HASH_INDEX)?;
+            for (hash, pattern_id) in &owners {
+                content_hash_index.insert(hash.as_str(), pattern_id.as_str())?;
+            }
+        }
+
+        write_txn.commit()?;
+
+        Ok(removed)
+    }
+
+    fn remove_index_entry(
+        table: &mut Table<&str, &str>,
+        key: &str,
+        pattern_id: &str,
+    ) -> Result<()> {
+        let ids = match table.get(key)? {
+            Some(value) => serde_json::from_str::<Vec<String>>(value.value())?,
+            None => return Ok(()),
+        };
+
+        let remaining: Vec<_> = ids.into_iter().filter(|id| id != pattern_id).collect();
+        if remaining.is_empty() {
+            table.remove(key)?;
+        } else {
+            let updated = serde_json::to_string(&remaining)?;
+            table.insert(key, updated.as_str())?;
+        }
+
+        Ok(())
+    }
+
+    fn update_index_entry(
+        table: &mut Table<&str, &str>,
+        key: &str,
+        pattern_id: &str,
+    ) -> Result<()> {
+        let (mut ids, is_new) = {
+            let existing = table.get(key)?;
+            if let Some(value) = existing {
+                (serde_json::from_str::<HashSet<String>>(value.value())?, false)
+            } else {
+                (HashSet::new(), true)
+            }
+        };
+
+        let inserted = ids.insert(pattern_id.to_string());
+        if inserted || is_new {
+            let mut sorted: Vec<_> = ids.into_iter().collect();
+            sorted.sort();
+            let updated = serde_json::to_string(&sorted)?;
+            table.insert(key, updated.as_str())?;
+        }
+
+        Ok(())
+    }
+
+    /// Batch insert for efficiency
+    ///
+    /// Unlike [`VectorStore::insert`], patterns whose content hash is already
+    /// owned by another pattern are still written; use
+    /// [`VectorStore::dedup_by_content_hash`] to remove them afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern cannot be serialized or the write fails; no
+    /// record of the batch is written in that case.
+    pub fn insert_batch(&self, records: &[(Pattern, Vec<f32>)]) -> Result<()> {
+        let write_txn = self.db.begin_write()?;
+
+        {
+            let mut embeddings_table = write_txn.open_table(EMBEDDINGS)?;
+            let mut metadata_table = write_txn.open_table(METADATA)?;
+            let mut metrics_table = write_txn.open_table(METRICS)?;
+            let mut file_path_index = write_txn.open_table(FILE_PATH_INDEX)?;
+            let mut tag_index = write_txn.open_table(TAG_INDEX)?;
+            let mut commit_index = write_txn.open_table(COMMIT_SHA_INDEX)?;
+            let mut co_author_index = write_txn.open_table(CO_AUTHOR_INDEX)?;
+            let mut content_hash_index = write_txn.open_table(CONTENT_HASH_INDEX)?;
+
+            for (pattern, embedding) in records {
+                let pattern = self.keyed(pattern);
+                let embedding_record = EmbeddingRecord::new(embedding.clone());
+                let embedding_bytes = embedding_record.encode(StorageMode::Float32)?;
+                embeddings_table.insert(pattern.id.as_str(), embedding_bytes.as_slice())?;
+
+                let metadata_json = serde_json::to_string(pattern.as_ref())?;
+                metadata_table.insert(pattern.id.as_str(), metadata_json.as_str())?;
+
+                let metrics = PerformanceMetrics::new();
+                let metrics_json = serde_json::to_string(&metrics)?;
+                metrics_table.insert(pattern.id.as_str(), metrics_json.as_str())?;
+
+                // Update indexes
+                for file_path in &pattern.file_paths {
+                    Self::update_index_entry(
+                        &mut file_path_index,
+                        file_path.as_str(),
+                        pattern.id.as_str(),
+                    )?;
+                }
+
+                for tag in &pattern.tags {
+                    Self::update_index_entry(&mut tag_index, tag.as_str(), pattern.id.as_str())?;
+                }
+
+                Self::update_index_entry(
+                    &mut commit_index,
+                    pattern.commit_sha.as_str(),
+                    pattern.id.as_str(),
+                )?;
+
+                for email in &pattern.co_authors {
+                    Self::update_index_entry(
+                        &mut co_author_index,
+                        email.as_str(),
+                        pattern.id.as_str(),
+                    )?;
+                }
+
+                if let Some(hash) = &pattern.content_hash {
+                    if content_hash_index.get(hash.as_str())?.is_none() {
+                        content_hash_index.insert(hash.as_str(), pattern.id.as_str())?;
+                    }
+                }
+            }
+        }
+
+        write_txn.commit()?;
+
+        Ok(())
+    }
+
+    /// Read up to `max_embeddings` embedding records to pre-populate the OS page cache
+    ///
+    /// Records are scanned sequentially and only their raw bytes are touched, so no
+    /// deserialization cost is paid. Intended to run right after [`VectorStore::open`]
+    /// so the first query does not pay for cold page-cache misses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails.
+    pub fn warm_up(&self, max_embeddings: usize) -> Result<WarmUpStats> {
+        let start = Instant::now();
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(EMBEDDINGS)?;
+
+        let mut stats = WarmUpStats::default();
+        for item in table.iter()?.take(max_embeddings) {
+            let (_, value) = item?;
+            stats.embeddings_read += 1;
+            stats.bytes_read += value.value().len() as u64;
+        }
+
+        stats.elapsed_ms = start.elapsed().as_millis() as u64;
+        Ok(stats)
+    }
+
+    /// Build an HNSW index over every stored embedding
+    ///
+    /// See [`HnswIndex::build`](crate::hnsw::HnswIndex::build) for the meaning of
+    /// `m` and `ef_construction`. The index is a snapshot; patterns inserted
+    /// afterwards are not found until it is rebuilt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or a stored embedding cannot be decoded.
+    #[cfg(feature = "hnsw")]
+    pub fn build_hnsw_index(
+        &self,
+        m: usize,
+        ef_construction: usize,
+    ) -> Result<crate::hnsw::HnswIndex> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(EMBEDDINGS)?;
+
+        let mut entries = Vec::new();
+        for item in table.iter()? {
+            let (id, bytes) = item?;
+            let record = EmbeddingRecord::decode(bytes.value())?;
+            entries.push((id.value().to_string(), record.vector));
+        }
+
+        Ok(crate::hnsw::HnswIndex::build(entries, m, ef_construction))
+    }
+
+    /// Re-key every pattern stored under a SHA-256 ID to [`Pattern::ulid_id`]
+    ///
+    /// Embeddings, metadata and metrics move to the new key, and the file path,
+    /// tag, commit, co-author and TTL indexes as well as `related_ids` are
+    /// rewritten to match. Runs in a single transaction and is idempotent: patterns that already
+    /// have ULID keys are left alone. Returns the number of patterns re-keyed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a stored value cannot be decoded or the write fails;
+    /// nothing is changed in that case.
+    pub fn migrate_ids_to_ulid(&self) -> Result<usize> {
+        let write_txn = self.db.begin_write()?;
+        let migrated;
+
+        {
+            let mut metadata_table = write_txn.open_table(METADATA)?;
+            let mut patterns = Vec::new();
+            for item in metadata_table.iter()? {
+                let (_, value) = item?;
+                patterns.push(serde_json::from_str::<Pattern>(value.value())?);
+            }
+
+            let mapping: HashMap<String, String> = patterns
+                .iter()
+                .filter(|pattern| !Pattern::is_ulid_id(&pattern.id))
+                .map(|pattern| (pattern.id.clone(), pattern.ulid_id()))
+                .collect();
+            migrated = mapping.len();
+            if mapping.is_empty() {
+                return Ok(0);
+            }
+            let remap = |id: &str| mapping.get(id).cloned().unwrap_or_else(|| id.to_string());
+
+            for mut pattern in patterns {
+                let old_id = std::mem::take(&mut pattern.id);
+                pattern.id = remap(&old_id);
+                for related in &mut pattern.related_ids {
+                    *related = remap(related);
+                }
+                metadata_table.remove(old_id.as_str())?;
+                let metadata_json = serde_json::to_string(&pattern)?;
+                metadata_table.insert(pattern.id.as_str(), metadata_json.as_str())?;
+            }
+
+            let mut embeddings_table = write_txn.open_table(EMBEDDINGS)?;
+            let mut metrics_table = write_txn.open_table(METRICS)?;
+            for (old_id, new_id) in &mapping {
+                let embedding = embeddings_table
+                    .remove(old_id.as_str())?
+                    .map(|bytes| bytes.value().to_vec());
+                if let Some(bytes) = embedding {
+                    embeddings_table.insert(new_id.as_str(), bytes.as_slice())?;
+                }
+                let metrics = metrics_table
+                    .remove(old_id.as_str())?
+                    .map(|json| json.value().to_string());
+                if let Some(json) = metrics {
+                    metrics_table.insert(new_id.as_str(), json.as_str())?;
+                }
+            }
+
+            for index in [FILE_PATH_INDEX, TAG_INDEX, COMMIT_SHA_INDEX, CO_AUTHOR_INDEX] {
+                let mut table = write_txn.open_table(index)?;
+                let mut entries = Vec::new();
+                for item in table.iter()? {
+                    let (key, value) = item?;
+                    entries.push((key.value().to_string(), value.value().to_string()));
+                }
+                for (key, value) in entries {
+                    let mut ids: Vec<String> = serde_json::from_str::<Vec<String>>(&value)?
+                        .into_iter()
+                        .map(|id| remap(&id))
+                        .collect();
+                    ids.sort();
+                    let updated = serde_json::to_string(&ids)?;
+                    table.insert(key.as_str(), updated.as_str())?;
+                }
+            }
+
+            let mut content_hash_index = write_txn.open_table(CONTENT_HASH_INDEX)?;
+            let mut entries = Vec::new();
+            for item in content_hash_index.iter()? {
+                let (key, value) = item?;
+                entries.push((key.value().to_string(), remap(value.value())));
+            }
+            for (key, pattern_id) in entries {
+                content_hash_index.insert(key.as_str(), pattern_id.as_str())?;
+            }
+
+            let mut ttl_index = write_txn.open_table(TTL_INDEX)?;
+            let mut entries = Vec::new();
+            for item in ttl_index.iter()? {
+                let (key, value) = item?;
+                entries.push((key.value(), value.value().to_string()));
+            }
+            for (key, value) in entries {
+                let mut ids: Vec<String> = serde_json::from_str::<Vec<String>>(&value)?
+                    .into_iter()
+                    .map(|id| remap(&id))
+                    .collect();
+                ids.sort();
+                let updated = serde_json::to_string(&ids)?;
+                ttl_index.insert(key, updated.as_str())?;
+            }
+        }
+
+        write_txn.commit()?;
+        Ok(migrated)
+    }
+
+    /// Read the schema version recorded for the stored records
+    ///
+    /// New databases record [`crate::SCHEMA_VERSION`] when they are created and
+    /// [`VectorStore::migrate`] updates it. Returns `None` for databases created
+    /// before the version was recorded, which hold version 1 records.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or the stored value is not a version
+    /// number.
+    pub fn schema_version(&self) -> Result<Option<u8>> {
+        read_schema_version(&self.db.begin_read()?)
+    }
+
+    /// Whether the stored records are at a schema version other than `target_version`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schema version cannot be read.
+    pub fn needs_migration(&self, target_version: u8) -> Result<bool> {
+        let current = self.schema_version()?.unwrap_or(UNRECORDED_SCHEMA_VERSION);
+        Ok(current != target_version)
+    }
+
+    /// Rewrite every embedding record with `migrator` and record `target_version`
+    ///
+    /// Each record is decoded, passed to `migrator`, stamped with
+    /// `target_version` and written back in the storage mode it was read in.
+    /// All records and the new schema version are written in a single
+    /// transaction, so a failing migrator leaves the database untouched.
+    /// Records are migrated unconditionally; check
+    /// [`VectorStore::needs_migration`] first to avoid applying a migration
+    /// twice. Returns the number of records migrated.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from `migrator`, or an error if a record cannot
+    /// be decoded or encoded or the write fails.
+    pub fn migrate(
+        &self,
+        target_version: u8,
+        migrator: impl Fn(EmbeddingRecord) -> Result<EmbeddingRecord>,
+    ) -> Result<u64> {
+        let write_txn = self.db.begin_write()?;
+        let mut migrated = 0;
+        {
+            let mut embeddings = write_txn.open_table(EMBEDDINGS)?;
+            let mut entries = Vec::new();
+            for item in embeddings.iter()? {
+                let (id, bytes) = item?;
+                entries.push((id.value().to_string(), bytes.value().to_vec()));
+            }
+
+            for (id, bytes) in entries {
+                let mode = StorageMode::of_encoded(&bytes);
+                let mut record = migrator(EmbeddingRecord::decode(&bytes)?)?;
+                record.version = target_version;
+                embeddings.insert(id.as_str(), record.encode(mode)?.as_slice())?;
+                migrated += 1;
+            }
+
+            let version = target_version.to_string();
+            write_txn
+                .open_table(STORE_INFO)?
+                .insert(SCHEMA_VERSION_KEY, version.as_str())?;
+        }
+        write_txn.commit()?;
+
+        Ok(migrated)
+    }
+
+    /// Rebuild the database into a fresh file at `dest_path`
+    ///
+    /// redb keeps freed pages inside the file, so a database that had many
+    /// patterns removed never shrinks. Every table is copied into a new database
+    /// in a single transaction, which lays the btrees out without fragmentation.
+    /// `self` keeps serving from the original file; open `dest_path` to use the
+    /// compacted copy, or move it over the original once this handle is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dest_path` already exists or a read or write fails.
+    pub fn compact(&self, dest_path: impl AsRef<Path>) -> Result<CompactionReport> {
+        let dest_path = dest_path.as_ref();
+        if dest_path.exists() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} already exists", dest_path.display()),
+            )
+            .into());
+        }
+
+        let compacted = VectorStore::open(dest_path)?;
+        let read_txn = self.db.begin_read()?;
+        let write_txn = compacted.db.begin_write()?;
+        copy_table(&read_txn, &write_txn, EMBEDDINGS)?;
+        copy_table(&read_txn, &write_txn, METADATA)?;
+        copy_table(&read_txn, &write_txn, METRICS)?;
+        copy_table(&read_txn, &write_txn, FILE_PATH_INDEX)?;
+        copy_table(&read_txn, &write_txn, TAG_INDEX)?;
+        copy_table(&read_txn, &write_txn, COMMIT_SHA_INDEX)?;
+        copy_table(&read_txn, &write_txn, CO_AUTHOR_INDEX)?;
+        copy_table(&read_txn, &write_txn, TTL_INDEX)?;
+        copy_table(&read_txn, &write_txn, CONTENT_HASH_INDEX)?;
+        copy_table(&read_txn, &write_txn, REFRESH_STATE)?;
+        copy_table(&read_txn, &write_txn, STORE_INFO)?;
+        write_txn.commit()?;
+        drop(compacted);
+
+        let original_size_bytes = match &self.path {
+            Some(path) => std::fs::metadata(path)?.len(),
+            None => 0,
+        };
+        let compacted_size_bytes = std::fs::metadata(dest_path)?.len();
+
+        Ok(CompactionReport {
+            original_size_bytes,
+            compacted_size_bytes,
+            space_saved_bytes: original_size_bytes as i64 - compacted_size_bytes as i64,
+        })
+    }
+
+    /// Write a snapshot of every table to `dest_path`
+    ///
+    /// The snapshot is read in one read transaction, so writers are not blocked
+    /// and the file reflects a single point in time. The file starts with
+    /// [`BACKUP_MAGIC`], the backup format version, the database's
+    /// [schema version](VectorStore::schema_version) and the pattern count.
+    /// Each table follows as its name, its entry count and length-prefixed keys
+    /// and values, in key order. All integers are little endian, and a SHA-256
+    /// digest of the preceding bytes closes the file. The same database
+    /// contents always produce the same bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dest_path` already exists or a read or write fails;
+    /// a partially written file is removed.
+    pub fn backup(&self, dest_path: impl AsRef<Path>) -> Result<()> {
+        let dest_path = dest_path.as_ref();
+        let file = OpenOptions::new().write(true).create_new(true).open(dest_path)?;
+        let written = self.write_backup(file);
+        if written.is_err() {
+            let _ = std::fs::remove_file(dest_path);
+        }
+        written
+    }
+
+    /// Write the backup described in [`VectorStore::backup`] to `file`
+    fn write_backup(&self, file: File) -> Result<()> {
+        let mut out = HashingWriter {
+            inner: BufWriter::new(file),
+            hasher: Sha256::new(),
+        };
+
+        let read_txn = self.db.begin_read()?;
+        let record_count = read_txn.open_table(METADATA)?.len()?;
+        let schema_version =
+            read_schema_version(&read_txn)?.unwrap_or(UNRECORDED_SCHEMA_VERSION);
+        out.write_all(BACKUP_MAGIC)?;
+        out.write_all(&[BACKUP_FORMAT_VERSION, schema_version])?;
+        out.write_all(&record_count.to_le_bytes())?;
+        out.write_all(&BACKUP_TABLE_COUNT.to_le_bytes())?;
+
+        backup_table(&read_txn, &mut out, EMBEDDINGS)?;
+        backup_table(&read_txn, &mut out, METADATA)?;
+        backup_table(&read_txn, &mut out, METRICS)?;
+        backup_table(&read_txn, &mut out, FILE_PATH_INDEX)?;
+        backup_table(&read_txn, &mut out, TAG_INDEX)?;
+        backup_table(&read_txn, &mut out, COMMIT_SHA_INDEX)?;
+        backup_table(&read_txn, &mut out, CO_AUTHOR_INDEX)?;
+        backup_table(&read_txn, &mut out, TTL_INDEX)?;
+        backup_table(&read_txn, &mut out, CONTENT_HASH_INDEX)?;
+        backup_table(&read_txn, &mut out, REFRESH_STATE)?;
+        backup_table(&read_txn, &mut out, STORE_INFO)?;
+
+        let digest = out.hasher.finalize();
+        let mut inner = out.inner;
+        inner.write_all(&digest)?;
+        inner.into_inner().map_err(io::IntoInnerError::into_error)?.sync_all()?;
+
+        Ok(())
+    }
+
+    /// Recreate a database at `dest_path` from a file written by [`VectorStore::backup`]
+    ///
+    /// The checksum, header and table layout are validated before anything is
+    /// created, and the tables are then written in a single transaction. The
+    /// restored database keeps the backup's schema version, so an older backup
+    /// can be brought up to date with [`VectorStore::migrate`]. If restoring
+    /// fails, `dest_path` is removed again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::ChecksumMismatch` if the file is corrupt or
+    /// truncated, an `InvalidData` I/O error if it is not a backup or was written
+    /// for a newer schema version than [`crate::SCHEMA_VERSION`], or an error if
+    /// `dest_path` already exists or a read or write fails.
+    pub fn restore(src_path: impl AsRef<Path>, dest_path: impl AsRef<Path>) -> Result<Self> {
+        let src_path = src_path.as_ref();
+        let dest_path = dest_path.as_ref();
+        if dest_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", dest_path.display()),
+            )
+            .into());
+        }
+
+        let body_len = verify_backup_checksum(src_path)?;
+        let mut input = BufReader::new(File::open(src_path)?).take(body_len);
+        let header = read_backup_header(&mut input)?;
+
+        let store = Self::open(dest_path)?;
+        let restored = restore_tables(&store, &mut input, &header);
+        match restored {
+            Ok(()) => Ok(store),
+            Err(err) => {
+                drop(store);
+                let _ = std::fs::remove_file(dest_path);
+                Err(err)
+            }
+        }
+    }
+
+    /// Write every pattern as newline-delimited JSON to `writer`
+    ///
+    /// Each line is a [`PatternRecord`] holding the pattern, its embedding and
+    /// its metrics, in pattern ID order, so the output can be streamed into
+    /// [`VectorStore::import_json`] or the `import` command on another machine.
+    /// All records come from one read transaction. Returns the number of
+    /// records written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails, a stored value cannot be decoded, or
+    /// writing to `writer` fails.
+    pub fn export_json(&self, writer: &mut impl Write) -> Result<u64> {
+        let read_txn = self.db.begin_read()?;
+        let metadata = read_txn.open_table(METADATA)?;
+        let embeddings = read_txn.open_table(EMBEDDINGS)?;
+        let metrics = read_txn.open_table(METRICS)?;
+
+        let mut written = 0;
+        for item in metadata.iter()? {
+            let (id, pattern_json) = item?;
+            let Some(embedding_bytes) = embeddings.get(id.value())? else {
+                continue;
+            };
+            let record = PatternRecord {
+                pattern: serde_json::from_str(pattern_json.value())?,
+                embedding: EmbeddingRecord::decode(embedding_bytes.value())?.vector,
+                metrics: match metrics.get(id.value())? {
+                    Some(json) => Some(serde_json::from_str(json.value())?),
+                    None => None,
+                },
+            };
+            serde_json::to_writer(&mut *writer, &record)?;
+            writer.write_all(b"\n")?;
+            written += 1;
+        }
+        writer.flush()?;
+
+        Ok(written)
+    }
+
+    /// Load newline-delimited JSON written by [`VectorStore::export_json`]
+    ///
+    /// Records are written with [`VectorStore::insert_batch`] in batches, and
+    /// the metrics they carry are restored. Patterns whose IDs already exist
+    /// are overwritten. Blank lines are ignored. Returns the number of records
+    /// imported. Use [`crate::import_jsonl_with_embeddings`] to skip existing
+    /// patterns and malformed lines instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemporalAIError::SerializationError` naming the line if a line
+    /// is not a valid record, `TemporalAIError::DimensionMismatch` if an
+    /// embedding does not have [`EMBEDDING_DIM`] components, or an error if
+    /// reading or a database write fails. Batches written before the error are
+    /// kept.
+    pub fn import_json(&self, reader: &mut impl Read) -> Result<u64> {
+        let mut imported = 0;
+        let mut batch = Vec::with_capacity(crate::import::IMPORT_BATCH_SIZE);
+
+        for (index, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: PatternRecord = serde_json::from_str(&line).map_err(|err| {
+                TemporalAIError::SerializationError(format!("line {}: {}", index + 1, err))
+            })?;
+            if record.embedding.len() != EMBEDDING_DIM {
+                return Err(TemporalAIError::DimensionMismatch {
+                    expected: EMBEDDING_DIM,
+                    actual: record.embedding.len(),
+                });
+            }
+
+            batch.push(record);
+            if batch.len() >= crate::import::IMPORT_BATCH_SIZE {
+                imported += crate::import::flush_batch(self, &mut batch)? as u64;
+            }
+        }
+        imported += crate::import::flush_batch(self, &mut batch)? as u64;
+
+        Ok(imported)
+    }
+
+    /// Count stored patterns without reading their IDs
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails.
+    pub fn count(&self) -> Result<u64> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(METADATA)?;
+        Ok(table.len()?)
+    }
+
+    /// Get the size of the database file in bytes
+    ///
+    /// This includes index tables and pages redb keeps free for reuse; see
+    /// [`VectorStore::compact`]. Stores loaded with [`VectorStore::from_bytes`]
+    /// have no file and return an estimate of 3500 bytes per pattern instead.
+    /// Use [`VectorStore::count`] for the number of patterns.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file metadata or the pattern count cannot be read.
+    pub fn size(&self) -> Result<u64> {
+        match &self.path {
+            Some(path) => Ok(std::fs::metadata(path)?.len()),
+            // Rough estimate: 3KB per embedding + 500B metadata
+            None => Ok(self.count()? * 3500),
+        }
+    }
+}
+
+/// Copy every row of `definition` from `source` into `dest`
+fn copy_table<K: redb::Key + 'static, V: redb::Value + 'static>(
+    source: &ReadTransaction,
+    dest: &WriteTransaction,
+    definition: TableDefinition<K, V>,
+) -> Result<()> {
+    let from = source.open_table(definition)?;
+    let mut to = dest.open_table(definition)?;
+    for item in from.iter()? {
+        let (key, value) = item?;
+        to.insert(key.value(), value.value())?;
+    }
+    Ok(())
+}
+
+/// Read the schema version recorded in `STORE_INFO`, if any
+fn read_schema_version(read_txn: &ReadTransaction) -> Result<Option<u8>> {
+    let table = read_txn.open_table(STORE_INFO)?;
+    let Some(value) = table.get(SCHEMA_VERSION_KEY)? else {
+        return Ok(None);
+    };
+    let version = value.value().parse().map_err(|_| {
+        TemporalAIError::SerializationError(format!("Invalid schema version: {}", value.value()))
+    })?;
+    Ok(Some(version))
+}
+
+/// Writer that hashes everything passed through it
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// `InvalidData` error for a file that is not a usable backup
+fn invalid_backup(message: impl Into<String>) -> TemporalAIError {
+    io::Error::new(io::ErrorKind::InvalidData, message.into()).into()
+}
+
+/// Write one table as its name, entry count and length-prefixed entries
+fn backup_table<K: redb::Key + 'static, V: redb::Value + 'static>(
+    read_txn: &ReadTransaction,
+    out: &mut impl Write,
+    definition: TableDefinition<K, V>,
+) -> Result<()> {
+    let table = read_txn.open_table(definition)?;
+    write_backup_bytes(out, definition.name().as_bytes())?;
+    out.write_all(&table.len()?.to_le_bytes())?;
+    for item in table.iter()? {
+        let (key, value) = item?;
+        write_backup_bytes(out, K::as_bytes(&key.value()).as_ref())?;
+        write_backup_bytes(out, V::as_bytes(&value.value()).as_ref())?;
+    }
+    Ok(())
+}
+
+fn write_backup_bytes(out: &mut impl Write, bytes: &[u8]) -> Result<()> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| invalid_backup("entry larger than 4 GiB cannot be backed up"))?;
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(bytes)?;
+    Ok(())
+}
+
+/// Check the trailing SHA-256 digest and return the length of the data it covers
+fn verify_backup_checksum(src_path: &Path) -> Result<u64> {
+    let mut file = File::open(src_path)?;
+    let digest_len = Sha256::output_size() as u64;
+    let body_len = file
+        .metadata()?
+        .len()
+        .checked_sub(digest_len)
+        .ok_or_else(|| invalid_backup("file is too short to be a backup"))?;
+
+    let mut hasher = Sha256::new();
+    io::copy(&mut (&mut file).take(body_len), &mut hasher)?;
+    let mut expected = vec![0; digest_len as usize];
+    file.read_exact(&mut expected)?;
+
+    let actual = hasher.finalize();
+    if actual[..] != expected[..] {
+        return Err(TemporalAIError::ChecksumMismatch {
+            expected: hex_digest(&expected),
+            actual: hex_digest(&actual),
+        });
+    }
+    Ok(body_len)
+}
+
+fn hex_digest(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Fields of a backup header that restoring needs
+struct BackupHeader {
+    schema_version: u8,
+    record_count: u64,
+}
+
+/// Validate the header of a backup
+fn read_backup_header(input: &mut impl Read) -> Result<BackupHeader> {
+    let mut magic = [0; 8];
+    input.read_exact(&mut magic)?;
+    if &magic != BACKUP_MAGIC {
+        return Err(invalid_backup("not a temporal-ai backup"));
+    }
+
+    let mut versions = [0; 2];
+    input.read_exact(&mut versions)?;
+    let [format_version, schema_version] = versions;
+    if format_version != BACKUP_FORMAT_VERSION {
+        return Err(invalid_backup(format!(
+            "unsupported backup format version {}",
+            format_version
+        )));
+    }
+    if schema_version > crate::SCHEMA_VERSION {
+        return Err(invalid_backup(format!(
+            "backup has schema version {}, newer than {}",
+            schema_version,
+            crate::SCHEMA_VERSION
+        )));
+    }
+
+    let record_count = read_backup_u64(input)?;
+    let mut table_count = [0; 4];
+    input.read_exact(&mut table_count)?;
+    if u32::from_le_bytes(table_count) != BACKUP_TABLE_COUNT {
+        return Err(invalid_backup("unexpected number of tables"));
+    }
+    Ok(BackupHeader {
+        schema_version,
+        record_count,
+    })
+}
+
+/// Read every table in backup order into `store` and commit
+fn restore_tables(store: &VectorStore, input: &mut impl Read, header: &BackupHeader) -> Result<()> {
+    let write_txn = store.db.begin_write()?;
+    restore_table(&write_txn, input, EMBEDDINGS)?;
+    let restored_count = restore_table(&write_txn, input, METADATA)?;
+    restore_table(&write_txn, input, METRICS)?;
+    restore_table(&write_txn, input, FILE_PATH_INDEX)?;
+    restore_table(&write_txn, input, TAG_INDEX)?;
+    restore_table(&write_txn, input, COMMIT_SHA_INDEX)?;
+    restore_table(&write_txn, input, CO_AUTHOR_INDEX)?;
+    restore_table(&write_txn, input, TTL_INDEX)?;
+    restore_table(&write_txn, input, CONTENT_HASH_INDEX)?;
+    restore_table(&write_txn, input, REFRESH_STATE)?;
+    restore_table(&write_txn, input, STORE_INFO)?;
+
+    if restored_count != header.record_count {
+        return Err(invalid_backup(format!(
+            "header records {} patterns, found {}",
+            header.record_count, restored_count
+        )));
+    }
+    if input.read(&mut [0])? != 0 {
+        return Err(invalid_backup("unexpected data after the last table"));
+    }
+
+    // The destination recorded the current version when it was created
+    let version = header.schema_version.to_string();
+    write_txn
+        .open_table(STORE_INFO)?
+        .insert(SCHEMA_VERSION_KEY, version.as_str())?;
+
+    write_txn.commit()?;
+    Ok(())
+}
+
+/// Read one table written by [`backup_table`] and return its entry count
+fn restore_table<K: redb::Key + 'static, V: redb::Value + 'static>(
+    write_txn: &WriteTransaction,
+    input: &mut impl Read,
+    definition: TableDefinition<K, V>,
+) -> Result<u64> {
+    let name = read_backup_bytes(input)?;
+    if name != definition.name().as_bytes() {
+        return Err(invalid_backup(format!(
+            "expected table {}, found {}",
+            definition.name(),
+            String::from_utf8_lossy(&name)
+        )));
+    }
+
+    let mut table = write_txn.open_table(definition)?;
+    let count = read_backup_u64(input)?;
+    for _ in 0..count {
+        let key = read_backup_bytes(input)?;
+        let value = read_backup_bytes(input)?;
+        table.insert(K::from_bytes(&key), V::from_bytes(&value))?;
+    }
+    Ok(count)
+}
+
+fn read_backup_u64(input: &mut impl Read) -> Result<u64> {
+    let mut bytes = [0; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_backup_bytes(input: &mut impl Read) -> Result<Vec<u8>> {
+    let mut len = [0; 4];
+    input.read_exact(&mut len)?;
+    let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn create_test_pattern() -> Pattern {
+        Pattern {
+            id: "test123".to_string(),
+            description: "Test pattern".to_string(),
+            file_paths: vec!["src/main.rs".to_string()],
+            commit_sha: "abc123".to_string(),
+            timestamp: 1234567890,
+            tags: vec!["rust".to_string(), "test".to_string()],
+            related_ids: Vec::new(),
+            co_authors: Vec::new(),
+            pr_number: None,
+            content_hash: None,
+            lines_added: 0,
+            lines_deleted: 0,
+        }
+    }
+
+    #[test]
+    fn test_vector_store_create() -> Result<()> {
         let dir = tempdir()?;
         let db_path = dir.path().join("test.redb");
         let _store = VectorStore::open(&db_path)?;
@@ -306,6 +2063,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_from_bytes_loads_database_image() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        {
+            let store = VectorStore::open(&db_path)?;
+            store.insert(&create_test_pattern(), vec![0.1; 768])?;
+        }
+
+        let bytes = std::fs::read(&db_path)?;
+        let store = VectorStore::from_bytes(&bytes)?;
+
+        assert_eq!(store.list_patterns()?, vec!["test123".to_string()]);
+        assert!(store.get_embedding("test123")?.is_some());
+        Ok(())
+    }
+
     #[test]
     fn test_insert_and_retrieve() -> Result<()> {
         let dir = tempdir()?;
@@ -317,69 +2091,186 @@ mod tests {
 
         store.insert(&pattern, embedding.clone())?;
 
-        let retrieved_embedding = store.get_embedding(&pattern.id)?;
-        assert!(retrieved_embedding.is_some());
-        assert_eq!(retrieved_embedding.unwrap().len(), 768);
+        let retrieved_embedding = store.get_embedding(&pattern.id)?;
+        assert!(retrieved_embedding.is_some());
+        assert_eq!(retrieved_embedding.unwrap().len(), 768);
+
+        let retrieved_pattern = store.get_pattern(&pattern.id)?;
+        assert!(retrieved_pattern.is_some());
+        assert_eq!(retrieved_pattern.unwrap().id, pattern.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_patterns() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        let pattern1 = create_test_pattern();
+        let pattern2 = Pattern {
+            id: "test456".to_string(),
+            ..create_test_pattern()
+        };
+
+        store.insert(&pattern1, vec![0.1; 768])?;
+        store.insert(&pattern2, vec![0.2; 768])?;
+
+        let patterns = store.list_patterns()?;
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns.contains(&pattern1.id));
+        assert!(patterns.contains(&pattern2.id));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_by_tag() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        let pattern = create_test_pattern();
+        store.insert(&pattern, vec![0.1; 768])?;
+
+        let rust_patterns = store.find_by_tag("rust")?;
+        assert_eq!(rust_patterns.len(), 1);
+        assert_eq!(rust_patterns[0], pattern.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_by_file_path_index() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        let mut pattern = create_test_pattern();
+        pattern.id = "file-path-pattern".to_string();
+        pattern.file_paths = vec!["src/lib.rs".to_string()];
+        store.insert(&pattern, vec![0.1; 768])?;
+
+        let matches = store.find_by_file_path("src/lib.rs")?;
+        assert_eq!(matches, vec![pattern.id]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_and_count_by_commit_sha() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        let pattern1 = create_test_pattern();
+        let pattern2 = Pattern {
+            id: "test456".to_string(),
+            ..create_test_pattern()
+        };
+        let other = Pattern {
+            id: "test789".to_string(),
+            commit_sha: "def456".to_string(),
+            ..create_test_pattern()
+        };
+
+        store.insert(&pattern1, vec![0.1; 768])?;
+        store.insert_batch(&[(pattern2.clone(), vec![0.2; 768]), (other, vec![0.3; 768])])?;
+        // Re-inserting the same pattern must not double count
+        store.insert(&pattern1, vec![0.1; 768])?;
 
-        let retrieved_pattern = store.get_pattern(&pattern.id)?;
-        assert!(retrieved_pattern.is_some());
-        assert_eq!(retrieved_pattern.unwrap().id, pattern.id);
+        assert_eq!(store.count_by_commit_sha("abc123")?, 2);
+        assert_eq!(
+            store.find_by_commit_sha("abc123")?,
+            vec![pattern1.id, pattern2.id]
+        );
+        assert_eq!(store.count_by_commit_sha("def456")?, 1);
+        assert_eq!(store.count_by_commit_sha("missing")?, 0);
 
         Ok(())
     }
 
     #[test]
-    fn test_list_patterns() -> Result<()> {
+    fn test_find_by_co_author() -> Result<()> {
         let dir = tempdir()?;
         let db_path = dir.path().join("test.redb");
         let store = VectorStore::open(&db_path)?;
 
-        let pattern1 = create_test_pattern();
+        let pattern1 = Pattern {
+            co_authors: vec!["jane@example.com".to_string(), "john@example.com".to_string()],
+            ..create_test_pattern()
+        };
         let pattern2 = Pattern {
             id: "test456".to_string(),
+            co_authors: vec!["jane@example.com".to_string()],
             ..create_test_pattern()
         };
 
         store.insert(&pattern1, vec![0.1; 768])?;
-        store.insert(&pattern2, vec![0.2; 768])?;
+        store.insert_batch(&[(pattern2.clone(), vec![0.2; 768])])?;
 
-        let patterns = store.list_patterns()?;
-        assert_eq!(patterns.len(), 2);
-        assert!(patterns.contains(&pattern1.id));
-        assert!(patterns.contains(&pattern2.id));
+        assert_eq!(
+            store.find_by_co_author("Jane@Example.com")?,
+            vec![pattern1.id.clone(), pattern2.id]
+        );
+        assert_eq!(store.find_by_co_author("john@example.com")?, vec![pattern1.id]);
+        assert!(store.find_by_co_author("nobody@example.com")?.is_empty());
 
         Ok(())
     }
 
     #[test]
-    fn test_find_by_tag() -> Result<()> {
+    fn test_link_and_get_related_patterns() -> Result<()> {
         let dir = tempdir()?;
         let db_path = dir.path().join("test.redb");
         let store = VectorStore::open(&db_path)?;
 
-        let pattern = create_test_pattern();
-        store.insert(&pattern, vec![0.1; 768])?;
+        let first = create_test_pattern();
+        let second = Pattern {
+            id: "test456".to_string(),
+            ..create_test_pattern()
+        };
+        store.insert(&first, vec![0.1; 768])?;
+        store.insert(&second, vec![0.2; 768])?;
 
-        let rust_patterns = store.find_by_tag("rust")?;
-        assert_eq!(rust_patterns.len(), 1);
-        assert_eq!(rust_patterns[0], pattern.id);
+        store.link_patterns(&first.id, &second.id)?;
+        // Linking again must not duplicate the IDs
+        store.link_patterns(&second.id, &first.id)?;
+
+        assert_eq!(store.get_pattern(&first.id)?.unwrap().related_ids, vec!["test456"]);
+        assert_eq!(store.get_pattern(&second.id)?.unwrap().related_ids, vec!["test123"]);
+
+        let related = store.get_related_patterns(&first.id)?;
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].id, second.id);
+
+        assert!(matches!(
+            store.link_patterns(&first.id, "missing"),
+            Err(TemporalAIError::PatternNotFound(_))
+        ));
 
         Ok(())
     }
 
     #[test]
-    fn test_find_by_file_path_index() -> Result<()> {
+    fn test_insert_float16_mode() -> Result<()> {
         let dir = tempdir()?;
         let db_path = dir.path().join("test.redb");
         let store = VectorStore::open(&db_path)?;
 
-        let mut pattern = create_test_pattern();
-        pattern.id = "file-path-pattern".to_string();
-        pattern.file_paths = vec!["src/lib.rs".to_string()];
-        store.insert(&pattern, vec![0.1; 768])?;
+        let pattern = create_test_pattern();
+        let embedding: Vec<f32> = (0..768).map(|i| (i as f32 / 768.0) - 0.5).collect();
+        store.insert_with_mode(&pattern, embedding.clone(), StorageMode::Float16)?;
 
-        let matches = store.find_by_file_path("src/lib.rs")?;
-        assert_eq!(matches, vec![pattern.id]);
+        let stored = store.get_embedding(&pattern.id)?.unwrap();
+        assert_eq!(stored.len(), 768);
+        for (original, decoded) in embedding.iter().zip(&stored) {
+            assert!((original - decoded).abs() < 0.001);
+        }
+
+        let (_, stored_pattern) = store.get_embedding_and_pattern(&pattern.id)?.unwrap();
+        assert_eq!(stored_pattern.id, pattern.id);
 
         Ok(())
     }
@@ -417,6 +2308,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_warm_up_reads_embeddings() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        for id in ["a", "b", "c"] {
+            let pattern = Pattern {
+                id: id.to_string(),
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![0.1; 768])?;
+        }
+
+        let stats = store.warm_up(2)?;
+        assert_eq!(stats.embeddings_read, 2);
+        assert!(stats.bytes_read > 2 * 768 * 4);
+
+        let stats = store.warm_up(usize::MAX)?;
+        assert_eq!(stats.embeddings_read, 3);
+
+        Ok(())
+    }
+
     #[test]
     fn test_metrics_update() -> Result<()> {
         let dir = tempdir()?;
@@ -437,4 +2352,724 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_list_patterns_sorted_by_timestamp() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        for (id, timestamp) in [("aaa", 300), ("bbb", 100), ("ccc", 200)] {
+            let pattern = Pattern {
+                id: id.to_string(),
+                timestamp,
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![0.1; 768])?;
+        }
+
+        assert_eq!(
+            store.list_patterns_sorted_by_timestamp(SortOrder::Ascending, None)?,
+            vec!["bbb", "ccc", "aaa"]
+        );
+        assert_eq!(
+            store.list_patterns_sorted_by_timestamp(SortOrder::Descending, Some(2))?,
+            vec!["aaa", "ccc"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ulid_strategy_lists_patterns_chronologically() -> Result<()> {
+        let dir = tempdir()?;
+        let store =
+            VectorStore::open_with_id_strategy(dir.path().join("test.redb"), IdStrategy::Ulid)?;
+
+        // Inserted newest first; the original IDs sort in the opposite order.
+        let patterns = [
+            ("aaa", 1_700_000_300),
+            ("bbb", 1_700_000_200),
+            ("ccc", 1_700_000_100),
+        ];
+        for (id, timestamp) in patterns {
+            let pattern = Pattern {
+                id: id.to_string(),
+                timestamp,
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![0.1; 768])?;
+        }
+
+        let ids = store.list_patterns()?;
+        assert!(ids.iter().all(|id| Pattern::is_ulid_id(id)));
+        let timestamps: Vec<i64> = ids
+            .iter()
+            .map(|id| store.get_pattern(id).map(|p| p.unwrap().timestamp))
+            .collect::<Result<_>>()?;
+        assert_eq!(timestamps, vec![1_700_000_100, 1_700_000_200, 1_700_000_300]);
+
+        // Re-inserting the same pattern keeps a single record.
+        let first = Pattern {
+            id: "ccc".to_string(),
+            timestamp: 1_700_000_100,
+            ..create_test_pattern()
+        };
+        store.insert(&first, vec![0.2; 768])?;
+        assert_eq!(store.list_patterns()?.len(), 3);
+        assert_eq!(
+            store.get_embedding(&store.storage_id(&first))?.unwrap(),
+            vec![0.2; 768]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_ids_to_ulid() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        let first = create_test_pattern();
+        let second = Pattern {
+            id: "test456".to_string(),
+            ..create_test_pattern()
+        };
+        store.insert(&first, vec![0.1; 768])?;
+        store.insert(&second, vec![0.2; 768])?;
+        store.link_patterns(&first.id, &second.id)?;
+        let mut metrics = PerformanceMetrics::new();
+        metrics.usage_count = 5;
+        store.update_metrics(&first.id, metrics)?;
+
+        assert_eq!(store.migrate_ids_to_ulid()?, 2);
+        assert_eq!(store.migrate_ids_to_ulid()?, 0);
+
+        let new_first = first.ulid_id();
+        let new_second = second.ulid_id();
+        assert!(store.get_pattern(&first.id)?.is_none());
+        assert_eq!(store.get_embedding(&new_first)?.unwrap(), vec![0.1; 768]);
+        assert_eq!(store.get_metrics(&new_first)?.unwrap().usage_count, 5);
+        assert_eq!(
+            store.get_pattern(&new_first)?.unwrap().related_ids,
+            vec![new_second.clone()]
+        );
+
+        let mut expected = vec![new_first, new_second];
+        expected.sort();
+        assert_eq!(store.find_by_tag("rust")?, expected);
+        assert_eq!(store.find_by_commit_sha("abc123")?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_copies_all_tables() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        for i in 0..20 {
+            let pattern = Pattern {
+                id: format!("pattern{}", i),
+                co_authors: vec!["pair@example.com".to_string()],
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![i as f32 / 20.0; 768])?;
+        }
+        let mut metrics = PerformanceMetrics::new();
+        metrics.usage_count = 3;
+        store.update_metrics("pattern7", metrics)?;
+
+        let dest = dir.path().join("compacted.redb");
+        let report = store.compact(&dest)?;
+        assert!(report.original_size_bytes > 0);
+        assert!(report.compacted_size_bytes > 0);
+        assert_eq!(
+            report.space_saved_bytes,
+            report.original_size_bytes as i64 - report.compacted_size_bytes as i64
+        );
+
+        let compacted = VectorStore::open(&dest)?;
+        assert_eq!(compacted.list_patterns()?, store.list_patterns()?);
+        assert_eq!(compacted.get_embedding("pattern5")?, store.get_embedding("pattern5")?);
+        assert_eq!(compacted.get_metrics("pattern7")?.unwrap().usage_count, 3);
+        assert_eq!(compacted.find_by_tag("rust")?.len(), 20);
+        assert_eq!(compacted.find_by_co_author("pair@example.com")?.len(), 20);
+
+        assert!(store.compact(&dest).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_by_file_path_prefix() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        for (id, path) in [
+            ("p1", "crates/temporal-ai/src/lib.rs"),
+            ("p2", "crates/temporal-ai/Cargo.toml"),
+            ("p3", "crates/temporal-ai-cli/src/main.rs"),
+            ("p4", "crates/vibepro-observe/src/lib.rs"),
+        ] {
+            let pattern = Pattern {
+                id: id.to_string(),
+                file_paths: vec![path.to_string()],
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![0.1; 768])?;
+        }
+
+        assert_eq!(store.find_by_file_path_prefix("crates/temporal-ai/")?, vec!["p1", "p2"]);
+        assert_eq!(store.find_by_file_path_prefix("crates/temporal-ai")?, vec!["p1", "p2", "p3"]);
+        assert_eq!(store.find_by_file_path_prefix("crates/")?.len(), 4);
+        assert!(store.find_by_file_path_prefix("docs/")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_expired_removes_pattern_and_index_entries() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        let now = Utc::now().timestamp();
+
+        let expired = Pattern {
+            id: "expired".to_string(),
+            co_authors: vec!["pair@example.com".to_string()],
+            ..create_test_pattern()
+        };
+        let kept = Pattern {
+            id: "kept".to_string(),
+            ..create_test_pattern()
+        };
+        store.insert(&expired, vec![0.1; 768])?;
+        store.insert(&kept, vec![0.2; 768])?;
+
+        store.set_ttl("expired", now + 3600)?;
+        store.set_ttl("kept", now + 3600)?;
+        assert_eq!(store.prune_expired()?, 0);
+
+        // A later call replaces the earlier expiry.
+        store.set_ttl("expired", now - 1)?;
+        assert_eq!(store.prune_expired()?, 1);
+
+        assert_eq!(store.list_patterns()?, vec!["kept"]);
+        assert!(store.get_embedding("expired")?.is_none());
+        assert!(store.get_metrics("expired")?.is_none());
+        assert_eq!(store.find_by_tag("rust")?, vec!["kept"]);
+        assert_eq!(store.find_by_commit_sha("abc123")?, vec!["kept"]);
+        assert!(store.find_by_co_author("pair@example.com")?.is_empty());
+        assert_eq!(store.prune_expired()?, 0);
+
+        assert!(matches!(
+            store.set_ttl("missing", now),
+            Err(TemporalAIError::PatternNotFound(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upsert_updates_indexes_when_file_paths_change() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let original = Pattern {
+            file_paths: vec!["src/old.rs".to_string(), "src/kept.rs".to_string()],
+            tags: vec!["rust".to_string(), "stale".to_string()],
+            ..create_test_pattern()
+        };
+        assert_eq!(store.upsert(&original, vec![0.1; 768])?, UpsertResult::Inserted);
+        let metrics = PerformanceMetrics {
+            usage_count: 7,
+            ..PerformanceMetrics::new()
+        };
+        store.update_metrics("test123", metrics)?;
+
+        let updated = Pattern {
+            file_paths: vec!["src/kept.rs".to_string(), "src/new.rs".to_string()],
+            tags: vec!["rust".to_string(), "fresh".to_string()],
+            commit_sha: "def456".to_string(),
+            ..create_test_pattern()
+        };
+        assert_eq!(store.upsert(&updated, vec![0.2; 768])?, UpsertResult::Updated);
+
+        assert!(store.find_by_file_path("src/old.rs")?.is_empty());
+        assert_eq!(store.find_by_file_path("src/kept.rs")?, vec!["test123"]);
+        assert_eq!(store.find_by_file_path("src/new.rs")?, vec!["test123"]);
+        assert!(store.find_by_tag("stale")?.is_empty());
+        assert_eq!(store.find_by_tag("fresh")?, vec!["test123"]);
+        assert_eq!(store.find_by_tag("rust")?, vec!["test123"]);
+        assert!(store.find_by_commit_sha("abc123")?.is_empty());
+        assert_eq!(store.find_by_commit_sha("def456")?, vec!["test123"]);
+
+        let read_txn = store.db.begin_read()?;
+        assert!(read_txn.open_table(FILE_PATH_INDEX)?.get("src/old.rs")?.is_none());
+        drop(read_txn);
+
+        let stored = store.get_pattern("test123")?.expect("pattern stored");
+        assert_eq!(stored.file_paths, updated.file_paths);
+        assert_eq!(store.get_embedding("test123")?, Some(vec![0.2; 768]));
+        assert_eq!(store.get_metrics("test123")?.map(|m| m.usage_count), Some(7));
+        assert_eq!(store.count()?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_and_restore_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        for i in 0..5 {
+            let pattern = Pattern {
+                id: format!("pattern{}", i),
+                co_authors: vec!["pair@example.com".to_string()],
+                content_hash: Some(format!("hash{}", i)),
+                lines_added: 0,
+                lines_deleted: 0,
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![i as f32; 768])?;
+        }
+        let metrics = PerformanceMetrics {
+            usage_count: 3,
+            ..PerformanceMetrics::new()
+        };
+        store.update_metrics("pattern2", metrics)?;
+        store.set_ttl("pattern4", Utc::now().timestamp() + 3600)?;
+
+        let backup_path = dir.path().join("store.backup");
+        store.backup(&backup_path)?;
+        assert!(store.backup(&backup_path).is_err());
+
+        // Unchanged contents produce identical bytes.
+        let second_path = dir.path().join("second.backup");
+        store.backup(&second_path)?;
+        assert_eq!(std::fs::read(&backup_path)?, std::fs::read(&second_path)?);
+
+        let restored = VectorStore::restore(&backup_path, dir.path().join("restored.redb"))?;
+        assert_eq!(restored.list_patterns()?, store.list_patterns()?);
+        assert_eq!(restored.get_embedding("pattern3")?, Some(vec![3.0; 768]));
+        let pattern = restored.get_pattern("pattern1")?.unwrap();
+        assert_eq!(pattern.content_hash.as_deref(), Some("hash1"));
+        assert_eq!(pattern.co_authors, vec!["pair@example.com"]);
+        assert_eq!(restored.get_metrics("pattern2")?.unwrap().usage_count, 3);
+        assert_eq!(restored.find_by_tag("rust")?.len(), 5);
+        assert_eq!(restored.find_by_co_author("pair@example.com")?.len(), 5);
+        assert_eq!(restored.schema_version()?, Some(crate::SCHEMA_VERSION));
+
+        let read_txn = restored.db.begin_read()?;
+        assert_eq!(read_txn.open_table(TTL_INDEX)?.len()?, 1);
+        assert_eq!(
+            read_txn.open_table(CONTENT_HASH_INDEX)?.get("hash0")?.unwrap().value(),
+            "pattern0"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_rejects_invalid_backups() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        store.insert(&create_test_pattern(), vec![0.1; 768])?;
+
+        let backup_path = dir.path().join("store.backup");
+        store.backup(&backup_path)?;
+        let bytes = std::fs::read(&backup_path)?;
+
+        let existing = dir.path().join("test.redb");
+        assert!(VectorStore::restore(&backup_path, &existing).is_err());
+
+        let mut corrupt = bytes.clone();
+        corrupt[40] ^= 0xff;
+        let corrupt_path = dir.path().join("corrupt.backup");
+        std::fs::write(&corrupt_path, &corrupt)?;
+        let dest = dir.path().join("restored.redb");
+        assert!(matches!(
+            VectorStore::restore(&corrupt_path, &dest),
+            Err(TemporalAIError::ChecksumMismatch { .. })
+        ));
+        assert!(!dest.exists());
+
+        let truncated_path = dir.path().join("truncated.backup");
+        std::fs::write(&truncated_path, &bytes[..bytes.len() / 2])?;
+        assert!(VectorStore::restore(&truncated_path, &dest).is_err());
+
+        // A file with a valid digest but no backup header is rejected.
+        let body = b"not a backup at all";
+        let mut foreign = body.to_vec();
+        foreign.extend_from_slice(&Sha256::digest(body));
+        let foreign_path = dir.path().join("foreign.backup");
+        std::fs::write(&foreign_path, &foreign)?;
+        match VectorStore::restore(&foreign_path, &dest) {
+            Err(TemporalAIError::IoError(err)) => {
+                assert_eq!(err.kind(), io::ErrorKind::InvalidData)
+            }
+            other => panic!("expected InvalidData, got {:?}", other.map(|_| ())),
+        }
+        assert!(!dest.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_import_json_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("source.redb"))?;
+        for i in 0..3 {
+            let pattern = Pattern {
+                id: format!("pattern{}", i),
+                tags: vec!["rust".to_string(), format!("tag{}", i)],
+                pr_number: Some(40 + i),
+                ..create_test_pattern()
+            };
+            let mut embedding = vec![0.25; 768];
+            embedding[i as usize] = -1.5;
+            store.insert(&pattern, embedding)?;
+        }
+        let metrics = PerformanceMetrics {
+            usage_count: 4,
+            avg_relevance_score: 0.75,
+            ..PerformanceMetrics::new()
+        };
+        store.update_metrics("pattern1", metrics)?;
+
+        let mut exported = Vec::new();
+        assert_eq!(store.export_json(&mut exported)?, 3);
+        assert_eq!(exported.iter().filter(|&&b| b == b'\n').count(), 3);
+
+        let imported = VectorStore::open(dir.path().join("dest.redb"))?;
+        assert_eq!(imported.import_json(&mut exported.as_slice())?, 3);
+        assert_eq!(imported.find_by_tag("tag2")?, vec!["pattern2"]);
+        assert_eq!(imported.get_metrics("pattern1")?.unwrap().usage_count, 4);
+
+        let mut reexported = Vec::new();
+        imported.export_json(&mut reexported)?;
+        assert_eq!(String::from_utf8(reexported).unwrap(), String::from_utf8(exported).unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_json_rejects_invalid_lines() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let valid = serde_json::to_string(&PatternRecord {
+            pattern: create_test_pattern(),
+            embedding: vec![0.1; 768],
+            metrics: None,
+        })?;
+        let input = format!("{}\n\nnot json\n", valid);
+        match store.import_json(&mut input.as_bytes()) {
+            Err(TemporalAIError::SerializationError(message)) => {
+                assert!(message.starts_with("line 3:"))
+            }
+            other => panic!("expected SerializationError, got {:?}", other),
+        }
+
+        let short = serde_json::to_string(&PatternRecord {
+            pattern: create_test_pattern(),
+            embedding: vec![0.1; 3],
+            metrics: None,
+        })?;
+        assert!(matches!(
+            store.import_json(&mut short.as_bytes()),
+            Err(TemporalAIError::DimensionMismatch {
+                expected: 768,
+                actual: 3
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_reports_file_size() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        let store = VectorStore::open(&db_path)?;
+
+        for i in 0..20 {
+            let pattern = Pattern {
+                id: format!("pattern{}", i),
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![0.1; 768])?;
+        }
+        let size = store.size()?;
+        assert_eq!(size, std::fs::metadata(&db_path)?.len());
+        // 20 float32 embeddings alone take 60 KiB.
+        assert!(size > 20 * 768 * 4);
+        drop(store);
+
+        let in_memory = VectorStore::from_bytes(&std::fs::read(&db_path)?)?;
+        assert_eq!(in_memory.size()?, 20 * 3500);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_database_records_schema_version() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        assert_eq!(store.schema_version()?, Some(crate::SCHEMA_VERSION));
+        assert!(!store.needs_migration(crate::SCHEMA_VERSION)?);
+        assert!(store.needs_migration(crate::SCHEMA_VERSION + 1)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_rewrites_records_and_schema_version() -> Result<()> {
+        let dir = tempdir()?;
+        let db_path = dir.path().join("test.redb");
+        {
+            let store = VectorStore::open(&db_path)?;
+            store.insert(&create_test_pattern(), vec![0.5; 768])?;
+            let half = Pattern {
+                id: "half".to_string(),
+                ..create_test_pattern()
+            };
+            store.insert_with_mode(&half, vec![0.25; 768], StorageMode::Float16)?;
+
+            // Simulate a database from before the version was recorded
+            let write_txn = store.db.begin_write()?;
+            write_txn.open_table(STORE_INFO)?.remove(SCHEMA_VERSION_KEY)?;
+            write_txn.commit()?;
+        }
+
+        // Reopening a database that has patterns does not record a version
+        let store = VectorStore::open(&db_path)?;
+        assert_eq!(store.schema_version()?, None);
+        assert!(!store.needs_migration(1)?);
+        assert!(store.needs_migration(2)?);
+
+        let failed = store.migrate(2, |_| {
+            Err(TemporalAIError::InferenceError("model unavailable".to_string()))
+        });
+        assert!(failed.is_err());
+        assert_eq!(store.schema_version()?, None);
+        assert_eq!(store.get_embedding("test123")?, Some(vec![0.5; 768]));
+
+        let migrated = store.migrate(2, |mut record| {
+            record.vector.iter_mut().for_each(|x| *x *= 2.0);
+            record.norm *= 2.0;
+            Ok(record)
+        })?;
+        assert_eq!(migrated, 2);
+        assert_eq!(store.schema_version()?, Some(2));
+        assert!(!store.needs_migration(2)?);
+
+        let (record, _) = store.get_embedding_record_and_pattern("test123")?.unwrap();
+        assert_eq!(record.vector, vec![1.0; 768]);
+        assert_eq!(record.version, 2);
+
+        let read_txn = store.db.begin_read()?;
+        let half = read_txn.open_table(EMBEDDINGS)?.get("half")?.unwrap();
+        assert_eq!(StorageMode::of_encoded(half.value()), StorageMode::Float16);
+        assert_eq!(EmbeddingRecord::decode(half.value())?.vector, vec![0.5; 768]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        assert_eq!(store.count()?, 0);
+
+        for id in ["p1", "p2", "p3"] {
+            let pattern = Pattern {
+                id: id.to_string(),
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![0.1; 768])?;
+        }
+        assert_eq!(store.count()?, 3);
+
+        store.delete_pattern("p2")?;
+        assert_eq!(store.count()?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_patterns_paginated() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        for id in ["p1", "p2", "p3", "p4", "p5"] {
+            let pattern = Pattern {
+                id: id.to_string(),
+                ..create_test_pattern()
+            };
+            store.insert(&pattern, vec![0.1; 768])?;
+        }
+
+        let (page, cursor) = store.list_patterns_paginated(None, 2)?;
+        assert_eq!(page, vec!["p1", "p2"]);
+        assert_eq!(cursor.as_deref(), Some("p2"));
+
+        let (page, cursor) = store.list_patterns_paginated(cursor, 2)?;
+        assert_eq!(page, vec!["p3", "p4"]);
+
+        let (page, cursor) = store.list_patterns_paginated(cursor, 2)?;
+        assert_eq!(page, vec!["p5"]);
+        assert!(cursor.is_none());
+
+        let (page, cursor) = store.list_patterns_paginated(None, 5)?;
+        assert_eq!(page.len(), 5);
+        assert!(cursor.is_none());
+
+        let (page, _) = store.list_patterns_paginated(Some("p4".to_string()), 0)?;
+        assert_eq!(page, vec!["p5"]);
+
+        let empty = VectorStore::open(dir.path().join("empty.redb"))?;
+        assert_eq!(empty.list_patterns_paginated(None, 10)?, (Vec::new(), None));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_pattern_cleans_up_indexes() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let deleted = Pattern {
+            id: "deleted".to_string(),
+            file_paths: vec!["src/only_deleted.rs".to_string(), "src/shared.rs".to_string()],
+            tags: vec!["rust".to_string(), "only-deleted".to_string()],
+            ..create_test_pattern()
+        };
+        let kept = Pattern {
+            id: "kept".to_string(),
+            file_paths: vec!["src/shared.rs".to_string()],
+            ..create_test_pattern()
+        };
+        store.insert(&deleted, vec![0.1; 768])?;
+        store.insert(&kept, vec![0.2; 768])?;
+        store.set_ttl("deleted", Utc::now().timestamp() + 3600)?;
+
+        assert!(store.delete_pattern("deleted")?);
+
+        assert_eq!(store.list_patterns()?, vec!["kept"]);
+        assert!(store.get_embedding("deleted")?.is_none());
+        assert!(store.get_metrics("deleted")?.is_none());
+        assert!(store.find_by_file_path("src/only_deleted.rs")?.is_empty());
+        assert_eq!(store.find_by_file_path("src/shared.rs")?, vec!["kept"]);
+        assert!(store.find_by_tag("only-deleted")?.is_empty());
+        assert_eq!(store.find_by_tag("rust")?, vec!["kept"]);
+        assert_eq!(store.find_by_commit_sha("abc123")?, vec!["kept"]);
+
+        let read_txn = store.db.begin_read()?;
+        assert!(read_txn.open_table(FILE_PATH_INDEX)?.get("src/only_deleted.rs")?.is_none());
+        assert!(read_txn.open_table(TAG_INDEX)?.get("only-deleted")?.is_none());
+        assert_eq!(read_txn.open_table(TTL_INDEX)?.iter()?.count(), 0);
+        drop(read_txn);
+
+        assert!(!store.delete_pattern("deleted")?);
+        assert!(!store.delete_pattern("missing")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_deduplicates_by_content_hash() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        let hashed = |id: &str, commit_sha: &str| {
+            let mut pattern = Pattern {
+                id: id.to_string(),
+                commit_sha: commit_sha.to_string(),
+                ..create_test_pattern()
+            };
+            pattern.content_hash = Some(pattern.compute_content_hash());
+            pattern
+        };
+        let original = hashed("repo-a", "aaa111");
+        let copy = hashed("repo-b", "bbb222");
+        assert_eq!(original.content_hash, copy.content_hash);
+
+        assert_eq!(store.insert(&original, vec![0.1; 768])?, "repo-a");
+        assert_eq!(store.insert(&copy, vec![0.2; 768])?, "repo-a");
+        // Re-inserting the owner overwrites it as before.
+        assert_eq!(store.insert(&original, vec![0.3; 768])?, "repo-a");
+
+        assert_eq!(store.list_patterns()?, vec!["repo-a"]);
+        let hash = original.content_hash.as_deref().unwrap();
+        assert_eq!(store.find_by_content_hash(hash)?.as_deref(), Some("repo-a"));
+        assert!(store.find_by_content_hash("unknown")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedup_by_content_hash() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+
+        // Stored without content hashes, as by older versions or `insert_batch`.
+        let older = Pattern {
+            id: "older".to_string(),
+            timestamp: 100,
+            ..create_test_pattern()
+        };
+        let newer = Pattern {
+            id: "newer".to_string(),
+            timestamp: 200,
+            ..create_test_pattern()
+        };
+        let distinct = Pattern {
+            id: "distinct".to_string(),
+            description: "Another change".to_string(),
+            ..create_test_pattern()
+        };
+        store.insert_batch(&[
+            (newer, vec![0.1; 768]),
+            (older.clone(), vec![0.2; 768]),
+            (distinct, vec![0.3; 768]),
+        ])?;
+
+        assert_eq!(store.dedup_by_content_hash()?, 1);
+        assert_eq!(store.list_patterns()?, vec!["distinct", "older"]);
+        assert!(store.get_embedding("newer")?.is_none());
+
+        let stored = store.get_pattern("older")?.unwrap();
+        let hash = older.compute_content_hash();
+        assert_eq!(stored.content_hash.as_deref(), Some(hash.as_str()));
+        assert_eq!(store.find_by_content_hash(&hash)?.as_deref(), Some("older"));
+        assert_eq!(store.dedup_by_content_hash()?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_state_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let store = VectorStore::open(dir.path().join("test.redb"))?;
+        assert!(store.get_refresh_state()?.is_none());
+
+        let state = RefreshState {
+            last_indexed_sha: "abc123".to_string(),
+            last_run_at: 1_700_000_000,
+            total_indexed: 42,
+        };
+        store.set_refresh_state(state.clone())?;
+        assert_eq!(store.get_refresh_state()?, Some(state));
+
+        let next = RefreshState {
+            last_indexed_sha: "def456".to_string(),
+            last_run_at: 1_700_000_100,
+            total_indexed: 45,
+        };
+        store.set_refresh_state(next.clone())?;
+        assert_eq!(store.get_refresh_state()?, Some(next));
+
+        Ok(())
+    }
 }
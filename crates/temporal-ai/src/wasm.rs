@@ -0,0 +1,52 @@
+//! Browser bindings for serving recommendations from WebAssembly.
+//!
+//! The `wasm32` build has no embedding model and no Git access, so the caller
+//! supplies both the database (as the raw bytes of a `.redb` file produced by the
+//! native CLI) and the query embedding (computed by whatever model the host page
+//! runs). [`WasmRecommender`] then runs the usual [`SimilaritySearch`] →
+//! [`RecommendationRanker`] pipeline against an in-memory [`VectorStore`].
+
+use crate::ranker::RecommendationRanker;
+use crate::similarity::SimilaritySearch;
+use crate::vector_store::VectorStore;
+use wasm_bindgen::prelude::*;
+
+/// A recommendation engine exposed to JavaScript via `wasm-bindgen`.
+#[wasm_bindgen]
+pub struct WasmRecommender {
+    store: VectorStore,
+}
+
+#[wasm_bindgen]
+impl WasmRecommender {
+    /// Loads a database image into memory.
+    ///
+    /// # Errors
+    ///
+    /// Throws if `db_bytes` is not a valid redb database.
+    #[wasm_bindgen(constructor)]
+    pub fn new(db_bytes: &[u8]) -> Result<WasmRecommender, JsError> {
+        let store = VectorStore::from_bytes(db_bytes)?;
+        Ok(Self { store })
+    }
+
+    /// Returns the top `top` recommendations for `query_embedding`.
+    ///
+    /// The result is an array of plain objects mirroring
+    /// [`crate::ranker::Recommendation`].
+    ///
+    /// # Errors
+    ///
+    /// Throws if the database cannot be read or the results cannot be converted
+    /// to JavaScript values.
+    pub fn query(&self, query_embedding: &[f32], top: usize) -> Result<JsValue, JsError> {
+        let search = SimilaritySearch::new(&self.store);
+        let results = search.search(query_embedding, top * 2)?;
+
+        let ranker = RecommendationRanker::new(&self.store);
+        let mut recommendations = ranker.rank(results)?;
+        recommendations.truncate(top);
+
+        Ok(serde_wasm_bindgen::to_value(&recommendations)?)
+    }
+}
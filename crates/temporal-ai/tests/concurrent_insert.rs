@@ -0,0 +1,55 @@
+//! Concurrent `VectorStore::insert_or_ignore` from several threads
+
+use std::sync::Arc;
+use std::thread;
+use tempfile::tempdir;
+use temporal_ai::{Pattern, VectorStore};
+
+const THREADS: usize = 8;
+const PATTERNS: usize = 20;
+
+fn pattern(i: usize) -> Pattern {
+    Pattern {
+        id: format!("pattern-{i}"),
+        description: format!("Concurrent pattern {i}"),
+        file_paths: vec![format!("src/module_{i}.rs")],
+        commit_sha: format!("{i:040x}"),
+        timestamp: 1_700_000_000,
+        tags: vec!["rust".to_string()],
+        related_ids: Vec::new(),
+        co_authors: Vec::new(),
+        pr_number: None,
+        content_hash: None,
+        lines_added: 0,
+        lines_deleted: 0,
+    }
+}
+
+#[test]
+fn test_insert_or_ignore_inserts_each_pattern_once() {
+    let dir = tempdir().unwrap();
+    let store = Arc::new(VectorStore::open(dir.path().join("test.redb")).unwrap());
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|thread_index| {
+            let store = Arc::clone(&store);
+            thread::spawn(move || {
+                // Each thread embeds differently so an overwrite would be visible.
+                let embedding = vec![thread_index as f32 + 1.0; 768];
+                (0..PATTERNS)
+                    .filter(|&i| store.insert_or_ignore(&pattern(i), embedding.clone()).unwrap())
+                    .count()
+            })
+        })
+        .collect();
+    let inserted: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+
+    assert_eq!(inserted, PATTERNS);
+    assert_eq!(store.list_patterns().unwrap().len(), PATTERNS);
+    assert_eq!(store.find_by_tag("rust").unwrap().len(), PATTERNS);
+
+    // A later attempt is ignored and leaves the winning embedding in place.
+    let before = store.get_embedding("pattern-0").unwrap().unwrap();
+    assert!(!store.insert_or_ignore(&pattern(0), vec![99.0; 768]).unwrap());
+    assert_eq!(store.get_embedding("pattern-0").unwrap().unwrap(), before);
+}
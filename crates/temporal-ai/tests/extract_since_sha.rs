@@ -0,0 +1,168 @@
+//! Integration tests for incremental extraction with `extract_since_sha` and
+//! `extract_incremental`, and for `extract_by_branch`
+
+use git2::{Repository, Signature};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+use temporal_ai::{PatternExtractor, RefreshState, VectorStore};
+
+fn commit_file(repo: &Repository, path: &str, message: &str) {
+    let workdir = repo.workdir().unwrap();
+    fs::create_dir_all(workdir.join(path).parent().unwrap()).unwrap();
+    fs::write(workdir.join(path), message).unwrap();
+
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+    let signature = Signature::now("Test", "test@example.com").unwrap();
+    let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+    let parents: Vec<_> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap();
+}
+
+#[test]
+fn test_extract_since_sha_skips_indexed_commits() {
+    let dir = tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    commit_file(&repo, "src/auth.rs", "feat(auth): add JWT validation");
+
+    let extractor = PatternExtractor::new(dir.path()).unwrap();
+    let indexed = extractor.head_sha().unwrap();
+
+    commit_file(&repo, "src/cache.rs", "feat(cache): add LRU cache");
+    commit_file(&repo, "src/db.rs", "fix(db): close idle connections");
+
+    let patterns = extractor.extract_since_sha(&indexed).unwrap();
+    let descriptions: Vec<_> = patterns.iter().map(|p| p.description.as_str()).collect();
+    assert_eq!(descriptions, vec!["close idle connections", "add LRU cache"]);
+
+    let head = extractor.head_sha().unwrap();
+    assert!(extractor.extract_since_sha(&head).unwrap().is_empty());
+    assert!(extractor.extract_since_sha("0000000000000000000000000000000000000000").is_err());
+}
+
+/// Stores `patterns` the way `temporal-ai refresh` does: checkpoint last.
+fn checkpoint(store: &VectorStore, patterns: &[temporal_ai::Pattern], head_sha: String) {
+    let previous = store.get_refresh_state().unwrap();
+    store
+        .set_refresh_state(RefreshState {
+            last_indexed_sha: head_sha,
+            last_run_at: 0,
+            total_indexed: previous.map_or(0, |state| state.total_indexed) + patterns.len() as u64,
+        })
+        .unwrap();
+}
+
+#[test]
+fn test_extract_incremental_reads_since_checkpoint() {
+    let dir = tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    commit_file(&repo, "src/auth.rs", "feat(auth): add JWT validation");
+
+    let store = VectorStore::open(dir.path().join("patterns.redb")).unwrap();
+    let extractor = PatternExtractor::new(dir.path()).unwrap();
+    let (patterns, head_sha) = extractor.extract_incremental(&store, 100).unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(head_sha, extractor.head_sha().unwrap());
+    assert!(store.get_refresh_state().unwrap().is_none());
+    checkpoint(&store, &patterns, head_sha);
+
+    commit_file(&repo, "src/cache.rs", "feat(cache): add LRU cache");
+    let (patterns, head_sha) = extractor.extract_incremental(&store, 100).unwrap();
+    let descriptions: Vec<_> = patterns.iter().map(|p| p.description.as_str()).collect();
+    assert_eq!(descriptions, vec!["add LRU cache"]);
+    assert_eq!((patterns[0].lines_added, patterns[0].lines_deleted), (1, 0));
+    checkpoint(&store, &patterns, head_sha);
+
+    assert!(extractor
+        .extract_incremental(&store, 100)
+        .unwrap()
+        .0
+        .is_empty());
+    assert_eq!(store.get_refresh_state().unwrap().unwrap().total_indexed, 2);
+}
+
+#[test]
+fn test_extract_incremental_reads_every_commit_since_checkpoint() {
+    let dir = tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    commit_file(&repo, "src/auth.rs", "feat(auth): add JWT validation");
+
+    let store = VectorStore::open(dir.path().join("patterns.redb")).unwrap();
+    let extractor = PatternExtractor::new(dir.path()).unwrap();
+    let (patterns, head_sha) = extractor.extract_incremental(&store, 1).unwrap();
+    checkpoint(&store, &patterns, head_sha);
+
+    commit_file(&repo, "src/cache.rs", "feat(cache): add LRU cache");
+    commit_file(&repo, "src/db.rs", "fix(db): close idle connections");
+    let (patterns, _) = extractor.extract_incremental(&store, 1).unwrap();
+    let descriptions: Vec<_> = patterns.iter().map(|p| p.description.as_str()).collect();
+    assert_eq!(
+        descriptions,
+        vec!["close idle connections", "add LRU cache"]
+    );
+}
+
+#[test]
+fn test_extract_incremental_falls_back_without_checkpoint_commit() {
+    let dir = tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    commit_file(&repo, "src/auth.rs", "feat(auth): add JWT validation");
+    commit_file(&repo, "src/cache.rs", "feat(cache): add LRU cache");
+
+    let store = VectorStore::open(dir.path().join("patterns.redb")).unwrap();
+    store
+        .set_refresh_state(RefreshState {
+            last_indexed_sha: "0000000000000000000000000000000000000000".to_string(),
+            last_run_at: 0,
+            total_indexed: 0,
+        })
+        .unwrap();
+
+    let extractor = PatternExtractor::new(dir.path()).unwrap();
+    let (patterns, _) = extractor.extract_incremental(&store, 1).unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].description, "add LRU cache");
+}
+
+#[test]
+fn test_extract_by_branch_walks_branch_tip() {
+    let dir = tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    commit_file(&repo, "src/auth.rs", "feat(auth): add JWT validation");
+
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch("feature", &head, false).unwrap();
+    repo.set_head("refs/heads/feature").unwrap();
+    commit_file(&repo, "src/cache.rs", "feat(cache): add LRU cache");
+    let default_branch = repo
+        .branches(Some(git2::BranchType::Local))
+        .unwrap()
+        .map(|branch| branch.unwrap().0.name().unwrap().unwrap().to_string())
+        .find(|name| name != "feature")
+        .unwrap();
+    repo.set_head(&format!("refs/heads/{}", default_branch)).unwrap();
+
+    let extractor = PatternExtractor::new(dir.path()).unwrap();
+    let descriptions = |patterns: Vec<temporal_ai::Pattern>| {
+        patterns
+            .into_iter()
+            .map(|p| p.description)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        descriptions(extractor.extract_by_branch("feature", 10).unwrap()),
+        vec!["add LRU cache", "add JWT validation"]
+    );
+    assert_eq!(
+        descriptions(extractor.extract_recent(10).unwrap()),
+        vec!["add JWT validation"]
+    );
+
+    let err = extractor.extract_by_branch("missing", 10).unwrap_err();
+    assert!(err.to_string().contains("feature"));
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "http")]
+
+//! Integration tests for the `http` feature's REST API
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::tempdir;
+use temporal_ai::server::router;
+use temporal_ai::{Pattern, VectorStore};
+use tower::ServiceExt;
+
+fn test_pattern(id: &str) -> Pattern {
+    Pattern {
+        id: id.to_string(),
+        description: format!("Test pattern {}", id),
+        file_paths: vec![format!("src/{}.rs", id)],
+        commit_sha: format!("abcdef{}", id),
+        timestamp: 1234567890,
+        tags: vec!["rust".to_string()],
+        related_ids: Vec::new(),
+        co_authors: Vec::new(),
+        pr_number: None,
+        content_hash: None,
+        lines_added: 0,
+        lines_deleted: 0,
+    }
+}
+
+async fn body_json(response: axum::response::Response) -> Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_recommend_returns_ranked_patterns() {
+    let dir = tempdir().unwrap();
+    let store = VectorStore::open(dir.path().join("test.redb")).unwrap();
+    store.insert(&test_pattern("1"), vec![0.9; 768]).unwrap();
+    store.insert(&test_pattern("2"), vec![0.1; 768]).unwrap();
+
+    let app = router(Arc::new(store));
+    let request = Request::post("/v1/recommend")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({ "embedding": vec![1.0; 768], "top": 1 }).to_string(),
+        ))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = body_json(response).await;
+    let recommendations = body["recommendations"].as_array().unwrap();
+    assert_eq!(recommendations.len(), 1);
+    assert!(recommendations[0]["pattern"]["id"].is_string());
+    assert!(recommendations[0]["final_score"].is_number());
+    assert!(recommendations[0]["explanation"].is_string());
+}
+
+#[tokio::test]
+async fn test_recommend_rejects_wrong_dimension() {
+    let dir = tempdir().unwrap();
+    let store = VectorStore::open(dir.path().join("test.redb")).unwrap();
+
+    let app = router(Arc::new(store));
+    let request = Request::post("/v1/recommend")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "embedding": [1.0, 0.0] }).to_string()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_get_pattern_and_stats() {
+    let dir = tempdir().unwrap();
+    let store = VectorStore::open(dir.path().join("test.redb")).unwrap();
+    store.insert(&test_pattern("1"), vec![0.5; 768]).unwrap();
+    let app = router(Arc::new(store));
+
+    let response = app
+        .clone()
+        .oneshot(Request::get("/v1/patterns/1").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["id"], "1");
+
+    let response = app
+        .clone()
+        .oneshot(Request::get("/v1/patterns/missing").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .oneshot(Request::get("/v1/stats").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["total_patterns"], 1);
+}
+
+#[tokio::test]
+async fn test_openapi_document_lists_routes() {
+    let dir = tempdir().unwrap();
+    let store = VectorStore::open(dir.path().join("test.redb")).unwrap();
+    let app = router(Arc::new(store));
+
+    let response = app
+        .oneshot(Request::get("/openapi.json").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let spec = body_json(response).await;
+    assert!(spec["paths"]["/v1/recommend"]["post"].is_object());
+    assert!(spec["paths"]["/v1/patterns/{id}"]["get"].is_object());
+    assert!(spec["paths"]["/v1/stats"]["get"].is_object());
+}
@@ -0,0 +1,101 @@
+//! Integration tests for `temporal-ai download-model`'s download and verification
+
+use mockito::Server;
+use std::fs;
+use tempfile::tempdir;
+use temporal_ai::model_download::{download_model, partial_path, sha256_file};
+use temporal_ai::TemporalAIError;
+
+const MODEL_BYTES: &[u8] = b"GGUF fake model weights for integrity tests";
+
+fn model_sha256() -> String {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("model.gguf");
+    fs::write(&path, MODEL_BYTES).unwrap();
+    sha256_file(&path).unwrap()
+}
+
+#[tokio::test]
+async fn test_download_verifies_published_digest() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/model.gguf")
+        .with_status(200)
+        .with_header("x-linked-etag", &format!("\"{}\"", model_sha256()))
+        .with_body(MODEL_BYTES)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let dest = dir.path().join("models/model.gguf");
+    let url = format!("{}/model.gguf", server.url());
+    let report = download_model(&reqwest::Client::new(), &url, &dest, None, |_, _| {})
+        .await
+        .unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(report.sha256, model_sha256());
+    assert_eq!(report.size_bytes, MODEL_BYTES.len() as u64);
+    assert_eq!(fs::read(&dest).unwrap(), MODEL_BYTES);
+    assert!(!partial_path(&dest).exists());
+}
+
+#[tokio::test]
+async fn test_download_deletes_file_on_checksum_mismatch() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/model.gguf")
+        .with_status(200)
+        .with_body(MODEL_BYTES)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let dest = dir.path().join("model.gguf");
+    let url = format!("{}/model.gguf", server.url());
+    let wrong = "0".repeat(64);
+    let err = download_model(&reqwest::Client::new(), &url, &dest, Some(&wrong), |_, _| {})
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, TemporalAIError::ChecksumMismatch { .. }));
+    assert!(!dest.exists());
+    assert!(!partial_path(&dest).exists());
+}
+
+#[tokio::test]
+async fn test_download_resumes_partial_file() {
+    let split = 10;
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/model.gguf")
+        .match_header("range", format!("bytes={}-", split).as_str())
+        .with_status(206)
+        .with_body(&MODEL_BYTES[split..])
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let dest = dir.path().join("model.gguf");
+    fs::write(partial_path(&dest), &MODEL_BYTES[..split]).unwrap();
+
+    let url = format!("{}/model.gguf", server.url());
+    let expected = model_sha256();
+    let mut progress = Vec::new();
+    let report = download_model(
+        &reqwest::Client::new(),
+        &url,
+        &dest,
+        Some(&expected),
+        |downloaded, total| progress.push((downloaded, total)),
+    )
+    .await
+    .unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(report.resumed_from, split as u64);
+    assert_eq!(fs::read(&dest).unwrap(), MODEL_BYTES);
+    let total = Some(MODEL_BYTES.len() as u64);
+    assert_eq!(progress.first(), Some(&(split as u64, total)));
+    assert_eq!(progress.last(), Some(&(MODEL_BYTES.len() as u64, total)));
+}
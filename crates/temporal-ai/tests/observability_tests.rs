@@ -1,6 +1,6 @@
 use serde_json::json;
 use std::env;
-use temporal_ai::observability_aggregator::{ObservabilityClient, ObservabilityMode};
+use temporal_ai::observability_aggregator::{HealthStatus, ObservabilityClient, ObservabilityMode};
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -93,3 +93,112 @@ async fn test_api_error() {
 
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_health_check_reachable_and_authenticated() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/healthz"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "ok",
+            "version": "v0.14.0"
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/default/streams"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "list": [] })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    env::set_var("OPENOBSERVE_URL", mock_server.uri());
+    env::set_var("OPENOBSERVE_TOKEN", "test-token");
+    env::set_var("OPENOBSERVE_ORG", "default");
+
+    let client = ObservabilityClient::from_env_with_mode(ObservabilityMode::Online).unwrap();
+    let status = client.health_check().await.unwrap();
+
+    assert!(status.reachable);
+    assert!(status.authenticated);
+    assert_eq!(status.version.as_deref(), Some("v0.14.0"));
+}
+
+#[tokio::test]
+async fn test_health_check_reachable_but_unauthenticated() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/healthz"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "status": "ok" })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/default/streams"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&mock_server)
+        .await;
+
+    env::set_var("OPENOBSERVE_URL", mock_server.uri());
+    env::set_var("OPENOBSERVE_TOKEN", "wrong-token");
+    env::set_var("OPENOBSERVE_ORG", "default");
+
+    let client = ObservabilityClient::from_env_with_mode(ObservabilityMode::Online).unwrap();
+    let status = client.health_check().await.unwrap();
+
+    assert!(status.reachable);
+    assert!(!status.authenticated);
+    assert_eq!(status.version, None);
+}
+
+#[tokio::test]
+async fn test_health_check_unhealthy_skips_auth() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/healthz"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/default/streams"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    env::set_var("OPENOBSERVE_URL", mock_server.uri());
+    env::set_var("OPENOBSERVE_TOKEN", "test-token");
+    env::set_var("OPENOBSERVE_ORG", "default");
+
+    let client = ObservabilityClient::from_env_with_mode(ObservabilityMode::Online).unwrap();
+    let status = client.health_check().await.unwrap();
+
+    assert!(!status.reachable);
+    assert!(!status.authenticated);
+}
+
+#[tokio::test]
+async fn test_health_check_connection_error_is_not_an_error() {
+    // Reserve a port and release it so nothing is listening there.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    env::set_var("OPENOBSERVE_URL", format!("http://{}", addr));
+    env::set_var("OPENOBSERVE_TOKEN", "test-token");
+
+    let client = ObservabilityClient::from_env_with_mode(ObservabilityMode::Online).unwrap();
+    let status = client.health_check().await.unwrap();
+
+    assert_eq!(
+        status,
+        HealthStatus {
+            reachable: false,
+            authenticated: false,
+            latency_ms: status.latency_ms,
+            version: None,
+        }
+    );
+}
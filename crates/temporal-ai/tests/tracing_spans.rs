@@ -0,0 +1,119 @@
+use chrono::Utc;
+use std::sync::{Arc, Mutex};
+use temporal_ai::{Pattern, RecommendationRanker, SearchFilters, SimilaritySearch, VectorStore};
+use tempfile::tempdir;
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+/// Records `(span name, parent span name)` for every span that is created.
+#[derive(Clone, Default)]
+struct SpanCollector {
+    spans: Arc<Mutex<Vec<(String, Option<String>)>>>,
+}
+
+impl SpanCollector {
+    fn parent_of(&self, name: &str) -> Option<String> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(span, _)| span == name)
+            .and_then(|(_, parent)| parent.clone())
+    }
+
+    fn count(&self, name: &str) -> usize {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(span, _)| span == name)
+            .count()
+    }
+}
+
+impl<S> Layer<S> for SpanCollector
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let parent = span.parent().map(|parent| parent.name().to_string());
+        self.spans
+            .lock()
+            .unwrap()
+            .push((span.name().to_string(), parent));
+    }
+}
+
+fn create_pattern(id: &str) -> Pattern {
+    Pattern {
+        id: id.to_string(),
+        description: format!("Pattern {}", id),
+        file_paths: vec!["src/lib.rs".to_string()],
+        commit_sha: format!("{:0>40}", id),
+        timestamp: Utc::now().timestamp(),
+        tags: vec!["feat".to_string()],
+        related_ids: Vec::new(),
+        co_authors: Vec::new(),
+        pr_number: None,
+        content_hash: None,
+        lines_added: 0,
+        lines_deleted: 0,
+    }
+}
+
+#[test]
+fn recommendation_pipeline_emits_span_tree() {
+    let collector = SpanCollector::default();
+    let subscriber = Registry::default().with(collector.clone());
+
+    let dir = tempdir().unwrap();
+    let store = VectorStore::open(dir.path().join("spans.redb")).unwrap();
+
+    let recommendations = tracing::subscriber::with_default(subscriber, || {
+        let _pipeline = tracing::info_span!("recommendation_pipeline").entered();
+
+        for i in 0..3 {
+            let mut embedding = vec![0.0; 768];
+            embedding[i] = 1.0;
+            store
+                .insert(&create_pattern(&i.to_string()), embedding)
+                .unwrap();
+        }
+
+        let mut query = vec![0.0; 768];
+        query[1] = 1.0;
+        let filters = SearchFilters {
+            tags: vec!["feat".to_string()],
+            ..Default::default()
+        };
+        let results = SimilaritySearch::new(&store)
+            .search_filtered(&query, 2, &filters)
+            .unwrap();
+        RecommendationRanker::new(&store).rank(results).unwrap()
+    });
+
+    assert_eq!(recommendations.len(), 2);
+
+    assert_eq!(collector.count("vector_store.insert"), 3);
+    assert_eq!(collector.count("similarity.search_filtered"), 1);
+    assert_eq!(collector.count("ranker.rank"), 1);
+
+    for name in [
+        "vector_store.insert",
+        "similarity.search_filtered",
+        "ranker.rank",
+    ] {
+        assert_eq!(
+            collector.parent_of(name).as_deref(),
+            Some("recommendation_pipeline"),
+            "{name} should be nested under the pipeline span"
+        );
+    }
+    assert_eq!(collector.parent_of("recommendation_pipeline"), None);
+}
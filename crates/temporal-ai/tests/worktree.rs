@@ -0,0 +1,51 @@
+//! Integration tests for extracting patterns from a linked `git worktree`
+
+use git2::{Repository, Signature};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+use temporal_ai::PatternExtractor;
+
+fn commit_file(repo: &Repository, path: &str, message: &str) {
+    let workdir = repo.workdir().unwrap();
+    fs::create_dir_all(workdir.join(path).parent().unwrap()).unwrap();
+    fs::write(workdir.join(path), message).unwrap();
+
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+    let signature = Signature::now("Test", "test@example.com").unwrap();
+    let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+    let parents: Vec<_> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .unwrap();
+}
+
+#[test]
+fn test_extract_from_worktree() {
+    let dir = tempdir().unwrap();
+    let main_path = dir.path().join("main");
+    let repo = Repository::init(&main_path).unwrap();
+    commit_file(&repo, "src/auth.rs", "feat(auth): add JWT validation");
+
+    let worktree_path = dir.path().join("feature");
+    repo.worktree("feature", &worktree_path, None).unwrap();
+    assert!(worktree_path.join(".git").is_file());
+
+    let worktree_repo = Repository::open(&worktree_path).unwrap();
+    commit_file(&worktree_repo, "src/cache.rs", "feat(cache): add LRU cache");
+
+    let patterns = PatternExtractor::extract_from_worktree(&worktree_path, 10).unwrap();
+    let descriptions: Vec<_> = patterns.iter().map(|p| p.description.as_str()).collect();
+    assert_eq!(descriptions, vec!["add LRU cache", "add JWT validation"]);
+    assert_eq!(patterns[0].file_paths, vec!["src/cache.rs"]);
+
+    // The main checkout is unaffected by the worktree's branch.
+    let main_patterns = PatternExtractor::new(&main_path)
+        .unwrap()
+        .extract_recent(10)
+        .unwrap();
+    assert_eq!(main_patterns.len(), 1);
+}
@@ -0,0 +1,47 @@
+//! Bakes build metadata into `$OUT_DIR/build_info.rs`, which `lib.rs` includes.
+//!
+//! `GIT_COMMIT_SHA` is expected to be set by CI; local builds leave
+//! `BUILD_COMMIT_SHA` as `None`.
+//!
+//! With the `tokio-console` feature it also warns when `--cfg tokio_unstable` is
+//! missing from the rustflags. A build script can only set cfgs for its own
+//! crate, so it cannot turn on tokio's instrumentation itself.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT_SHA");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var_os("CARGO_FEATURE_TOKIO_CONSOLE").is_some() {
+        let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+        if !rustflags.split('\x1f').any(|flag| flag.contains("tokio_unstable")) {
+            println!(
+                "cargo:warning=the tokio-console feature needs RUSTFLAGS=\"--cfg tokio_unstable\" \
+                 for tokio to emit task data"
+            );
+        }
+    }
+
+    let name = env::var("CARGO_PKG_NAME").unwrap_or_default();
+    let version = env::var("CARGO_PKG_VERSION").unwrap_or_default();
+    let commit_sha = env::var("GIT_COMMIT_SHA")
+        .ok()
+        .map(|sha| sha.trim().to_string())
+        .filter(|sha| !sha.is_empty());
+
+    let contents = format!(
+        "/// Package name `vibepro-observe` was built as.\n\
+         pub const BUILD_NAME: &str = {name:?};\n\
+         /// Package version, used as `service.version` when `OTEL_SERVICE_VERSION` is unset.\n\
+         pub const BUILD_VERSION: &str = {version:?};\n\
+         /// `GIT_COMMIT_SHA` at build time, used when `VIBEPRO_COMMIT_SHA` is unset.\n\
+         pub const BUILD_COMMIT_SHA: Option<&str> = {commit_sha:?};\n"
+    );
+
+    let out_dir = env::var("OUT_DIR").expect("cargo sets OUT_DIR for build scripts");
+    fs::write(Path::new(&out_dir).join("build_info.rs"), contents)
+        .expect("failed to write build_info.rs");
+}
@@ -0,0 +1,230 @@
+//! Cloud resource attribute detection.
+//!
+//! With the `cloud-metadata` feature, `init_tracing` queries the instance
+//! metadata services of AWS, GCP and Azure before building the OTLP resource and
+//! adds the `cloud.*` and `host.*` attributes of whichever one answers, plus
+//! `container.id` when running in a container:
+//!
+//! | attribute                 | AWS                 | GCP                 | Azure            |
+//! |---------------------------|---------------------|---------------------|------------------|
+//! | `cloud.provider`          | `aws`               | `gcp`               | `azure`          |
+//! | `cloud.platform`          | `aws_ec2`           | `gcp_compute_engine`| `azure_vm`       |
+//! | `cloud.account.id`        | `accountId`         | project ID          | subscription ID  |
+//! | `cloud.region`            | `region`            | derived from zone   | `location`       |
+//! | `cloud.availability_zone` | `availabilityZone`  | zone                | `zone`           |
+//! | `host.id`                 | `instanceId`        | instance ID         | `vmId`           |
+//! | `host.name`               | -                   | instance name       | `name`           |
+//! | `host.type`               | `instanceType`      | machine type        | `vmSize`         |
+//!
+//! All three services are queried in parallel over plain HTTP at
+//! `169.254.169.254`, so no DNS lookup is involved. Initialization waits at most
+//! [`DETECT_TIMEOUT`]; on timeout or any error the base resource is used as is.
+//! AWS instances that only allow IMDSv2 (token-based) requests are not detected.
+
+use opentelemetry::KeyValue;
+use serde_json::Value;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Upper bound on how long [`detect`] delays initialization.
+pub const DETECT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Link-local address shared by the AWS, GCP and Azure metadata services.
+const METADATA_ADDR: ([u8; 4], u16) = ([169, 254, 169, 254], 80);
+
+/// One metadata service: request and response mapping.
+struct Provider {
+    host: &'static str,
+    path: &'static str,
+    headers: &'static [(&'static str, &'static str)],
+    parse: fn(&str) -> Option<Vec<KeyValue>>,
+}
+
+static PROVIDERS: [Provider; 3] = [
+    Provider {
+        host: "169.254.169.254",
+        path: "/latest/dynamic/instance-identity/document",
+        headers: &[],
+        parse: aws_attributes,
+    },
+    Provider {
+        host: "metadata.google.internal",
+        path: "/computeMetadata/v1/?recursive=true",
+        headers: &[("Metadata-Flavor", "Google")],
+        parse: gcp_attributes,
+    },
+    Provider {
+        host: "169.254.169.254",
+        path: "/metadata/instance/compute?api-version=2021-02-01",
+        headers: &[("Metadata", "true")],
+        parse: azure_attributes,
+    },
+];
+
+/// Returns the cloud and container attributes of the current host.
+///
+/// Waits at most `timeout` for a metadata service to answer; returns only the
+/// container attributes (or nothing) when none does in time.
+pub fn detect(timeout: Duration) -> Vec<KeyValue> {
+    let deadline = Instant::now() + timeout;
+    let (tx, rx) = mpsc::channel();
+    for provider in &PROVIDERS {
+        let tx = tx.clone();
+        // Threads that outlive the deadline finish in the background; their
+        // sockets time out on their own.
+        let _ = thread::Builder::new()
+            .name("vibepro-observe-cloud-metadata".to_string())
+            .spawn(move || {
+                let attributes =
+                    http_get(provider, timeout).and_then(|body| (provider.parse)(&body));
+                let _ = tx.send(attributes);
+            });
+    }
+    drop(tx);
+
+    let mut attributes = Vec::new();
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match rx.recv_timeout(remaining) {
+            Ok(Some(found)) => {
+                attributes = found;
+                break;
+            }
+            Ok(None) => continue,
+            Err(_) => break,
+        }
+    }
+
+    if let Some(id) = container_id() {
+        attributes.push(KeyValue::new("container.id", id));
+    }
+    attributes
+}
+
+/// Fetches `provider`'s metadata document, or `None` on any error or non-200 status.
+fn http_get(provider: &Provider, timeout: Duration) -> Option<String> {
+    let addr = SocketAddr::from(METADATA_ADDR);
+    let mut stream = TcpStream::connect_timeout(&addr, timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+
+    // HTTP/1.0 keeps the response unchunked and closes the connection after it.
+    let mut request = format!("GET {} HTTP/1.0\r\nHost: {}\r\n", provider.path, provider.host);
+    for (name, value) in provider.headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).ok()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    let status = head.lines().next()?.split_whitespace().nth(1)?;
+    (status == "200").then(|| body.to_string())
+}
+
+/// Maps an AWS instance identity document to resource attributes.
+pub fn aws_attributes(document: &str) -> Option<Vec<KeyValue>> {
+    let document: Value = serde_json::from_str(document).ok()?;
+    let field = |name: &str| document.get(name)?.as_str().map(str::to_string);
+
+    let mut attributes = vec![
+        KeyValue::new("cloud.provider", "aws"),
+        KeyValue::new("cloud.platform", "aws_ec2"),
+        KeyValue::new("host.id", field("instanceId")?),
+    ];
+    push_some(&mut attributes, "cloud.account.id", field("accountId"));
+    push_some(&mut attributes, "cloud.region", field("region"));
+    push_some(&mut attributes, "cloud.availability_zone", field("availabilityZone"));
+    push_some(&mut attributes, "host.type", field("instanceType"));
+    push_some(&mut attributes, "host.image.id", field("imageId"));
+    Some(attributes)
+}
+
+/// Maps a recursive GCP metadata document to resource attributes.
+pub fn gcp_attributes(document: &str) -> Option<Vec<KeyValue>> {
+    let document: Value = serde_json::from_str(document).ok()?;
+    let instance = document.get("instance")?;
+    let field = |value: &Value, name: &str| -> Option<String> {
+        match value.get(name)? {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    };
+    // `zone` and `machineType` are paths like `projects/123/zones/us-central1-a`.
+    let last_segment =
+        |value: Option<String>| value.and_then(|v| v.rsplit('/').next().map(str::to_string));
+
+    let zone = last_segment(field(instance, "zone"));
+    let region = zone
+        .as_deref()
+        .and_then(|zone| zone.rsplit_once('-'))
+        .map(|(region, _)| region.to_string());
+
+    let mut attributes = vec![
+        KeyValue::new("cloud.provider", "gcp"),
+        KeyValue::new("cloud.platform", "gcp_compute_engine"),
+        KeyValue::new("host.id", field(instance, "id")?),
+    ];
+    let project = document.get("project");
+    push_some(
+        &mut attributes,
+        "cloud.account.id",
+        project.and_then(|project| field(project, "projectId")),
+    );
+    push_some(&mut attributes, "cloud.region", region);
+    push_some(&mut attributes, "cloud.availability_zone", zone);
+    push_some(&mut attributes, "host.name", field(instance, "name"));
+    push_some(&mut attributes, "host.type", last_segment(field(instance, "machineType")));
+    Some(attributes)
+}
+
+/// Maps an Azure compute metadata document to resource attributes.
+pub fn azure_attributes(document: &str) -> Option<Vec<KeyValue>> {
+    let document: Value = serde_json::from_str(document).ok()?;
+    let field = |name: &str| {
+        document
+            .get(name)?
+            .as_str()
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    let mut attributes = vec![
+        KeyValue::new("cloud.provider", "azure"),
+        KeyValue::new("cloud.platform", "azure_vm"),
+        KeyValue::new("host.id", field("vmId")?),
+    ];
+    push_some(&mut attributes, "cloud.account.id", field("subscriptionId"));
+    push_some(&mut attributes, "cloud.region", field("location"));
+    push_some(&mut attributes, "cloud.availability_zone", field("zone"));
+    push_some(&mut attributes, "host.name", field("name"));
+    push_some(&mut attributes, "host.type", field("vmSize"));
+    Some(attributes)
+}
+
+/// Returns the container ID from `/proc/self/cgroup`, if this process runs in one.
+fn container_id() -> Option<String> {
+    container_id_from_cgroup(&fs::read_to_string("/proc/self/cgroup").ok()?)
+}
+
+/// Finds a 64-character hex container ID in the contents of `/proc/self/cgroup`.
+pub fn container_id_from_cgroup(cgroup: &str) -> Option<String> {
+    cgroup.lines().find_map(|line| {
+        let last = line.rsplit('/').next()?;
+        // Runtimes decorate the ID, e.g. `docker-<id>.scope` or `cri-containerd-<id>`.
+        let last = last.strip_suffix(".scope").unwrap_or(last);
+        let id = last.rsplit('-').next()?;
+        (id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())).then(|| id.to_string())
+    })
+}
+
+fn push_some(attributes: &mut Vec<KeyValue>, key: &'static str, value: Option<String>) {
+    if let Some(value) = value {
+        attributes.push(KeyValue::new(key, value));
+    }
+}
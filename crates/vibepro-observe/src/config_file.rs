@@ -0,0 +1,181 @@
+//! File-based OTLP configuration.
+//!
+//! Reads a minimal subset of the OpenTelemetry SDK declarative configuration
+//! format from the file named by `OTEL_CONFIG_FILE`:
+//!
+//! ```yaml
+//! disabled: false
+//! tracer_provider:
+//!   processors:
+//!     - batch:
+//!         schedule_delay: 5000
+//!         max_queue_size: 2048
+//!         max_export_batch_size: 512
+//!         exporter:
+//!           otlp:
+//!             protocol: grpc
+//!             endpoint: http://collector:4317
+//!             headers:
+//!               - name: api-key
+//!                 value: secret
+//! ```
+//!
+//! Only the first `batch` processor with an `otlp` exporter is used; other keys
+//! are ignored. Environment variables take precedence over the file:
+//! `OTLP_ENDPOINT` and `OTLP_PROTOCOL` override the exporter settings, and
+//! `OTEL_SDK_DISABLED=true` disables export regardless of `disabled`.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+use std::time::Duration;
+
+/// OTLP settings resolved from a configuration file and the environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OtelFileConfig {
+    /// Disables the OTLP exporter entirely.
+    pub disabled: bool,
+    /// Exporter endpoint, e.g. `http://collector:4317`.
+    pub endpoint: Option<String>,
+    /// Exporter protocol (`grpc`, `http/protobuf`).
+    pub protocol: Option<String>,
+    /// Extra headers sent with each export request.
+    pub headers: BTreeMap<String, String>,
+    /// Batch span processor settings.
+    pub batch: BatchSettings,
+}
+
+/// Batch span processor settings; unset values keep the SDK defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSettings {
+    /// Delay between two consecutive exports.
+    pub schedule_delay: Option<Duration>,
+    /// Maximum number of spans buffered before new ones are dropped.
+    pub max_queue_size: Option<usize>,
+    /// Maximum number of spans per export request.
+    pub max_export_batch_size: Option<usize>,
+}
+
+impl OtelFileConfig {
+    /// Parses the YAML file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read OTel config file {}", path.display()))?;
+        Self::from_yaml(&contents)
+            .with_context(|| format!("failed to parse OTel config file {}", path.display()))
+    }
+
+    /// Parses a YAML document in the supported subset of the format.
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let raw: RawConfig = serde_yaml::from_str(yaml)?;
+
+        let mut config = OtelFileConfig {
+            disabled: raw.disabled,
+            ..Default::default()
+        };
+
+        let batch = raw
+            .tracer_provider
+            .processors
+            .into_iter()
+            .filter_map(|processor| processor.batch)
+            .find(|batch| batch.exporter.otlp.is_some());
+
+        if let Some(batch) = batch {
+            config.batch = BatchSettings {
+                schedule_delay: batch.schedule_delay.map(Duration::from_millis),
+                max_queue_size: batch.max_queue_size,
+                max_export_batch_size: batch.max_export_batch_size,
+            };
+            if let Some(otlp) = batch.exporter.otlp {
+                config.endpoint = otlp.endpoint;
+                config.protocol = otlp.protocol;
+                config.headers = otlp
+                    .headers
+                    .into_iter()
+                    .map(|header| (header.name, header.value))
+                    .collect();
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Loads the file named by `OTEL_CONFIG_FILE` and applies environment overrides.
+    ///
+    /// When `OTEL_CONFIG_FILE` is unset, the result holds only the environment
+    /// overrides.
+    pub fn from_env() -> Result<Self> {
+        let config = match env::var("OTEL_CONFIG_FILE") {
+            Ok(path) if !path.is_empty() => Self::load(path)?,
+            _ => Self::default(),
+        };
+        Ok(config.with_env_overrides())
+    }
+
+    /// Applies `OTLP_ENDPOINT`, `OTLP_PROTOCOL` and `OTEL_SDK_DISABLED` on top of
+    /// the file values.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Ok(endpoint) = env::var("OTLP_ENDPOINT") {
+            self.endpoint = Some(endpoint);
+        }
+        if let Ok(protocol) = env::var("OTLP_PROTOCOL") {
+            self.protocol = Some(protocol);
+        }
+        if let Ok(disabled) = env::var("OTEL_SDK_DISABLED") {
+            self.disabled = disabled.trim().eq_ignore_ascii_case("true");
+        }
+        self
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawConfig {
+    disabled: bool,
+    tracer_provider: RawTracerProvider,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawTracerProvider {
+    processors: Vec<RawProcessor>,
+}
+
+#[derive(Deserialize)]
+struct RawProcessor {
+    #[serde(default)]
+    batch: Option<RawBatch>,
+}
+
+#[derive(Deserialize)]
+struct RawBatch {
+    schedule_delay: Option<u64>,
+    max_queue_size: Option<usize>,
+    max_export_batch_size: Option<usize>,
+    #[serde(default)]
+    exporter: RawExporter,
+}
+
+#[derive(Deserialize, Default)]
+struct RawExporter {
+    #[serde(default)]
+    otlp: Option<RawOtlp>,
+}
+
+#[derive(Deserialize)]
+struct RawOtlp {
+    endpoint: Option<String>,
+    protocol: Option<String>,
+    #[serde(default)]
+    headers: Vec<RawHeader>,
+}
+
+#[derive(Deserialize)]
+struct RawHeader {
+    name: String,
+    value: String,
+}
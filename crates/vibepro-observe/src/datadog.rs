@@ -0,0 +1,142 @@
+//! Datadog interoperability for the OTLP pipeline.
+//!
+//! When `DD_SERVICE` is set, the Datadog unified service tags are mapped onto the
+//! OpenTelemetry resource (`service.name`, `deployment.environment`,
+//! `service.version`) and duplicated as `dd.service` / `dd.env` / `dd.version` so
+//! that both OTel-native backends and the Datadog Agent pick them up.
+//!
+//! [`DatadogCorrelationLayer`] adds `dd.trace_id` and `dd.span_id` to each span's
+//! JSON fields, which lets Datadog correlate log lines with APM traces. Datadog
+//! uses 64-bit decimal IDs, so the low 64 bits of the OTel trace ID are used.
+
+use opentelemetry::trace::{SpanId, TraceId};
+use opentelemetry::KeyValue;
+use serde_json::{Map, Value};
+use std::env;
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_opentelemetry::OtelData;
+use tracing_subscriber::fmt::format::JsonFields;
+use tracing_subscriber::fmt::FormattedFields;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Datadog unified service tags read from the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatadogConfig {
+    /// Value of `DD_SERVICE`.
+    pub service: String,
+    /// Value of `DD_ENV`, if set.
+    pub env: Option<String>,
+    /// Value of `DD_VERSION`, if set.
+    pub version: Option<String>,
+}
+
+impl DatadogConfig {
+    /// Reads `DD_SERVICE`, `DD_ENV` and `DD_VERSION`.
+    ///
+    /// Returns `None` when `DD_SERVICE` is unset or empty, in which case no
+    /// Datadog-specific behavior is applied.
+    pub fn from_env() -> Option<Self> {
+        let service = env::var("DD_SERVICE").ok().filter(|s| !s.is_empty())?;
+        Some(Self {
+            service,
+            env: env::var("DD_ENV").ok().filter(|s| !s.is_empty()),
+            version: env::var("DD_VERSION").ok().filter(|s| !s.is_empty()),
+        })
+    }
+
+    /// Returns the OTel resource attributes and their `dd.*` duplicates.
+    pub fn resource_attributes(&self) -> Vec<KeyValue> {
+        let mut attributes = vec![
+            KeyValue::new("service.name", self.service.clone()),
+            KeyValue::new("dd.service", self.service.clone()),
+        ];
+        if let Some(env) = &self.env {
+            attributes.push(KeyValue::new("deployment.environment", env.clone()));
+            attributes.push(KeyValue::new("dd.env", env.clone()));
+        }
+        if let Some(version) = &self.version {
+            attributes.push(KeyValue::new("service.version", version.clone()));
+            attributes.push(KeyValue::new("dd.version", version.clone()));
+        }
+        attributes
+    }
+}
+
+/// Replaces `service.name` (and `service.version`, when `DD_VERSION` is set) in
+/// `attributes` with the Datadog mapping when `DD_SERVICE` is set; otherwise
+/// leaves them untouched.
+pub(crate) fn apply_resource_attributes(attributes: &mut Vec<KeyValue>) {
+    if let Some(config) = DatadogConfig::from_env() {
+        attributes.retain(|kv| match kv.key.as_str() {
+            "service.name" => false,
+            "service.version" => config.version.is_none(),
+            _ => true,
+        });
+        attributes.extend(config.resource_attributes());
+    }
+}
+
+/// Converts an OTel trace ID to the 64-bit form Datadog expects in `dd.trace_id`.
+pub fn to_datadog_trace_id(trace_id: TraceId) -> u64 {
+    let bytes = trace_id.to_bytes();
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&bytes[8..]);
+    u64::from_be_bytes(low)
+}
+
+/// Converts an OTel span ID to the 64-bit form Datadog expects in `dd.span_id`.
+pub fn to_datadog_span_id(span_id: SpanId) -> u64 {
+    u64::from_be_bytes(span_id.to_bytes())
+}
+
+/// A layer that writes `dd.trace_id` and `dd.span_id` into each span's JSON fields.
+///
+/// It must be stacked after both the JSON fmt layer and the
+/// `tracing_opentelemetry` layer, because it reads the IDs the latter assigns and
+/// rewrites the fields the former has already formatted.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DatadogCorrelationLayer;
+
+impl DatadogCorrelationLayer {
+    /// Returns the layer when `DD_SERVICE` is set, `None` otherwise.
+    pub fn from_env() -> Option<Self> {
+        DatadogConfig::from_env().map(|_| Self)
+    }
+}
+
+impl<S> Layer<S> for DatadogCorrelationLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+
+        let ids = extensions
+            .get_mut::<OtelData>()
+            .and_then(|data| Some((data.trace_id()?, data.span_id()?)));
+        let Some((trace_id, span_id)) = ids else {
+            return;
+        };
+
+        let Some(formatted) = extensions.get_mut::<FormattedFields<JsonFields>>() else {
+            return;
+        };
+        let mut fields: Map<String, Value> =
+            serde_json::from_str(&formatted.fields).unwrap_or_default();
+        fields.insert(
+            "dd.trace_id".to_string(),
+            Value::String(to_datadog_trace_id(trace_id).to_string()),
+        );
+        fields.insert(
+            "dd.span_id".to_string(),
+            Value::String(to_datadog_span_id(span_id).to_string()),
+        );
+        formatted.fields = Value::Object(fields).to_string();
+    }
+}
@@ -0,0 +1,112 @@
+//! Per-level counts of emitted log events.
+//!
+//! `init_tracing` always installs [`global_event_counter`] as a layer, so
+//! [`event_counts`](crate::event_counts) answers "how many errors since
+//! startup?" without a log backend. The counter sees the events that pass
+//! `RUST_LOG`, before any rate limiting of the JSON output.
+//!
+//! With the `otlp` feature and a Tokio runtime, the counts are also emitted
+//! through [`record_metric`](crate::record_metric) every 60 seconds as
+//! `log_events.{trace,debug,info,warn,error}`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+static GLOBAL_EVENT_COUNTER: EventCounter = EventCounter::new();
+
+/// Interval between two `record_metric` reports of the counts.
+#[cfg(feature = "otlp")]
+const REPORT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Layer counting the events it sees, per level.
+#[derive(Debug, Default)]
+pub struct EventCounter {
+    trace_count: AtomicU64,
+    debug_count: AtomicU64,
+    info_count: AtomicU64,
+    warn_count: AtomicU64,
+    error_count: AtomicU64,
+}
+
+/// Snapshot of an [`EventCounter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventCounts {
+    /// Number of `TRACE` events.
+    pub trace_count: u64,
+    /// Number of `DEBUG` events.
+    pub debug_count: u64,
+    /// Number of `INFO` events.
+    pub info_count: u64,
+    /// Number of `WARN` events.
+    pub warn_count: u64,
+    /// Number of `ERROR` events.
+    pub error_count: u64,
+}
+
+impl EventCounter {
+    /// Creates a counter with every count at zero.
+    pub const fn new() -> Self {
+        Self {
+            trace_count: AtomicU64::new(0),
+            debug_count: AtomicU64::new(0),
+            info_count: AtomicU64::new(0),
+            warn_count: AtomicU64::new(0),
+            error_count: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the current counts.
+    pub fn counts(&self) -> EventCounts {
+        EventCounts {
+            trace_count: self.trace_count.load(Ordering::Relaxed),
+            debug_count: self.debug_count.load(Ordering::Relaxed),
+            info_count: self.info_count.load(Ordering::Relaxed),
+            warn_count: self.warn_count.load(Ordering::Relaxed),
+            error_count: self.error_count.load(Ordering::Relaxed),
+        }
+    }
+
+    fn counter(&self, level: Level) -> &AtomicU64 {
+        match level {
+            Level::TRACE => &self.trace_count,
+            Level::DEBUG => &self.debug_count,
+            Level::INFO => &self.info_count,
+            Level::WARN => &self.warn_count,
+            _ => &self.error_count,
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for &'static EventCounter {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        self.counter(*event.metadata().level()).fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns the process-wide counter that `init_tracing` installs.
+pub fn global_event_counter() -> &'static EventCounter {
+    &GLOBAL_EVENT_COUNTER
+}
+
+/// Reports the global counts via `record_metric` every 60 seconds.
+///
+/// Must be called from within a Tokio runtime.
+#[cfg(feature = "otlp")]
+pub(crate) fn spawn_reporter() {
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(REPORT_INTERVAL);
+        // The first tick completes immediately; skip it so reports start after a
+        // full interval.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let counts = global_event_counter().counts();
+            crate::record_metric("log_events.trace", counts.trace_count as f64);
+            crate::record_metric("log_events.debug", counts.debug_count as f64);
+            crate::record_metric("log_events.info", counts.info_count as f64);
+            crate::record_metric("log_events.warn", counts.warn_count as f64);
+            crate::record_metric("log_events.error", counts.error_count as f64);
+        }
+    });
+}
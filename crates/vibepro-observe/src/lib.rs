@@ -8,15 +8,21 @@
 //!
 //! - **Structured Logging**: By default, it configures `tracing_subscriber` to emit
 //!   JSON-formatted logs to standard output. This is suitable for production environments
-//!   where logs are collected and processed by an external agent.
+//!   where logs are collected and processed by an external agent. For local development,
+//!   `VIBEPRO_LOG_FORMAT` switches to human-readable output.
 //! - **Distributed Tracing**: When the `otlp` feature is enabled and the `VIBEPRO_OBSERVE`
 //!   environment variable is set to `1`, the crate automatically configures and installs
 //!   an OpenTelemetry (OTLP) exporter. This sends trace data to a configured OTLP
 //!   endpoint, enabling distributed tracing across services.
+//! - **Cloud Resource Detection**: With the `cloud-metadata` feature, `cloud.*`, `host.*`
+//!   and `container.id` resource attributes are detected from the AWS, GCP or Azure
+//!   metadata service, waiting at most 200 ms (see the `cloud_metadata` module).
 //! - **Dynamic Configuration**: The behavior of the crate is controlled by environment
 //!   variables, allowing for flexible configuration without code changes.
 //! - **Idempotent Initialization**: The `init_tracing` function is safe to call
 //!   multiple times; it will only initialize the global tracer once.
+//! - **Structured Panics**: [`install_panic_hook`] logs panics as `tracing::error!`
+//!   events with message, location and backtrace fields (see the `panic_hook` module).
 //!
 //! ## Usage
 //!
@@ -47,27 +53,158 @@
 //! }
 //! ```
 //!
+//! In unit tests, call [`init_tracing_for_test`] instead. It discards events unless
+//! `RUST_LOG` is set and is safe to call from every test.
+//!
+//! To configure tracing in code instead, for example in a test harness, build a
+//! [`TracingConfig`] and pass it to [`init_tracing_with_config`]. Its fields take
+//! precedence over the environment variables below.
+//!
+//! With the `config-env` feature, [`init_tracing_from_config_env`] reads the whole
+//! [`TracingConfig`] from `VIBEPRO_CONFIG` instead, for platforms such as Lambda or
+//! Cloud Run that allow only a single environment variable.
+//!
 //! ## Environment Variables
 //!
 //! - `RUST_LOG`: Controls the log level (e.g., `info`, `debug`, `my_crate=trace`).
 //!   Defaults to `info`.
+//! - `VIBEPRO_LOG_FORMAT`: The stdout log format: `json`, `pretty` (multi-line) or
+//!   `compact` (single-line). Defaults to `json`. The OTLP layer is unaffected.
 //! - `VIBEPRO_OBSERVE`: Set to `1` to enable the OTLP exporter (requires the `otlp` feature).
 //! - `OTLP_ENDPOINT`: The OTLP endpoint to send traces to. Defaults to `http://127.0.0.1:4317`.
 //! - `OTLP_PROTOCOL`: The OTLP protocol (`grpc` or `http`). Defaults to `grpc`.
+//! - `OTLP_HEADERS`: Comma-separated `key=value` pairs sent with every export request,
+//!   e.g. `x-honeycomb-team=my-api-key,x-dataset=prod` (requires the `otlp` feature).
+//!   Entries override headers of the same name from `OTEL_CONFIG_FILE`.
+//! - `OTLP_LOGS_ENDPOINT`: With the `logs` feature, the OTLP endpoint log records are
+//!   exported to (see the `otlp_logs` module). Defaults to the trace endpoint. JSON logs
+//!   are still written to stdout.
+//! - `OTLP_SAMPLER`: The trace sampler: `always_on`, `always_off`, `traceidratio` or
+//!   `parentbased_traceidratio` (requires the `otlp` feature). Defaults to
+//!   `parentbased_traceidratio`.
+//! - `OTLP_SAMPLE_RATIO`: Fraction of traces kept by the ratio samplers, from `0.0` to
+//!   `1.0`. Defaults to `1.0`.
+//! - `OTLP_PROPAGATORS`: With the `propagation` feature, comma-separated trace context
+//!   formats to propagate: `tracecontext`, `b3` and/or `b3multi`. Defaults to
+//!   `tracecontext` (see the `propagation` module).
+//! - `OTLP_CA_CERT_PATH`, `OTLP_CLIENT_CERT_PATH`, `OTLP_CLIENT_KEY_PATH`: With the `tls`
+//!   feature, PEM files for TLS and mutual TLS on the gRPC exporter (see the `otlp_tls`
+//!   module).
+//! - `OTLP_BATCH_MAX_QUEUE_SIZE`, `OTLP_BATCH_MAX_EXPORT_BATCH_SIZE`,
+//!   `OTLP_BATCH_SCHEDULE_DELAY_MS`, `OTLP_BATCH_EXPORT_TIMEOUT_MS`: Batch span processor
+//!   tuning (requires the `otlp` feature). Default to the SDK's `2048`, `512`, `5000` and
+//!   `30000`; they take precedence over `OTEL_CONFIG_FILE`. A value that is set but not
+//!   a number makes `init_tracing` return an error.
+//! - `VIBEPRO_METRICS_PORT`: With the `prometheus` feature, serve metrics for Prometheus
+//!   on `0.0.0.0:<port>/metrics` (see the `prometheus` module).
+//! - `OTLP_METRICS_INTERVAL_MS`: Export interval for instruments created via [`meter`]
+//!   (requires the `otlp` feature). Defaults to `30000`.
+//! - `DD_SERVICE`, `DD_ENV`, `DD_VERSION`: With the `datadog` feature, map Datadog's
+//!   unified service tags onto the OTLP resource and add `dd.trace_id` / `dd.span_id`
+//!   to span fields in JSON logs (see the `datadog` module).
+//! - `OTEL_CONFIG_FILE`: With the `config-file` feature, path to a YAML file with the
+//!   OTLP endpoint, protocol, headers and batch settings (see the `config_file` module).
+//!   `OTLP_ENDPOINT` and `OTLP_PROTOCOL` take precedence over the file.
+//! - `VIBEPRO_LOG_RATE_LIMIT`: With the `rate-limit` feature, the maximum number of log
+//!   events per second written to stdout; excess events are dropped and
+//!   summarized once per second (see the `rate_limit` module).
+//! - `VIBEPRO_REDACT_PATTERNS`: With the `redact` feature, comma-separated PII patterns
+//!   replaced by `[REDACTED]` in stdout logs and OTLP spans and log records, e.g.
+//!   `email,jwt,credit_card` (the default) or `none` (see the `redact` module).
+//! - `TOKIO_CONSOLE`: With the `tokio-console` feature, set to `1` to start the
+//!   `tokio-console` server.
+//! - `TOKIO_CONSOLE_BIND`: With the `tokio-console` feature, the address the
+//!   `tokio-console` server listens on. Defaults to `127.0.0.1:6669`.
+//! - `OTEL_SERVICE_VERSION`: The `service.version` resource attribute (requires the `otlp`
+//!   feature). Defaults to [`BUILD_VERSION`], baked in at compile time.
+//! - `VIBEPRO_COMMIT_SHA`: The `vcs.ref.head.revision` resource attribute (requires the
+//!   `otlp` feature). Defaults to [`BUILD_COMMIT_SHA`], the `GIT_COMMIT_SHA` set at
+//!   compile time, if any.
+//! - `OTEL_SDK_DISABLED`: With the `config-file` feature, set to `true` to disable the
+//!   OTLP exporter even when `VIBEPRO_OBSERVE=1`.
+//! - `VIBEPRO_CONFIG`: With the `config-env` feature, a [`TracingConfig`] as JSON or
+//!   base64 JSON, read only by [`init_tracing_from_config_env`].
+//!
+//! ## tokio-console
+//!
+//! The `tokio-console` feature installs `console_subscriber::ConsoleLayer` next to the
+//! JSON layer when `TOKIO_CONSOLE=1`, so a running service can be inspected with
+//! `tokio-console`. Tokio only emits the task instrumentation the console reads when
+//! built with `--cfg tokio_unstable`; set it for the workspace in
+//! `.cargo/config.toml` (the build script warns when it is missing):
+//!
+//! ```toml
+//! [build]
+//! rustflags = ["--cfg", "tokio_unstable"]
+//! ```
 
+#[cfg(feature = "otlp")]
+use anyhow::anyhow;
 use anyhow::Result;
 use once_cell::sync::OnceCell;
 #[cfg(feature = "otlp")]
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+#[cfg(feature = "otlp")]
 use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::env;
-#[cfg(feature = "otlp")]
-use tracing::debug;
-use tracing::info;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use std::io;
+use std::time::Duration;
+use tracing::Subscriber;
+use tracing::{debug, info};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::TestWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+#[cfg(feature = "cloud-metadata")]
+pub mod cloud_metadata;
+#[cfg(feature = "config-file")]
+pub mod config_file;
+#[cfg(feature = "datadog")]
+pub mod datadog;
+pub mod event_counter;
+#[cfg(feature = "logs")]
+pub mod otlp_logs;
+#[cfg(feature = "tls")]
+pub mod otlp_tls;
+pub mod panic_hook;
+#[cfg(feature = "propagation")]
+pub mod propagation;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+#[cfg(feature = "rate-limit")]
+pub mod rate_limit;
+#[cfg(feature = "redact")]
+pub mod redact;
+#[cfg(feature = "tokio-console")]
+pub mod tokio_console;
+pub mod tracing_config;
+
+pub use event_counter::{global_event_counter, EventCounter, EventCounts};
+pub use panic_hook::install_panic_hook;
+#[cfg(feature = "config-env")]
+pub use tracing_config::{encode_config_env, CONFIG_ENV_VAR};
+pub use tracing_config::{LogFormat, TracingConfig};
+
+include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
 
 static INIT_GUARD: OnceCell<()> = OnceCell::new();
 #[cfg(feature = "otlp")]
 static OTLP_TRACER_PROVIDER: OnceCell<SdkTracerProvider> = OnceCell::new();
+#[cfg(feature = "otlp")]
+static OTLP_METER_PROVIDER: OnceCell<SdkMeterProvider> = OnceCell::new();
+#[cfg(feature = "logs")]
+static OTLP_LOGGER_PROVIDER: OnceCell<opentelemetry_sdk::logs::SdkLoggerProvider> =
+    OnceCell::new();
+
+/// Default export interval for the OTLP metrics pipeline.
+#[cfg(feature = "otlp")]
+const DEFAULT_METRICS_INTERVAL_MS: u64 = 30_000;
+
+/// Default `OTLP_SAMPLE_RATIO`: keep every trace.
+#[cfg(feature = "otlp")]
+const DEFAULT_SAMPLE_RATIO: f64 = 1.0;
 
 /// Initializes the global tracing subscriber for a given service.
 ///
@@ -77,12 +214,15 @@ static OTLP_TRACER_PROVIDER: OnceCell<SdkTracerProvider> = OnceCell::new();
 ///
 /// # Behavior
 ///
-/// - It always installs a JSON formatting layer that writes structured logs to stdout.
+/// - It always installs a JSON formatting layer that writes structured logs to stdout,
+///   and the event counter behind [`event_counts`].
 /// - It respects the `RUST_LOG` environment variable for log filtering, defaulting to `info`.
 /// - If the `otlp` feature is enabled and the `VIBEPRO_OBSERVE` environment variable
 ///   is set to `1`, it also installs an OTLP trace exporter. The exporter's endpoint
 ///   and protocol are configured via the `OTLP_ENDPOINT` and `OTLP_PROTOCOL`
 ///   environment variables.
+/// - With the `logs` feature, events are additionally exported as OTLP log records
+///   to `OTLP_LOGS_ENDPOINT` whenever the trace exporter is installed.
 ///
 /// # Arguments
 ///
@@ -104,32 +244,103 @@ static OTLP_TRACER_PROVIDER: OnceCell<SdkTracerProvider> = OnceCell::new();
 /// # }
 /// ```
 pub fn init_tracing(service_name: &str) -> Result<()> {
+    init_tracing_with_config(TracingConfig::from_env(service_name))
+}
+
+/// Initializes the global tracing subscriber from the [`TracingConfig`] in
+/// `VIBEPRO_CONFIG`.
+///
+/// The variable holds the config as a JSON object or as base64 JSON (see
+/// [`encode_config_env`]). The other variables listed in the crate docs are
+/// not read for fields the config covers. Like `init_tracing`, only the first
+/// call has an effect.
+///
+/// # Errors
+///
+/// Returns an error if `VIBEPRO_CONFIG` is unset or does not decode to a
+/// `TracingConfig`, or if [`init_tracing_with_config`] fails.
+#[cfg(feature = "config-env")]
+pub fn init_tracing_from_config_env() -> Result<()> {
+    init_tracing_with_config(TracingConfig::from_config_env()?)
+}
+
+/// Initializes the global tracing subscriber from a [`TracingConfig`].
+///
+/// Behaves like [`init_tracing`], but takes the log level, OTLP switch,
+/// endpoint, protocol and extra resource attributes from `config` instead of
+/// the environment. Start from [`TracingConfig::from_env`] to keep the
+/// environment variables as defaults. Like `init_tracing`, only the first call
+/// has an effect.
+///
+/// # Errors
+///
+/// Returns an error if `config.log_level` is not a valid `EnvFilter` directive
+/// or the OTLP exporter cannot be initialized.
+///
+/// # Examples
+///
+/// ```
+/// use vibepro_observe::{init_tracing_with_config, TracingConfig};
+///
+/// let config = TracingConfig::new("my-service")
+///     .with_log_level("debug")
+///     .with_resource_attribute("deployment.environment", "test");
+/// init_tracing_with_config(config)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn init_tracing_with_config(config: TracingConfig) -> Result<()> {
     if INIT_GUARD.get().is_some() {
         return Ok(());
     }
 
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    let observe_flag = env::var("VIBEPRO_OBSERVE").unwrap_or_default() == "1";
+    let service_name = config.service_name.as_str();
+    let env_filter = EnvFilter::try_new(&config.log_level)?;
+    let observe_flag = config.enable_otlp;
+    let log_format = config.log_format;
+    #[cfg(feature = "prometheus")]
+    let metrics_addr = prometheus::addr_from_env()?;
+
+    #[cfg(feature = "propagation")]
+    propagation::install();
 
     #[cfg(feature = "otlp")]
     {
+        #[cfg(feature = "config-file")]
+        let file_config = config_file::OtelFileConfig::from_env()?;
+        #[cfg(feature = "config-file")]
+        let observe_flag = observe_flag && !file_config.disabled;
+
+        #[cfg(not(feature = "tokio-console"))]
         let build_base_subscriber = || {
             tracing_subscriber::registry()
                 .with(env_filter.clone())
-                .with(
-                    tracing_subscriber::fmt::layer()
-                        .json()
-                        .with_target(true)
-                        .with_thread_ids(false)
-                        .with_thread_names(false)
-                        .with_current_span(true),
-                )
+                .with(base_layers(log_format))
+        };
+        // The console needs tokio's trace-level runtime spans, so `RUST_LOG`
+        // filters the JSON and OTLP layers individually instead of globally.
+        #[cfg(feature = "tokio-console")]
+        let build_base_subscriber = || {
+            tracing_subscriber::registry()
+                .with(tokio_console::console_layer())
+                .with(base_layers(log_format).with_filter(env_filter.clone()))
         };
 
         if observe_flag {
-            let endpoint =
-                env::var("OTLP_ENDPOINT").unwrap_or_else(|_| "http://127.0.0.1:4317".to_string());
-            let protocol = env::var("OTLP_PROTOCOL").unwrap_or_else(|_| "grpc".to_string());
+            #[cfg(not(feature = "config-file"))]
+            let (endpoint, protocol, tuning) = (
+                config.otlp_endpoint.clone(),
+                config.otlp_protocol.clone(),
+                ExporterTuning::default(),
+            );
+            #[cfg(feature = "config-file")]
+            let (endpoint, protocol, tuning) = (
+                config.otlp_endpoint.clone().or_else(|| file_config.endpoint.clone()),
+                config.otlp_protocol.clone().or_else(|| file_config.protocol.clone()),
+                ExporterTuning::from_file_config(&file_config),
+            );
+            let tuning = tuning.with_env_overrides()?;
+            let endpoint = endpoint.unwrap_or_else(|| "http://127.0.0.1:4317".to_string());
+            let protocol = protocol.unwrap_or_else(|| "grpc".to_string());
 
             if tokio::runtime::Handle::try_current().is_err() {
                 if let Err(err) = build_base_subscriber().try_init() {
@@ -142,11 +353,27 @@ pub fn init_tracing(service_name: &str) -> Result<()> {
                     "OTLP exporter skipped (no Tokio runtime available)"
                 );
             } else {
-                let tracer = setup_otlp_exporter(&endpoint, &protocol, service_name)?;
-                if let Err(err) = build_base_subscriber()
-                    .with(tracing_opentelemetry::layer().with_tracer(tracer))
-                    .try_init()
-                {
+                let tracer = setup_otlp_exporter(
+                    &endpoint,
+                    &protocol,
+                    service_name,
+                    &config.resource_attributes,
+                    &tuning,
+                )?;
+                let otel_layer = tracing_opentelemetry::layer().with_tracer(tracer);
+                #[cfg(feature = "tokio-console")]
+                let otel_layer = otel_layer.with_filter(env_filter.clone());
+                let subscriber = build_base_subscriber().with(otel_layer);
+                #[cfg(feature = "logs")]
+                let subscriber = {
+                    let logs_layer = OTLP_LOGGER_PROVIDER.get().map(otlp_logs::logs_layer);
+                    #[cfg(feature = "tokio-console")]
+                    let logs_layer = logs_layer.with_filter(env_filter.clone());
+                    subscriber.with(logs_layer)
+                };
+                #[cfg(feature = "datadog")]
+                let subscriber = subscriber.with(datadog::DatadogCorrelationLayer::from_env());
+                if let Err(err) = subscriber.try_init() {
                     debug!(target = "vibepro_observe::init", error = %err, "global subscriber already initialized");
                 }
 
@@ -159,37 +386,189 @@ pub fn init_tracing(service_name: &str) -> Result<()> {
 
             info!(
                 service = service_name,
-                "OTLP exporter disabled (VIBEPRO_OBSERVE!=1)"
+                "OTLP exporter disabled (VIBEPRO_OBSERVE!=1 or enable_otlp unset)"
             );
         }
+
+        if tokio::runtime::Handle::try_current().is_ok() {
+            event_counter::spawn_reporter();
+        }
     }
 
     #[cfg(not(feature = "otlp"))]
     {
-        if let Err(err) = tracing_subscriber::registry()
+        #[cfg(not(feature = "tokio-console"))]
+        let subscriber = tracing_subscriber::registry()
             .with(env_filter.clone())
-            .with(
-                tracing_subscriber::fmt::layer()
-                    .json()
-                    .with_target(true)
-                    .with_thread_ids(false)
-                    .with_thread_names(false)
-                    .with_current_span(true),
-            )
-            .try_init()
-        {
+            .with(base_layers(log_format));
+        #[cfg(feature = "tokio-console")]
+        let subscriber = tracing_subscriber::registry()
+            .with(tokio_console::console_layer())
+            .with(base_layers(log_format).with_filter(env_filter.clone()));
+
+        if let Err(err) = subscriber.try_init() {
             info!(service = service_name, error = %err, "tracing subscriber already initialized; skipping re-init");
         }
 
         if observe_flag {
-            info!(service = service_name, "VIBEPRO_OBSERVE=1 set, but crate built without `otlp` feature; exporting is disabled");
+            info!(service = service_name, "OTLP requested, but crate built without `otlp` feature; exporting is disabled");
         }
     }
 
+    #[cfg(feature = "prometheus")]
+    if let Some(addr) = metrics_addr {
+        prometheus::serve_metrics(addr)?;
+        info!(service = service_name, %addr, "Prometheus metrics endpoint enabled");
+    }
+
     let _ = INIT_GUARD.set(());
     Ok(())
 }
 
+/// Installs a quiet global subscriber for unit tests.
+///
+/// Events are discarded unless `RUST_LOG` is set, in which case they are written
+/// through `cargo test`'s output capture, so `RUST_LOG=trace cargo test` still works
+/// for debugging. Safe to call from any number of tests and threads; only the first
+/// call installs a subscriber, and later calls to [`init_tracing`] or
+/// [`init_tracing_with_config`] are no-ops.
+///
+/// # Examples
+///
+/// ```
+/// vibepro_observe::init_tracing_for_test();
+/// tracing::info!("only visible with RUST_LOG set");
+/// ```
+pub fn init_tracing_for_test() {
+    INIT_GUARD.get_or_init(|| {
+        let (env_filter, writer) = match EnvFilter::try_from_default_env() {
+            Ok(env_filter) => (env_filter, BoxMakeWriter::new(TestWriter::new())),
+            Err(_) => (EnvFilter::new("off"), BoxMakeWriter::new(io::sink)),
+        };
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_filter(env_filter);
+
+        if let Err(err) = tracing_subscriber::registry().with(layer).try_init() {
+            debug!(target = "vibepro_observe::init", error = %err, "global subscriber already initialized");
+        }
+    });
+}
+
+/// Builds the layers every subscriber gets: stdout output and the global event counter.
+fn base_layers<S>(format: LogFormat) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+{
+    fmt_layer(format).and_then(global_event_counter())
+}
+
+/// Builds the stdout layer in `format`, rate limited when the `rate-limit` feature is
+/// enabled.
+// The rebinding below is compiled out without `rate-limit`.
+#[allow(clippy::let_and_return)]
+fn fmt_layer<S>(format: LogFormat) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+{
+    let layer = match format {
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .with_writer(stdout_writer())
+            .json()
+            .with_target(true)
+            .with_thread_ids(false)
+            .with_thread_names(false)
+            .with_current_span(true)
+            .boxed(),
+        LogFormat::Pretty => tracing_subscriber::fmt::layer()
+            .with_writer(stdout_writer())
+            .pretty()
+            .with_target(true)
+            .boxed(),
+        LogFormat::Compact => tracing_subscriber::fmt::layer()
+            .with_writer(stdout_writer())
+            .compact()
+            .with_target(true)
+            .boxed(),
+    };
+
+    #[cfg(feature = "rate-limit")]
+    let layer = rate_limit::RateLimitLayer::from_env(layer);
+
+    layer
+}
+
+/// Returns the writer of the stdout layer, redacting PII with the `redact` feature.
+fn stdout_writer() -> impl for<'a> MakeWriter<'a> + Send + Sync + 'static {
+    #[cfg(feature = "redact")]
+    {
+        redact::RedactingMakeWriter::from_env(io::stdout)
+    }
+    #[cfg(not(feature = "redact"))]
+    {
+        io::stdout
+    }
+}
+
+/// Returns how many events of each level have been emitted since `init_tracing`.
+///
+/// Only events enabled by `RUST_LOG` are counted.
+///
+/// # Examples
+///
+/// ```
+/// vibepro_observe::init_tracing("my-service")?;
+/// tracing::error!("something failed");
+/// assert!(vibepro_observe::event_counts().error_count >= 1);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn event_counts() -> EventCounts {
+    global_event_counter().counts()
+}
+
+/// Returns whether [`init_tracing`] (or one of its variants) has already run.
+///
+/// # Examples
+///
+/// ```
+/// vibepro_observe::init_tracing("my-service")?;
+/// assert!(vibepro_observe::is_tracing_initialized());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn is_tracing_initialized() -> bool {
+    INIT_GUARD.get().is_some()
+}
+
+/// Returns whether the OTLP trace exporter has been installed.
+///
+/// Always `false` without the `otlp` feature. Stays `true` after [`shutdown_tracing`].
+pub fn is_otlp_enabled() -> bool {
+    #[cfg(feature = "otlp")]
+    {
+        OTLP_TRACER_PROVIDER.get().is_some()
+    }
+    #[cfg(not(feature = "otlp"))]
+    {
+        false
+    }
+}
+
+/// Returns `OTEL_SERVICE_VERSION`, falling back to [`BUILD_VERSION`] when unset or empty.
+pub fn service_version() -> String {
+    env::var("OTEL_SERVICE_VERSION")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| BUILD_VERSION.to_string())
+}
+
+/// Returns `VIBEPRO_COMMIT_SHA`, falling back to [`BUILD_COMMIT_SHA`] when unset or empty.
+pub fn commit_sha() -> Option<String> {
+    env::var("VIBEPRO_COMMIT_SHA")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .or_else(|| BUILD_COMMIT_SHA.map(str::to_string))
+}
+
 /// Records a simple numeric metric as a structured event.
 ///
 /// This function provides a basic way to emit metrics through the logging system.
@@ -209,13 +588,224 @@ pub fn init_tracing(service_name: &str) -> Result<()> {
 /// ```
 pub fn record_metric(key: &str, value: f64) {
     tracing::info!(metric.key = key, metric.value = value, "metric");
+    #[cfg(feature = "prometheus")]
+    prometheus::record(key, value, &[]);
+}
+
+/// Records a numeric metric with dimension labels as a structured event.
+///
+/// Like [`record_metric`], plus a `metric.labels` field holding the labels as a JSON
+/// object string, e.g. `{"method":"GET","status":"200"}`. `tracing` field names are
+/// fixed at compile time, so the labels share one field instead of getting a field
+/// each. A repeated label name keeps its last value.
+///
+/// # Examples
+///
+/// ```
+/// vibepro_observe::record_metric_with_labels(
+///     "http_requests_total",
+///     1.0,
+///     &[("method", "GET"), ("status", "200")],
+/// );
+/// ```
+pub fn record_metric_with_labels(key: &str, value: f64, labels: &[(&str, &str)]) {
+    let json_labels: serde_json::Map<String, serde_json::Value> = labels
+        .iter()
+        .map(|(name, value)| (name.to_string(), serde_json::Value::from(*value)))
+        .collect();
+    let json_labels = serde_json::Value::Object(json_labels);
+    tracing::info!(
+        metric.key = key,
+        metric.value = value,
+        metric.labels = %json_labels,
+        "metric"
+    );
+    #[cfg(feature = "prometheus")]
+    prometheus::record(key, value, labels);
+}
+
+/// Exporter settings beyond endpoint and protocol.
+#[cfg(feature = "otlp")]
+#[derive(Default)]
+struct ExporterTuning {
+    headers: std::collections::HashMap<String, String>,
+    schedule_delay: Option<std::time::Duration>,
+    max_queue_size: Option<usize>,
+    max_export_batch_size: Option<usize>,
+    export_timeout: Option<std::time::Duration>,
+}
+
+#[cfg(feature = "otlp")]
+impl ExporterTuning {
+    #[cfg(feature = "config-file")]
+    fn from_file_config(config: &config_file::OtelFileConfig) -> Self {
+        Self {
+            headers: config.headers.clone().into_iter().collect(),
+            schedule_delay: config.batch.schedule_delay,
+            max_queue_size: config.batch.max_queue_size,
+            max_export_batch_size: config.batch.max_export_batch_size,
+            export_timeout: None,
+        }
+    }
+
+    /// Applies the `OTLP_BATCH_*` variables, which take precedence over `OTEL_CONFIG_FILE`.
+    fn with_env_overrides(mut self) -> Result<Self> {
+        if let Some(size) = env_number("OTLP_BATCH_MAX_QUEUE_SIZE")? {
+            self.max_queue_size = Some(size);
+        }
+        if let Some(size) = env_number("OTLP_BATCH_MAX_EXPORT_BATCH_SIZE")? {
+            self.max_export_batch_size = Some(size);
+        }
+        if let Some(millis) = env_number("OTLP_BATCH_SCHEDULE_DELAY_MS")? {
+            self.schedule_delay = Some(std::time::Duration::from_millis(millis));
+        }
+        if let Some(millis) = env_number("OTLP_BATCH_EXPORT_TIMEOUT_MS")? {
+            self.export_timeout = Some(std::time::Duration::from_millis(millis));
+        }
+        Ok(self)
+    }
+
+    fn batch_config(&self) -> opentelemetry_sdk::trace::BatchConfig {
+        let mut builder = opentelemetry_sdk::trace::BatchConfigBuilder::default();
+        if let Some(delay) = self.schedule_delay {
+            builder = builder.with_scheduled_delay(delay);
+        }
+        if let Some(size) = self.max_queue_size {
+            builder = builder.with_max_queue_size(size);
+        }
+        if let Some(size) = self.max_export_batch_size {
+            builder = builder.with_max_export_batch_size(size);
+        }
+        builder.build()
+    }
+}
+
+/// Returns a `Meter` from the global OpenTelemetry meter provider.
+///
+/// Use this to create typed instruments (counters, histograms, gauges) with full
+/// OTel semantics. When `init_tracing` has installed the OTLP exporter, readings
+/// are exported every `OTLP_METRICS_INTERVAL_MS` milliseconds; otherwise the
+/// returned meter is a no-op, so instruments can be created unconditionally.
+///
+/// # Examples
+///
+/// ```
+/// use vibepro_observe::meter;
+///
+/// let counter = meter("my_service").u64_counter("requests").build();
+/// counter.add(1, &[]);
+/// ```
+#[cfg(feature = "otlp")]
+pub fn meter(scope: &str) -> opentelemetry::metrics::Meter {
+    opentelemetry::global::meter_provider()
+        .meter_with_scope(opentelemetry::InstrumentationScope::builder(scope.to_owned()).build())
+}
+
+/// Parses a comma-separated list of `key=value` pairs, as used by `OTLP_HEADERS`.
+///
+/// Whitespace around keys and values is trimmed; entries without `=` or with an
+/// empty key are ignored.
+///
+/// # Examples
+///
+/// ```
+/// let headers = vibepro_observe::parse_otlp_headers("x-honeycomb-team=key, x-dataset=prod");
+/// assert_eq!(headers["x-dataset"], "prod");
+/// ```
+#[cfg(feature = "otlp")]
+pub fn parse_otlp_headers(value: &str) -> std::collections::HashMap<String, String> {
+    value
+        .split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let key = key.trim();
+            (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Returns the headers configured via `OTLP_HEADERS`, or an empty map when unset.
+#[cfg(feature = "otlp")]
+pub fn otlp_headers_from_env() -> std::collections::HashMap<String, String> {
+    env::var("OTLP_HEADERS")
+        .map(|value| parse_otlp_headers(&value))
+        .unwrap_or_default()
 }
 
+/// Returns the trace sampler configured via `OTLP_SAMPLER` and `OTLP_SAMPLE_RATIO`.
+///
+/// `OTLP_SAMPLER` is one of `always_on`, `always_off`, `traceidratio` or
+/// `parentbased_traceidratio` (the default, also used for unknown values).
+/// `OTLP_SAMPLE_RATIO` is the fraction of traces the ratio samplers keep, clamped
+/// to `0.0..=1.0`; it defaults to `1.0` when unset or unparsable.
+///
+/// # Examples
+///
+/// ```
+/// std::env::set_var("OTLP_SAMPLER", "always_off");
+/// let sampler = vibepro_observe::sampler_from_env();
+/// assert!(format!("{sampler:?}").contains("AlwaysOff"));
+/// # std::env::remove_var("OTLP_SAMPLER");
+/// ```
 #[cfg(feature = "otlp")]
+pub fn sampler_from_env() -> opentelemetry_sdk::trace::Sampler {
+    use opentelemetry_sdk::trace::Sampler;
+
+    let ratio = env::var("OTLP_SAMPLE_RATIO")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|ratio| !ratio.is_nan())
+        .map_or(DEFAULT_SAMPLE_RATIO, |ratio| ratio.clamp(0.0, 1.0));
+
+    match env::var("OTLP_SAMPLER")
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "always_on" => Sampler::AlwaysOn,
+        "always_off" => Sampler::AlwaysOff,
+        "traceidratio" => Sampler::TraceIdRatioBased(ratio),
+        _ => Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(ratio))),
+    }
+}
+
+/// Parses the variable `key`, returning `None` when it is unset or empty.
+#[cfg(feature = "otlp")]
+fn env_number<T>(key: &str) -> Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match env::var(key) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|err| anyhow!("invalid {key}={value:?}: {err}")),
+        _ => Ok(None),
+    }
+}
+
+/// Reads `OTLP_METRICS_INTERVAL_MS`, falling back to the default on absence or parse errors.
+#[cfg(feature = "otlp")]
+fn metrics_interval() -> std::time::Duration {
+    let millis = env::var("OTLP_METRICS_INTERVAL_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_METRICS_INTERVAL_MS);
+    std::time::Duration::from_millis(millis)
+}
+
+#[cfg(feature = "otlp")]
+// The `mut` bindings on the tonic builders are only reassigned with `tls`.
+#[cfg_attr(not(feature = "tls"), allow(unused_mut))]
 fn setup_otlp_exporter(
     endpoint: &str,
     protocol: &str,
     service_name: &str,
+    resource_attributes: &std::collections::HashMap<String, String>,
+    tuning: &ExporterTuning,
 ) -> Result<opentelemetry_sdk::trace::Tracer> {
     debug!(
         target = "vibepro_observe::otlp",
@@ -225,44 +815,166 @@ fn setup_otlp_exporter(
         "initializing OTLP exporter"
     );
     use opentelemetry::{trace::TracerProvider as _, KeyValue};
-    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_otlp::tonic_types::metadata::MetadataMap;
+    use opentelemetry_otlp::{
+        MetricExporter, SpanExporter, WithExportConfig, WithHttpConfig, WithTonicConfig,
+    };
+    use opentelemetry_sdk::metrics::PeriodicReader;
     use opentelemetry_sdk::{trace as sdktrace, Resource};
 
+    let use_http = matches!(
+        protocol.to_lowercase().as_str(),
+        "http" | "http/proto" | "http/protobuf"
+    );
+
+    let mut headers = tuning.headers.clone();
+    headers.extend(otlp_headers_from_env());
+
+    // Read before any exporter is built so a bad path fails fast, runtime or not.
+    #[cfg(feature = "tls")]
+    let tls_config = if use_http {
+        None
+    } else {
+        otlp_tls::tls_config_from_env()?
+    };
+
+    let grpc_metadata = || -> Result<MetadataMap> {
+        let mut header_map = http::HeaderMap::with_capacity(headers.len());
+        for (name, value) in &headers {
+            header_map.insert(
+                http::HeaderName::from_bytes(name.as_bytes())?,
+                http::HeaderValue::try_from(value.as_str())?,
+            );
+        }
+        Ok(MetadataMap::from_headers(header_map))
+    };
+
     let build_exporter = || -> Result<SpanExporter> {
-        if matches!(
-            protocol.to_lowercase().as_str(),
-            "http" | "http/proto" | "http/protobuf"
-        ) {
-            Ok(SpanExporter::builder()
+        if use_http {
+            let mut builder = SpanExporter::builder()
                 .with_http()
                 .with_endpoint(endpoint)
-                .build()?)
+                .with_headers(headers.clone());
+            if let Some(timeout) = tuning.export_timeout {
+                builder = builder.with_timeout(timeout);
+            }
+            Ok(builder.build()?)
         } else {
-            Ok(SpanExporter::builder()
+            let mut builder = SpanExporter::builder()
                 .with_tonic()
                 .with_endpoint(endpoint)
-                .build()?)
+                .with_metadata(grpc_metadata()?);
+            if let Some(timeout) = tuning.export_timeout {
+                builder = builder.with_timeout(timeout);
+            }
+            #[cfg(feature = "tls")]
+            if let Some(tls_config) = tls_config.clone() {
+                builder = builder.with_tls_config(tls_config);
+            }
+            Ok(builder.build()?)
         }
     };
 
-    let resource = Resource::builder_empty()
-        .with_attributes(vec![
-            KeyValue::new("service.name", service_name.to_string()),
-            KeyValue::new("library.name", "vibepro-observe"),
-        ])
-        .build();
+    let mut attributes = vec![
+        KeyValue::new("service.name", service_name.to_string()),
+        KeyValue::new("service.version", service_version()),
+        KeyValue::new("library.name", "vibepro-observe"),
+    ];
+    if let Some(sha) = commit_sha() {
+        attributes.push(KeyValue::new("vcs.ref.head.revision", sha));
+    }
+    #[cfg(feature = "cloud-metadata")]
+    attributes.extend(cloud_metadata::detect(cloud_metadata::DETECT_TIMEOUT));
+    #[cfg(feature = "datadog")]
+    datadog::apply_resource_attributes(&mut attributes);
+    attributes.retain(|kv| !resource_attributes.contains_key(kv.key.as_str()));
+    attributes.extend(
+        resource_attributes
+            .iter()
+            .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+    );
 
-    let mut provider_builder = sdktrace::SdkTracerProvider::builder().with_resource(resource);
+    let resource = Resource::builder_empty().with_attributes(attributes).build();
+
+    let mut provider_builder = sdktrace::SdkTracerProvider::builder()
+        .with_resource(resource.clone())
+        .with_sampler(sampler_from_env());
     if tokio::runtime::Handle::try_current().is_ok() {
         let exporter = build_exporter()?;
-        provider_builder = provider_builder
-            .with_span_processor(sdktrace::BatchSpanProcessor::builder(exporter).build());
+        #[cfg(feature = "redact")]
+        let exporter = redact::RedactingSpanExporter::from_env(exporter);
+        let processor = sdktrace::BatchSpanProcessor::builder(exporter)
+            .with_batch_config(tuning.batch_config())
+            .build();
+        provider_builder = provider_builder.with_span_processor(processor);
     } else {
         let exporter = build_exporter()?;
+        #[cfg(feature = "redact")]
+        let exporter = redact::RedactingSpanExporter::from_env(exporter);
         provider_builder =
             provider_builder.with_span_processor(sdktrace::SimpleSpanProcessor::new(exporter));
     }
 
+    #[cfg(feature = "logs")]
+    {
+        use opentelemetry_otlp::LogExporter;
+        use opentelemetry_sdk::logs::SdkLoggerProvider;
+
+        let logs_endpoint = otlp_logs::logs_endpoint(endpoint);
+        let log_exporter = if use_http {
+            LogExporter::builder()
+                .with_http()
+                .with_endpoint(&logs_endpoint)
+                .with_headers(headers.clone())
+                .build()?
+        } else {
+            let mut builder = LogExporter::builder()
+                .with_tonic()
+                .with_endpoint(&logs_endpoint)
+                .with_metadata(grpc_metadata()?);
+            #[cfg(feature = "tls")]
+            if let Some(tls_config) = tls_config.clone() {
+                builder = builder.with_tls_config(tls_config);
+            }
+            builder.build()?
+        };
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_resource(resource.clone())
+            .with_batch_exporter(log_exporter)
+            .build();
+        let _ = OTLP_LOGGER_PROVIDER.set(logger_provider);
+    }
+
+    let metric_exporter = if use_http {
+        MetricExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .with_headers(headers.clone())
+            .build()?
+    } else {
+        let mut builder = MetricExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint)
+            .with_metadata(grpc_metadata()?);
+        #[cfg(feature = "tls")]
+        if let Some(tls_config) = tls_config.clone() {
+            builder = builder.with_tls_config(tls_config);
+        }
+        builder.build()?
+    };
+    let reader = PeriodicReader::builder(metric_exporter)
+        .with_interval(metrics_interval())
+        .build();
+    let meter_provider = SdkMeterProvider::builder().with_reader(reader);
+    #[cfg(feature = "prometheus")]
+    let meter_provider = match prometheus::take_reader()? {
+        Some(prometheus_reader) => meter_provider.with_reader(prometheus_reader),
+        None => meter_provider,
+    };
+    let meter_provider = meter_provider.with_resource(resource).build();
+    let _ = OTLP_METER_PROVIDER.set(meter_provider.clone());
+    opentelemetry::global::set_meter_provider(meter_provider);
+
     let tracer_provider = provider_builder.build();
     let tracer = tracer_provider.tracer("vibepro-observe");
     let provider_handle = tracer_provider.clone();
@@ -272,16 +984,23 @@ fn setup_otlp_exporter(
     Ok(tracer)
 }
 
+/// How long [`shutdown_tracing`] waits for buffered telemetry to flush.
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Gracefully shuts down the OTLP tracer provider, flushing any buffered spans.
 ///
+/// With the `logs` feature, buffered log records are flushed the same way.
+///
 /// It is recommended to call this function at the end of the application's lifecycle
 /// to ensure that all telemetry data is sent before the process exits. The function
 /// is safe to call multiple times and will do nothing if no tracer was initialized.
 ///
+/// Waits at most 5 seconds; use [`shutdown_tracing_with_timeout`] to choose the bound.
+///
 /// # Errors
 ///
 /// Returns an error if the shutdown process fails for a reason other than the
-/// tracer already being shut down.
+/// tracer already being shut down, or does not finish within 5 seconds.
 ///
 /// # Examples
 ///
@@ -293,10 +1012,78 @@ fn setup_otlp_exporter(
 /// # Ok(())
 /// # }
 /// ```
-#[cfg(feature = "otlp")]
 pub fn shutdown_tracing() -> Result<()> {
+    shutdown_tracing_with_timeout(DEFAULT_SHUTDOWN_TIMEOUT)
+}
+
+/// Like [`shutdown_tracing`], but waits at most `timeout` for the flush.
+///
+/// The shutdown runs on a separate thread so an unreachable OTLP endpoint cannot
+/// block the caller past `timeout`. On timeout that thread is left to finish in the
+/// background.
+///
+/// # Errors
+///
+/// Returns an error if the shutdown process fails for a reason other than the
+/// tracer already being shut down, or `tracing shutdown timed out` if it does not
+/// finish within `timeout`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// vibepro_observe::shutdown_tracing_with_timeout(Duration::from_secs(1))?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[cfg(feature = "otlp")]
+pub fn shutdown_tracing_with_timeout(timeout: Duration) -> Result<()> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::Builder::new()
+        .name("vibepro-observe-shutdown".to_string())
+        .spawn(move || {
+            let _ = tx.send(shutdown_providers());
+        })?;
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(_) => Err(anyhow!("tracing shutdown timed out")),
+    }
+}
+
+/// A no-op version of `shutdown_tracing_with_timeout` for when the `otlp` feature is
+/// not enabled.
+///
+/// This allows for unconditional calls to `shutdown_tracing` in application code
+/// without needing to use `#[cfg]` attributes.
+#[cfg(not(feature = "otlp"))]
+pub fn shutdown_tracing_with_timeout(_timeout: Duration) -> Result<()> {
+    Ok(())
+}
+
+/// Shuts down every installed provider, blocking until each one has flushed.
+#[cfg(feature = "otlp")]
+fn shutdown_providers() -> Result<()> {
     use opentelemetry_sdk::error::OTelSdkError;
 
+    #[cfg(feature = "logs")]
+    if let Some(provider) = OTLP_LOGGER_PROVIDER.get() {
+        match provider.shutdown() {
+            Ok(()) | Err(OTelSdkError::AlreadyShutdown) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    #[cfg(feature = "prometheus")]
+    prometheus::shutdown()?;
+
+    if let Some(provider) = OTLP_METER_PROVIDER.get() {
+        match provider.shutdown() {
+            Ok(()) | Err(OTelSdkError::AlreadyShutdown) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
     if let Some(provider) = OTLP_TRACER_PROVIDER.get() {
         match provider.shutdown() {
             Ok(()) => Ok(()),
@@ -308,11 +1095,3 @@ pub fn shutdown_tracing() -> Result<()> {
     }
 }
 
-/// A no-op version of `shutdown_tracing` for when the `otlp` feature is not enabled.
-///
-/// This allows for unconditional calls to `shutdown_tracing` in application code
-/// without needing to use `#[cfg]` attributes.
-#[cfg(not(feature = "otlp"))]
-pub fn shutdown_tracing() -> Result<()> {
-    Ok(())
-}
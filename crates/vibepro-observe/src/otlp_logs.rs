@@ -0,0 +1,48 @@
+//! OTLP log export.
+//!
+//! With the `logs` feature and `VIBEPRO_OBSERVE=1`, `init_tracing` installs
+//! [`logs_layer`] next to the JSON layer: every `tracing` event that passes
+//! `RUST_LOG` is still written to stdout and is also turned into an OTLP
+//! `LogRecord` by `opentelemetry_appender_tracing` and exported to
+//! [`logs_endpoint`].
+//!
+//! With the `redact` feature, log bodies and attributes are redacted as the
+//! bridge records them (see [`RedactingLoggerProvider`](crate::redact::RedactingLoggerProvider)).
+//!
+//! Events emitted by the exporter's own stack (`opentelemetry*`, `tonic`,
+//! `hyper`, `h2`, `tower`, `reqwest`) are not bridged, so exporting a batch
+//! cannot produce more records to export.
+
+use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+use opentelemetry_sdk::logs::SdkLoggerProvider;
+use std::env;
+use tracing::Subscriber;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Target prefixes of crates whose events would feed back into the exporter.
+const EXPORTER_TARGETS: &[&str] = &["opentelemetry", "tonic", "hyper", "h2", "tower", "reqwest"];
+
+/// Returns the endpoint for log export from `OTLP_LOGS_ENDPOINT`, falling back
+/// to `default` (the trace endpoint) when the variable is unset or empty.
+pub fn logs_endpoint(default: &str) -> String {
+    env::var("OTLP_LOGS_ENDPOINT")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Returns a layer that forwards `tracing` events to `provider` as log records.
+pub fn logs_layer<S>(provider: &SdkLoggerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    #[cfg(feature = "redact")]
+    let provider = &crate::redact::RedactingLoggerProvider::from_env(provider.clone());
+    OpenTelemetryTracingBridge::new(provider).with_filter(filter_fn(|metadata| {
+        let target = metadata.target();
+        !EXPORTER_TARGETS.iter().any(|prefix| target.starts_with(prefix))
+    }))
+}
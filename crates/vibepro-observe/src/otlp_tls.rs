@@ -0,0 +1,71 @@
+//! TLS for the OTLP gRPC exporter.
+//!
+//! With the `tls` feature, the tonic exporters (traces, metrics and, with the
+//! `logs` feature, logs) are built with a `ClientTlsConfig` whenever one of
+//! these variables is set:
+//!
+//! - `OTLP_CA_CERT_PATH`: PEM file with the CA certificate(s) that signed the
+//!   collector's certificate.
+//! - `OTLP_CLIENT_CERT_PATH`, `OTLP_CLIENT_KEY_PATH`: PEM client certificate and
+//!   private key for mutual TLS. Both must be set together.
+//!
+//! The files are read synchronously while the exporter is built, so this works
+//! with and without a Tokio runtime. The HTTP protocol ignores these variables.
+
+use anyhow::{bail, Context, Result};
+use opentelemetry_otlp::tonic_types::transport::{Certificate, ClientTlsConfig, Identity};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Builds the TLS config from `OTLP_CA_CERT_PATH`, `OTLP_CLIENT_CERT_PATH` and
+/// `OTLP_CLIENT_KEY_PATH`, or returns `None` when none of them is set.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read, or only one of the client
+/// certificate and key is set.
+pub fn tls_config_from_env() -> Result<Option<ClientTlsConfig>> {
+    let path = |key: &str| env::var_os(key).filter(|v| !v.is_empty()).map(PathBuf::from);
+    tls_config(
+        path("OTLP_CA_CERT_PATH").as_deref(),
+        path("OTLP_CLIENT_CERT_PATH").as_deref(),
+        path("OTLP_CLIENT_KEY_PATH").as_deref(),
+    )
+}
+
+/// Builds a TLS config from PEM files, or returns `None` when no path is given.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read, or only one of `client_cert` and
+/// `client_key` is given.
+pub fn tls_config(
+    ca_cert: Option<&Path>,
+    client_cert: Option<&Path>,
+    client_key: Option<&Path>,
+) -> Result<Option<ClientTlsConfig>> {
+    if ca_cert.is_none() && client_cert.is_none() && client_key.is_none() {
+        return Ok(None);
+    }
+
+    let mut config = ClientTlsConfig::new();
+    if let Some(path) = ca_cert {
+        config = config.ca_certificate(Certificate::from_pem(read_pem(path, "CA certificate")?));
+    }
+    match (client_cert, client_key) {
+        (Some(cert), Some(key)) => {
+            config = config.identity(Identity::from_pem(
+                read_pem(cert, "client certificate")?,
+                read_pem(key, "client key")?,
+            ));
+        }
+        (None, None) => {}
+        _ => bail!("OTLP_CLIENT_CERT_PATH and OTLP_CLIENT_KEY_PATH must be set together"),
+    }
+    Ok(Some(config))
+}
+
+fn read_pem(path: &Path, what: &str) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("failed to read OTLP {what} {}", path.display()))
+}
@@ -0,0 +1,73 @@
+//! Panics as structured log events.
+//!
+//! [`install_panic_hook`] wraps the current panic hook so every panic is first
+//! logged as a `tracing::error!` event with target `vibepro_observe::panic` and
+//! the fields:
+//!
+//! - `panic.message`: the panic payload, when it is a string.
+//! - `panic.file`, `panic.line`: where the panic happened.
+//! - `panic.backtrace`: a backtrace, only when `RUST_BACKTRACE` (or
+//!   `RUST_LIB_BACKTRACE`) enables capturing one.
+//!
+//! The previous hook runs afterwards, so the usual stderr message and abort
+//! behavior are unchanged.
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::Cell;
+use std::panic;
+use std::sync::Once;
+
+static INSTALL: Once = Once::new();
+
+thread_local! {
+    /// Set while this thread is inside the hook, so a panic raised while
+    /// logging is not logged again.
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs a panic hook that logs panics as `tracing::error!` events.
+///
+/// Only the first call has an effect. Install it after [`init_tracing`](crate::init_tracing)
+/// so the events reach the configured subscriber.
+///
+/// # Examples
+///
+/// ```
+/// vibepro_observe::init_tracing("my-service")?;
+/// vibepro_observe::install_panic_hook();
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn install_panic_hook() {
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // `try_with` fails instead of panicking once thread-locals are torn down.
+            let entered = IN_HOOK.try_with(|in_hook| !in_hook.replace(true));
+            if entered == Ok(true) {
+                let message = info
+                    .payload()
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str));
+                let location = info.location();
+                let backtrace = Backtrace::capture();
+                let backtrace = (backtrace.status() == BacktraceStatus::Captured)
+                    .then(|| backtrace.to_string());
+
+                tracing::event!(
+                    target: "vibepro_observe::panic",
+                    tracing::Level::ERROR,
+                    panic.message = message,
+                    panic.file = location.map(|location| location.file()),
+                    panic.line = location.map(|location| location.line()),
+                    panic.backtrace = backtrace.as_deref(),
+                    "panic"
+                );
+
+                let _ = IN_HOOK.try_with(|in_hook| in_hook.set(false));
+            }
+
+            previous(info);
+        }));
+    });
+}
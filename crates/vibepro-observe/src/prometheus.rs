@@ -0,0 +1,176 @@
+//! Prometheus scrape endpoint.
+//!
+//! With the `prometheus` feature, OpenTelemetry metrics (instruments created via
+//! [`meter`](crate::meter), and [`record_metric`](crate::record_metric) readings)
+//! are served in the Prometheus text exposition format on `GET /metrics` by
+//! [`serve_metrics`]. When `VIBEPRO_METRICS_PORT` is set, `init_tracing` starts
+//! the server on `0.0.0.0:<port>` itself.
+//!
+//! Metrics are collected by the `opentelemetry-prometheus` reader on each scrape.
+//! With the OTLP exporter installed, the reader is added to the OTLP meter
+//! provider and both see the same instruments; otherwise [`serve_metrics`]
+//! installs a meter provider of its own.
+//!
+//! Each connection is answered on its own thread, with read and write timeouts
+//! of [`CONNECTION_TIMEOUT`], so a slow scraper does not hold up the others.
+
+use anyhow::{anyhow, Context as _, Result};
+use once_cell::sync::OnceCell;
+use opentelemetry::KeyValue;
+use opentelemetry_prometheus::PrometheusExporter;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use prometheus::{Encoder, Registry, TextEncoder};
+use std::collections::HashMap;
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// Read and write timeout for each `/metrics` connection.
+pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Registry the reader collects into on each scrape.
+static REGISTRY: OnceCell<Registry> = OnceCell::new();
+/// Set once the reader has been handed to a meter provider.
+static READER_TAKEN: OnceCell<()> = OnceCell::new();
+/// Meter provider installed by `serve_metrics` when OTLP did not install one.
+static METER_PROVIDER: OnceCell<SdkMeterProvider> = OnceCell::new();
+/// Gauges backing `record_metric`, by metric key.
+static GAUGES: OnceCell<Mutex<HashMap<String, opentelemetry::metrics::Gauge<f64>>>> =
+    OnceCell::new();
+
+fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(Registry::new)
+}
+
+/// Returns the `/metrics` address from `VIBEPRO_METRICS_PORT`, or `None` when unset.
+///
+/// # Errors
+///
+/// Returns an error if the variable is set but not a port number.
+pub fn addr_from_env() -> Result<Option<SocketAddr>> {
+    match env::var("VIBEPRO_METRICS_PORT") {
+        Ok(port) if !port.trim().is_empty() => {
+            let port: u16 = port
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid VIBEPRO_METRICS_PORT={port:?}"))?;
+            Ok(Some(SocketAddr::from(([0, 0, 0, 0], port))))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns the reader feeding `/metrics` the first time it is called, and
+/// `None` afterwards: a reader can only be registered with one provider.
+///
+/// # Errors
+///
+/// Returns an error if the reader cannot register with the registry.
+pub(crate) fn take_reader() -> Result<Option<PrometheusExporter>> {
+    if READER_TAKEN.set(()).is_err() {
+        return Ok(None);
+    }
+    let reader = opentelemetry_prometheus::exporter()
+        .with_registry(registry().clone())
+        .build()
+        .map_err(|err| anyhow!("failed to build Prometheus reader: {err}"))?;
+    Ok(Some(reader))
+}
+
+/// Serves the Prometheus exposition on `GET /metrics` at `addr`.
+///
+/// Call it after [`init_tracing`](crate::init_tracing) so the reader is shared
+/// with the OTLP meter provider when there is one. The listener is bound before
+/// this returns; connections are then accepted on a background thread and each
+/// is answered on a thread of its own.
+///
+/// # Errors
+///
+/// Returns an error if `addr` cannot be bound.
+///
+/// # Examples
+///
+/// ```no_run
+/// vibepro_observe::init_tracing("my-service")?;
+/// vibepro_observe::prometheus::serve_metrics(([0, 0, 0, 0], 9464).into())?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn serve_metrics(addr: SocketAddr) -> Result<()> {
+    if let Some(reader) = take_reader()? {
+        let provider = SdkMeterProvider::builder().with_reader(reader).build();
+        opentelemetry::global::set_meter_provider(provider.clone());
+        let _ = METER_PROVIDER.set(provider);
+    }
+
+    let listener = TcpListener::bind(addr)
+        .with_context(|| format!("failed to bind Prometheus endpoint on {addr}"))?;
+    thread::Builder::new()
+        .name("vibepro-observe-prometheus".to_string())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = thread::Builder::new()
+                    .name("vibepro-observe-prometheus-conn".to_string())
+                    .spawn(move || respond(stream));
+            }
+        })?;
+    Ok(())
+}
+
+/// Shuts down the meter provider installed by [`serve_metrics`], if any.
+pub(crate) fn shutdown() -> Result<()> {
+    use opentelemetry_sdk::error::OTelSdkError;
+
+    match METER_PROVIDER.get().map(SdkMeterProvider::shutdown) {
+        None | Some(Ok(())) | Some(Err(OTelSdkError::AlreadyShutdown)) => Ok(()),
+        Some(Err(err)) => Err(anyhow!(err)),
+    }
+}
+
+/// Records `value` on the gauge named `key`, creating it on first use.
+pub(crate) fn record(key: &str, value: f64, labels: &[(&str, &str)]) {
+    let gauges = GAUGES.get_or_init(Default::default);
+    let Ok(mut gauges) = gauges.lock() else {
+        return;
+    };
+    let gauge = gauges
+        .entry(key.to_string())
+        .or_insert_with(|| crate::meter("vibepro_observe").f64_gauge(key.to_string()).build());
+    let attributes: Vec<KeyValue> = labels
+        .iter()
+        .map(|(name, value)| KeyValue::new(name.to_string(), value.to_string()))
+        .collect();
+    gauge.record(value, &attributes);
+}
+
+/// Answers one HTTP request with the exposition or a 404.
+fn respond(mut stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let encoder = TextEncoder::new();
+    let (status, body) = if path == "/metrics" || path.starts_with("/metrics?") {
+        let mut body = Vec::new();
+        encoder
+            .encode(&registry().gather(), &mut body)
+            .map_err(std::io::Error::other)?;
+        ("200 OK", body)
+    } else {
+        ("404 Not Found", b"not found\n".to_vec())
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        encoder.format_type(),
+        body.len()
+    )?;
+    stream.write_all(&body)
+}
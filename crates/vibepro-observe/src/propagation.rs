@@ -0,0 +1,86 @@
+//! Trace context propagation across service boundaries.
+//!
+//! With the `propagation` feature, `init_tracing` installs the propagators
+//! named in `OTLP_PROPAGATORS` as the global OpenTelemetry text map propagator:
+//!
+//! - `tracecontext`: W3C `traceparent` / `tracestate` headers (the default).
+//! - `b3`: the single `b3` header.
+//! - `b3multi`: the `X-B3-TraceId`, `X-B3-SpanId` and `X-B3-Sampled` headers.
+//!
+//! Several can be combined, e.g. `OTLP_PROPAGATORS=tracecontext,b3multi`; all
+//! of them are injected and the first one that matches is used on extraction.
+//! Unknown names are ignored.
+//!
+//! [`inject`] and [`extract`] wrap the global propagator for `http::HeaderMap`,
+//! so callers do not need the OpenTelemetry global API:
+//!
+//! ```
+//! use tracing_opentelemetry::OpenTelemetrySpanExt;
+//!
+//! // Incoming request: continue the caller's trace.
+//! let incoming = http::HeaderMap::new();
+//! let span = tracing::info_span!("handle_request");
+//! let _ = span.set_parent(vibepro_observe::propagation::extract(&incoming));
+//!
+//! // Outgoing request: pass the current trace on.
+//! let mut outgoing = http::HeaderMap::new();
+//! span.in_scope(|| vibepro_observe::propagation::inject(&mut outgoing));
+//! ```
+
+use http::HeaderMap;
+use opentelemetry::propagation::{TextMapCompositePropagator, TextMapPropagator};
+use opentelemetry::Context;
+use opentelemetry_http::{HeaderExtractor, HeaderInjector};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_zipkin::{B3Encoding, Propagator as B3Propagator};
+use std::env;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Propagator used when `OTLP_PROPAGATORS` is unset or names none we know.
+const DEFAULT_PROPAGATOR: &str = "tracecontext";
+
+/// Builds the composite propagator named by `OTLP_PROPAGATORS`.
+pub fn propagator_from_env() -> TextMapCompositePropagator {
+    let names = env::var("OTLP_PROPAGATORS").unwrap_or_default();
+    let mut propagators: Vec<Box<dyn TextMapPropagator + Send + Sync>> = names
+        .split(',')
+        .filter_map(|name| propagator(&name.trim().to_lowercase()))
+        .collect();
+    if propagators.is_empty() {
+        propagators.extend(propagator(DEFAULT_PROPAGATOR));
+    }
+    TextMapCompositePropagator::new(propagators)
+}
+
+/// Returns the propagator for one `OTLP_PROPAGATORS` entry.
+fn propagator(name: &str) -> Option<Box<dyn TextMapPropagator + Send + Sync>> {
+    match name {
+        "tracecontext" => Some(Box::new(TraceContextPropagator::new())),
+        "b3" => Some(Box::new(B3Propagator::with_encoding(B3Encoding::SingleHeader))),
+        "b3multi" => Some(Box::new(B3Propagator::with_encoding(B3Encoding::MultipleHeader))),
+        _ => None,
+    }
+}
+
+/// Installs [`propagator_from_env`] as the global text map propagator.
+pub(crate) fn install() {
+    opentelemetry::global::set_text_map_propagator(propagator_from_env());
+}
+
+/// Writes the context of the current `tracing` span into `headers`.
+pub fn inject(headers: &mut HeaderMap) {
+    let context = tracing::Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(headers));
+    });
+}
+
+/// Reads a remote trace context from `headers`.
+///
+/// Pass the result to `OpenTelemetrySpanExt::set_parent` to continue the
+/// caller's trace, or attach it to make it the current OpenTelemetry context.
+pub fn extract(headers: &HeaderMap) -> Context {
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(headers))
+    })
+}
@@ -0,0 +1,196 @@
+//! Log event rate limiting.
+//!
+//! [`RateLimitLayer`] wraps a layer (normally the JSON fmt layer) and caps how
+//! many events per second reach it, so a hot loop cannot flood the log shipper.
+//! Spans are always forwarded. Suppressed events are counted and reported to
+//! the wrapped layer by a single `info` event,
+//! `log rate limit reached; {n} events suppressed`, at most once per second.
+//!
+//! The notice is delivered straight to the wrapped layer rather than through
+//! `tracing::info!`, because `tracing` drops events emitted while another event
+//! is being dispatched.
+//!
+//! `init_tracing` installs the layer when `VIBEPRO_LOG_RATE_LIMIT` is set to a
+//! positive number of events per second.
+
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use std::any::TypeId;
+use std::env;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::callsite::{Callsite, Identifier};
+use tracing::field::{FieldSet, Value};
+use tracing::metadata::Kind;
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Minimum interval between two suppression notices.
+const NOTICE_INTERVAL: Duration = Duration::from_secs(1);
+
+struct NoticeCallsite;
+
+static NOTICE_CALLSITE: NoticeCallsite = NoticeCallsite;
+
+static NOTICE_METADATA: Metadata<'static> = Metadata::new(
+    "log rate limit notice",
+    "vibepro_observe::rate_limit",
+    Level::INFO,
+    Some(file!()),
+    Some(line!()),
+    Some(module_path!()),
+    FieldSet::new(&["message", "suppressed"], Identifier(&NOTICE_CALLSITE)),
+    Kind::EVENT,
+);
+
+impl Callsite for NoticeCallsite {
+    fn set_interest(&self, _interest: Interest) {}
+
+    fn metadata(&self) -> &Metadata<'_> {
+        &NOTICE_METADATA
+    }
+}
+
+/// Layer wrapper that forwards at most a fixed number of events per second.
+pub struct RateLimitLayer<L> {
+    inner: L,
+    limiter: Option<DefaultDirectRateLimiter>,
+    suppressed: AtomicU64,
+    last_notice: Mutex<Option<Instant>>,
+}
+
+impl<L> RateLimitLayer<L> {
+    /// Wraps `inner`, allowing at most `max_events_per_second` events through.
+    ///
+    /// A value of `0` disables limiting.
+    pub fn new(inner: L, max_events_per_second: u32) -> Self {
+        Self {
+            inner,
+            limiter: NonZeroU32::new(max_events_per_second)
+                .map(|rate| RateLimiter::direct(Quota::per_second(rate))),
+            suppressed: AtomicU64::new(0),
+            last_notice: Mutex::new(None),
+        }
+    }
+
+    /// Wraps `inner` using the limit in `VIBEPRO_LOG_RATE_LIMIT`; unset or
+    /// invalid values disable limiting.
+    pub fn from_env(inner: L) -> Self {
+        let rate = env::var("VIBEPRO_LOG_RATE_LIMIT")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(0);
+        Self::new(inner, rate)
+    }
+
+    /// Returns the number of suppressed events not yet reported by a notice.
+    pub fn pending_suppressed(&self) -> u64 {
+        self.suppressed.load(Ordering::Relaxed)
+    }
+
+    fn allow_event(&self) -> bool {
+        match &self.limiter {
+            Some(limiter) => limiter.check().is_ok(),
+            None => true,
+        }
+    }
+
+    /// Claims the notice slot if none was emitted within `NOTICE_INTERVAL`.
+    fn take_notice_slot(&self) -> Option<u64> {
+        let now = Instant::now();
+        let mut last = self.last_notice.try_lock().ok()?;
+        if last.is_some_and(|at| now.duration_since(at) < NOTICE_INTERVAL) {
+            return None;
+        }
+        *last = Some(now);
+        Some(self.suppressed.swap(0, Ordering::Relaxed))
+    }
+}
+
+impl<S, L> Layer<S> for RateLimitLayer<L>
+where
+    S: Subscriber,
+    L: Layer<S>,
+{
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber);
+    }
+
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.inner.register_callsite(metadata)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.enabled(metadata, ctx)
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_new_span(attrs, id, ctx);
+    }
+
+    fn on_record(&self, span: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        self.inner.on_record(span, values, ctx);
+    }
+
+    fn on_follows_from(&self, span: &Id, follows: &Id, ctx: Context<'_, S>) {
+        self.inner.on_follows_from(span, follows, ctx);
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.event_enabled(event, ctx)
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if self.allow_event() {
+            self.inner.on_event(event, ctx);
+            return;
+        }
+
+        self.suppressed.fetch_add(1, Ordering::Relaxed);
+        let Some(n) = self.take_notice_slot() else {
+            return;
+        };
+
+        let fields = NOTICE_METADATA.fields();
+        let (Some(message_field), Some(suppressed_field)) =
+            (fields.field("message"), fields.field("suppressed"))
+        else {
+            return;
+        };
+        let message = format!("log rate limit reached; {n} events suppressed");
+        let values = [
+            (&message_field, Some(&message.as_str() as &dyn Value)),
+            (&suppressed_field, Some(&n as &dyn Value)),
+        ];
+        let value_set = fields.value_set(&values);
+        self.inner
+            .on_event(&Event::new(&NOTICE_METADATA, &value_set), ctx);
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+
+    fn on_id_change(&self, old: &Id, new: &Id, ctx: Context<'_, S>) {
+        self.inner.on_id_change(old, new, ctx);
+    }
+
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+        if id == TypeId::of::<Self>() {
+            Some(self as *const Self as *const ())
+        } else {
+            self.inner.downcast_raw(id)
+        }
+    }
+}
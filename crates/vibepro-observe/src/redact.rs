@@ -0,0 +1,459 @@
+//! Redaction of PII in log output.
+//!
+//! With the `redact` feature, every match of the patterns named in
+//! `VIBEPRO_REDACT_PATTERNS` is replaced with `[REDACTED]` before it leaves the
+//! process:
+//!
+//! - The stdout layer writes through [`RedactingMakeWriter`], which covers field
+//!   values and messages in every log format.
+//! - With the `otlp` feature, spans are exported through [`RedactingSpanExporter`],
+//!   which redacts string attributes, event names and event attributes, and error
+//!   descriptions.
+//! - With the `logs` feature, the OTLP log bridge records into
+//!   [`RedactingLoggerProvider`], which redacts each log body and string attribute.
+//!
+//! Built-in patterns:
+//!
+//! - `email`: email addresses.
+//! - `jwt`: JSON Web Tokens (three dot-separated base64url parts starting with `eyJ`).
+//! - `credit_card`: 13 to 19 digit card numbers, optionally grouped with spaces or
+//!   dashes, that pass the Luhn check.
+//! - `ssn`: US social security numbers (`123-45-6789`).
+//! - `aws_access_key`: AWS access key IDs (`AKIA…`, `ASIA…`).
+//! - `bearer`: `Bearer <token>` credentials.
+//! - `ipv4`: IPv4 addresses.
+//!
+//! `VIBEPRO_REDACT_PATTERNS` is a comma-separated list of names and defaults to
+//! `email,jwt,credit_card`; set it to `none` to turn redaction off. Unknown names
+//! are ignored. The patterns are compiled into one regex when the subscriber is
+//! built, so a line without a match costs a single scan.
+
+#[cfg(feature = "logs")]
+use opentelemetry::logs::{AnyValue, LogRecord, Logger, LoggerProvider, Severity};
+#[cfg(feature = "otlp")]
+use opentelemetry::trace::Status;
+#[cfg(feature = "otlp")]
+use opentelemetry::{Array, KeyValue, StringValue, Value};
+#[cfg(feature = "logs")]
+use opentelemetry::{InstrumentationScope, Key, SpanId, TraceFlags, TraceId};
+#[cfg(feature = "otlp")]
+use opentelemetry_sdk::error::OTelSdkResult;
+#[cfg(feature = "logs")]
+use opentelemetry_sdk::logs::{SdkLogRecord, SdkLogger, SdkLoggerProvider};
+#[cfg(feature = "otlp")]
+use opentelemetry_sdk::trace::{SpanData, SpanExporter};
+#[cfg(feature = "otlp")]
+use opentelemetry_sdk::Resource;
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+use std::env;
+use std::io::{self, Write};
+use std::sync::Arc;
+#[cfg(feature = "logs")]
+use std::time::SystemTime;
+#[cfg(feature = "otlp")]
+use std::{future::Future, time::Duration};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Replacement for every redacted match.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Patterns used when `VIBEPRO_REDACT_PATTERNS` is unset.
+pub const DEFAULT_PATTERNS: &[&str] = &["email", "jwt", "credit_card"];
+
+/// Built-in patterns by name.
+const BUILT_IN_PATTERNS: &[(&str, &str)] = &[
+    ("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"),
+    ("jwt", r"eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+"),
+    ("credit_card", r"\b(?:\d[ -]?){12,18}\d\b"),
+    ("ssn", r"\b\d{3}-\d{2}-\d{4}\b"),
+    ("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("bearer", r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]+=*"),
+    ("ipv4", r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b"),
+];
+
+/// A compiled set of redaction patterns.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    regex: Regex,
+}
+
+impl Redactor {
+    /// Compiles the built-in patterns in `names`, or returns `None` if none of
+    /// them is known.
+    pub fn new(names: &[&str]) -> Option<Self> {
+        let alternatives: Vec<String> = BUILT_IN_PATTERNS
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .map(|(name, pattern)| format!("(?P<{name}>{pattern})"))
+            .collect();
+        if alternatives.is_empty() {
+            return None;
+        }
+        let regex = Regex::new(&alternatives.join("|")).expect("built-in patterns are valid");
+        Some(Self { regex })
+    }
+
+    /// Compiles the patterns named in `VIBEPRO_REDACT_PATTERNS`, falling back to
+    /// [`DEFAULT_PATTERNS`] when it is unset.
+    pub fn from_env() -> Option<Self> {
+        match env::var("VIBEPRO_REDACT_PATTERNS") {
+            Ok(value) => {
+                let names: Vec<String> =
+                    value.split(',').map(|name| name.trim().to_lowercase()).collect();
+                Self::new(&names.iter().map(String::as_str).collect::<Vec<_>>())
+            }
+            Err(_) => Self::new(DEFAULT_PATTERNS),
+        }
+    }
+
+    /// Replaces every match in `text` with [`REDACTED`].
+    ///
+    /// Returns `text` unchanged, without allocating, when nothing matches.
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        self.regex.replace_all(text, |captures: &Captures<'_>| {
+            let matched = &captures[0];
+            if captures.name("credit_card").is_some() && !luhn_valid(matched) {
+                matched.to_string()
+            } else {
+                REDACTED.to_string()
+            }
+        })
+    }
+
+    /// Redacts `value`, keeping it when nothing matches.
+    #[cfg(feature = "otlp")]
+    fn redact_string(&self, value: StringValue) -> StringValue {
+        match self.redact(value.as_str()) {
+            Cow::Borrowed(_) => value,
+            Cow::Owned(redacted) => redacted.into(),
+        }
+    }
+
+    /// Redacts the strings in an attribute value.
+    #[cfg(feature = "otlp")]
+    fn redact_value(&self, value: Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.redact_string(text)),
+            Value::Array(Array::String(texts)) => Value::Array(Array::String(
+                texts
+                    .into_iter()
+                    .map(|text| self.redact_string(text))
+                    .collect(),
+            )),
+            other => other,
+        }
+    }
+
+    /// Redacts the string values of `attributes` in place.
+    #[cfg(feature = "otlp")]
+    fn redact_attributes(&self, attributes: &mut [KeyValue]) {
+        for attribute in attributes {
+            let value = std::mem::replace(&mut attribute.value, Value::Bool(false));
+            attribute.value = self.redact_value(value);
+        }
+    }
+
+    /// Redacts the strings in a log body or attribute value, including nested ones.
+    #[cfg(feature = "logs")]
+    fn redact_any_value(&self, value: AnyValue) -> AnyValue {
+        match value {
+            AnyValue::String(text) => AnyValue::String(self.redact_string(text)),
+            AnyValue::ListAny(values) => AnyValue::ListAny(Box::new(
+                values
+                    .into_iter()
+                    .map(|value| self.redact_any_value(value))
+                    .collect(),
+            )),
+            AnyValue::Map(values) => AnyValue::Map(Box::new(
+                values
+                    .into_iter()
+                    .map(|(key, value)| (key, self.redact_any_value(value)))
+                    .collect(),
+            )),
+            other => other,
+        }
+    }
+}
+
+/// Returns whether the digits in `number` pass the Luhn checksum.
+fn luhn_valid(number: &str) -> bool {
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2 == 1, digit * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => digit,
+        })
+        .sum();
+    (13..=19).contains(&digits.len()) && sum.is_multiple_of(10)
+}
+
+/// `MakeWriter` that redacts each write of `inner`.
+///
+/// `tracing_subscriber::fmt` formats a whole event into a buffer and writes it
+/// with one call, so matches are never split across writes.
+#[derive(Debug, Clone)]
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+    redactor: Option<Arc<Redactor>>,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    /// Wraps `inner`, redacting with `redactor` (or passing writes through on `None`).
+    pub fn new(inner: M, redactor: Option<Redactor>) -> Self {
+        Self {
+            inner,
+            redactor: redactor.map(Arc::new),
+        }
+    }
+
+    /// Wraps `inner` with the patterns configured by `VIBEPRO_REDACT_PATTERNS`.
+    pub fn from_env(inner: M) -> Self {
+        Self::new(inner, Redactor::from_env())
+    }
+}
+
+impl<'a, M> MakeWriter<'a> for RedactingMakeWriter<M>
+where
+    M: MakeWriter<'a>,
+{
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter {
+            inner: self.inner.make_writer(),
+            redactor: self.redactor.clone(),
+        }
+    }
+}
+
+/// Writer returned by [`RedactingMakeWriter`].
+#[derive(Debug)]
+pub struct RedactingWriter<W> {
+    inner: W,
+    redactor: Option<Arc<Redactor>>,
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = match (&self.redactor, std::str::from_utf8(buf)) {
+            (Some(redactor), Ok(text)) => redactor.redact(text),
+            _ => return self.inner.write(buf),
+        };
+        self.inner.write_all(text.as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// `SpanExporter` that redacts each span before handing it to `inner`.
+///
+/// String attributes of the span and of its events are redacted, as are event
+/// names (`tracing` events inside a span become span events named after their
+/// message) and the description of an error status.
+#[cfg(feature = "otlp")]
+#[derive(Debug)]
+pub struct RedactingSpanExporter<E> {
+    inner: E,
+    redactor: Option<Redactor>,
+}
+
+#[cfg(feature = "otlp")]
+impl<E> RedactingSpanExporter<E> {
+    /// Wraps `inner`, redacting with `redactor` (or passing spans through on `None`).
+    pub fn new(inner: E, redactor: Option<Redactor>) -> Self {
+        Self { inner, redactor }
+    }
+
+    /// Wraps `inner` with the patterns configured by `VIBEPRO_REDACT_PATTERNS`.
+    pub fn from_env(inner: E) -> Self {
+        Self::new(inner, Redactor::from_env())
+    }
+}
+
+#[cfg(feature = "otlp")]
+impl<E: SpanExporter> SpanExporter for RedactingSpanExporter<E> {
+    fn export(&self, mut batch: Vec<SpanData>) -> impl Future<Output = OTelSdkResult> + Send {
+        if let Some(redactor) = &self.redactor {
+            for span in &mut batch {
+                redactor.redact_attributes(&mut span.attributes);
+                for event in span.events.events.iter_mut() {
+                    if let Cow::Owned(name) = redactor.redact(&event.name) {
+                        event.name = name.into();
+                    }
+                    redactor.redact_attributes(&mut event.attributes);
+                }
+                if let Status::Error { description } = &mut span.status {
+                    if let Cow::Owned(redacted) = redactor.redact(description) {
+                        *description = redacted.into();
+                    }
+                }
+            }
+        }
+        self.inner.export(batch)
+    }
+
+    fn shutdown_with_timeout(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+/// `LoggerProvider` whose log records redact each body and attribute as it is set.
+///
+/// Pass it to `OpenTelemetryTracingBridge::new` in place of `inner`; records are
+/// emitted through `inner`'s processors and exporters.
+#[cfg(feature = "logs")]
+#[derive(Debug, Clone)]
+pub struct RedactingLoggerProvider {
+    inner: SdkLoggerProvider,
+    redactor: Option<Arc<Redactor>>,
+}
+
+#[cfg(feature = "logs")]
+impl RedactingLoggerProvider {
+    /// Wraps `inner`, redacting with `redactor` (or passing records through on `None`).
+    pub fn new(inner: SdkLoggerProvider, redactor: Option<Redactor>) -> Self {
+        Self {
+            inner,
+            redactor: redactor.map(Arc::new),
+        }
+    }
+
+    /// Wraps `inner` with the patterns configured by `VIBEPRO_REDACT_PATTERNS`.
+    pub fn from_env(inner: SdkLoggerProvider) -> Self {
+        Self::new(inner, Redactor::from_env())
+    }
+}
+
+#[cfg(feature = "logs")]
+impl LoggerProvider for RedactingLoggerProvider {
+    type Logger = RedactingLogger;
+
+    fn logger_with_scope(&self, scope: InstrumentationScope) -> Self::Logger {
+        RedactingLogger {
+            inner: self.inner.logger_with_scope(scope),
+            redactor: self.redactor.clone(),
+        }
+    }
+}
+
+/// Logger returned by [`RedactingLoggerProvider`].
+#[cfg(feature = "logs")]
+#[derive(Debug)]
+pub struct RedactingLogger {
+    inner: SdkLogger,
+    redactor: Option<Arc<Redactor>>,
+}
+
+#[cfg(feature = "logs")]
+impl Logger for RedactingLogger {
+    type LogRecord = RedactingLogRecord;
+
+    fn create_log_record(&self) -> Self::LogRecord {
+        RedactingLogRecord {
+            inner: self.inner.create_log_record(),
+            redactor: self.redactor.clone(),
+        }
+    }
+
+    fn emit(&self, record: Self::LogRecord) {
+        self.inner.emit(record.inner);
+    }
+}
+
+/// Log record created by [`RedactingLogger`].
+#[cfg(feature = "logs")]
+#[derive(Debug)]
+pub struct RedactingLogRecord {
+    inner: SdkLogRecord,
+    redactor: Option<Arc<Redactor>>,
+}
+
+#[cfg(feature = "logs")]
+impl RedactingLogRecord {
+    fn redact(&self, value: AnyValue) -> AnyValue {
+        match &self.redactor {
+            Some(redactor) => redactor.redact_any_value(value),
+            None => value,
+        }
+    }
+}
+
+#[cfg(feature = "logs")]
+impl LogRecord for RedactingLogRecord {
+    fn set_event_name(&mut self, name: &'static str) {
+        self.inner.set_event_name(name);
+    }
+
+    fn set_target<T>(&mut self, target: T)
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.inner.set_target(target);
+    }
+
+    fn set_timestamp(&mut self, timestamp: SystemTime) {
+        self.inner.set_timestamp(timestamp);
+    }
+
+    fn set_observed_timestamp(&mut self, timestamp: SystemTime) {
+        self.inner.set_observed_timestamp(timestamp);
+    }
+
+    fn set_severity_text(&mut self, text: &'static str) {
+        self.inner.set_severity_text(text);
+    }
+
+    fn set_severity_number(&mut self, number: Severity) {
+        self.inner.set_severity_number(number);
+    }
+
+    fn set_body(&mut self, body: AnyValue) {
+        let body = self.redact(body);
+        self.inner.set_body(body);
+    }
+
+    fn add_attributes<I, K, V>(&mut self, attributes: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Key>,
+        V: Into<AnyValue>,
+    {
+        for (key, value) in attributes {
+            self.add_attribute(key, value);
+        }
+    }
+
+    fn add_attribute<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<Key>,
+        V: Into<AnyValue>,
+    {
+        let value = self.redact(value.into());
+        self.inner.add_attribute(key, value);
+    }
+
+    fn set_trace_context(
+        &mut self,
+        trace_id: TraceId,
+        span_id: SpanId,
+        trace_flags: Option<TraceFlags>,
+    ) {
+        self.inner.set_trace_context(trace_id, span_id, trace_flags);
+    }
+}
@@ -0,0 +1,51 @@
+//! `tokio-console` integration.
+//!
+//! When `TOKIO_CONSOLE=1`, [`console_layer`] starts the console's gRPC server on
+//! the address in `TOKIO_CONSOLE_BIND` (default `127.0.0.1:6669`) and returns the
+//! layer that feeds it. The layer only records `tokio` and `runtime` spans and
+//! events, so it can sit next to the JSON layer without changing what is logged.
+//! Without `TOKIO_CONSOLE=1` no server is started, so a binary built with the
+//! feature is safe to deploy.
+
+use std::env;
+use std::net::SocketAddr;
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Address the console server listens on when `TOKIO_CONSOLE_BIND` is unset.
+pub const DEFAULT_BIND: &str = "127.0.0.1:6669";
+
+/// Returns whether `TOKIO_CONSOLE=1` asks for the console server.
+pub fn is_enabled() -> bool {
+    env::var("TOKIO_CONSOLE").is_ok_and(|v| v.trim() == "1")
+}
+
+/// Returns the console server address from `TOKIO_CONSOLE_BIND`, falling back
+/// to [`DEFAULT_BIND`] when the variable is unset or not a valid socket address.
+pub fn bind_addr() -> SocketAddr {
+    env::var("TOKIO_CONSOLE_BIND")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or_else(|| {
+            DEFAULT_BIND
+                .parse()
+                .expect("DEFAULT_BIND is a valid socket address")
+        })
+}
+
+/// Spawns the console server on [`bind_addr`] and returns its layer, or `None`
+/// unless [`is_enabled`].
+///
+/// The server runs on a background thread with its own Tokio runtime, so no
+/// runtime needs to be active when this is called.
+pub fn console_layer<S>() -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    is_enabled().then(|| {
+        console_subscriber::ConsoleLayer::builder()
+            .server_addr(bind_addr())
+            .spawn()
+    })
+}
@@ -0,0 +1,254 @@
+//! Programmatic configuration for [`init_tracing_with_config`](crate::init_tracing_with_config).
+//!
+//! [`TracingConfig::from_env`] reads the same environment variables as
+//! [`init_tracing`](crate::init_tracing), so a config can start from the
+//! environment and override individual fields in code:
+//!
+//! ```
+//! use vibepro_observe::TracingConfig;
+//!
+//! let config = TracingConfig::from_env("my-service")
+//!     .with_log_level("debug")
+//!     .with_resource_attribute("deployment.environment", "staging");
+//! assert_eq!(config.log_level, "debug");
+//! ```
+//!
+//! With the `config-env` feature, the whole config can instead be passed as one
+//! JSON document in `VIBEPRO_CONFIG`, for platforms that allow only a single
+//! environment variable (see [`init_tracing_from_config_env`](crate::init_tracing_from_config_env)).
+//! [`encode_config_env`] produces the base64 form of that variable:
+//!
+//! ```json
+//! {"service_name": "my-service", "log_level": "debug", "enable_otlp": true}
+//! ```
+//!
+//! Fields other than `service_name` may be omitted and take the defaults of
+//! [`TracingConfig::new`].
+
+use anyhow::{anyhow, Error};
+#[cfg(feature = "config-env")]
+use anyhow::{Context, Result};
+#[cfg(feature = "config-env")]
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+#[cfg(feature = "config-env")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::str::FromStr;
+use tracing_subscriber::EnvFilter;
+
+/// Default `log_level` when `RUST_LOG` is unset or invalid.
+const DEFAULT_LOG_LEVEL: &str = "info";
+
+/// Environment variable read by [`init_tracing_from_config_env`](crate::init_tracing_from_config_env).
+#[cfg(feature = "config-env")]
+pub const CONFIG_ENV_VAR: &str = "VIBEPRO_CONFIG";
+
+/// Output format of the stdout log layer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config-env",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum LogFormat {
+    /// One JSON object per event, for log collectors. The default.
+    #[default]
+    Json,
+    /// Multi-line, human-readable output for local development.
+    Pretty,
+    /// Single-line, human-readable output.
+    Compact,
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    /// Parses `json`, `pretty` or `compact`, ignoring case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "pretty" => Ok(Self::Pretty),
+            "compact" => Ok(Self::Compact),
+            other => Err(anyhow!(
+                "unknown log format `{other}` (expected `json`, `pretty` or `compact`)"
+            )),
+        }
+    }
+}
+
+/// Settings for [`init_tracing_with_config`](crate::init_tracing_with_config).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "config-env", derive(Serialize, Deserialize))]
+pub struct TracingConfig {
+    /// The `service.name` resource attribute.
+    pub service_name: String,
+    /// An `EnvFilter` directive such as `info` or `my_crate=trace,info`.
+    #[cfg_attr(feature = "config-env", serde(default = "default_log_level"))]
+    pub log_level: String,
+    /// Format of the stdout log layer. The OTLP layer is installed regardless.
+    #[cfg_attr(feature = "config-env", serde(default))]
+    pub log_format: LogFormat,
+    /// Install the OTLP exporter (requires the `otlp` feature). With the
+    /// `config-file` feature, `OTEL_SDK_DISABLED=true` still turns it off.
+    #[cfg_attr(feature = "config-env", serde(default))]
+    pub enable_otlp: bool,
+    /// OTLP endpoint; falls back to `OTEL_CONFIG_FILE` (with the `config-file`
+    /// feature), then `http://127.0.0.1:4317`.
+    #[cfg_attr(feature = "config-env", serde(default))]
+    pub otlp_endpoint: Option<String>,
+    /// OTLP protocol (`grpc` or `http`); falls back like `otlp_endpoint`, then `grpc`.
+    #[cfg_attr(feature = "config-env", serde(default))]
+    pub otlp_protocol: Option<String>,
+    /// Extra OTel resource attributes. They override the built-in ones
+    /// (`service.version`, `vcs.ref.head.revision` and the Datadog mapping) of the
+    /// same name.
+    #[cfg_attr(feature = "config-env", serde(default))]
+    pub resource_attributes: HashMap<String, String>,
+}
+
+#[cfg(feature = "config-env")]
+fn default_log_level() -> String {
+    DEFAULT_LOG_LEVEL.to_string()
+}
+
+impl TracingConfig {
+    /// Creates a config with defaults and no environment lookups: `info` level,
+    /// JSON logs, OTLP disabled and no extra resource attributes.
+    pub fn new(service_name: impl Into<String>) -> Self {
+        Self {
+            service_name: service_name.into(),
+            log_level: DEFAULT_LOG_LEVEL.to_string(),
+            log_format: LogFormat::default(),
+            enable_otlp: false,
+            otlp_endpoint: None,
+            otlp_protocol: None,
+            resource_attributes: HashMap::new(),
+        }
+    }
+
+    /// Creates a config from `RUST_LOG`, `VIBEPRO_LOG_FORMAT`, `VIBEPRO_OBSERVE`,
+    /// `OTLP_ENDPOINT` and `OTLP_PROTOCOL`, matching the behavior of
+    /// [`init_tracing`](crate::init_tracing).
+    ///
+    /// An invalid `RUST_LOG` falls back to `info`, an unknown `VIBEPRO_LOG_FORMAT`
+    /// to JSON.
+    pub fn from_env(service_name: impl Into<String>) -> Self {
+        let log_level = env::var("RUST_LOG")
+            .ok()
+            .filter(|level| EnvFilter::try_new(level).is_ok())
+            .unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string());
+
+        let log_format = env::var("VIBEPRO_LOG_FORMAT")
+            .ok()
+            .and_then(|format| format.parse().ok())
+            .unwrap_or_default();
+
+        Self {
+            log_level,
+            log_format,
+            enable_otlp: env::var("VIBEPRO_OBSERVE").unwrap_or_default() == "1",
+            otlp_endpoint: env::var("OTLP_ENDPOINT").ok(),
+            otlp_protocol: env::var("OTLP_PROTOCOL").ok(),
+            ..Self::new(service_name)
+        }
+    }
+
+    /// Sets `log_level`.
+    pub fn with_log_level(mut self, log_level: impl Into<String>) -> Self {
+        self.log_level = log_level.into();
+        self
+    }
+
+    /// Sets `log_format`.
+    pub fn with_log_format(mut self, log_format: LogFormat) -> Self {
+        self.log_format = log_format;
+        self
+    }
+
+    /// Sets `enable_otlp`.
+    pub fn with_otlp(mut self, enable_otlp: bool) -> Self {
+        self.enable_otlp = enable_otlp;
+        self
+    }
+
+    /// Sets `otlp_endpoint`.
+    pub fn with_otlp_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.otlp_endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Sets `otlp_protocol`.
+    pub fn with_otlp_protocol(mut self, protocol: impl Into<String>) -> Self {
+        self.otlp_protocol = Some(protocol.into());
+        self
+    }
+
+    /// Adds or replaces one entry of `resource_attributes`.
+    pub fn with_resource_attribute(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.resource_attributes.insert(key.into(), value.into());
+        self
+    }
+
+    /// Reads [`CONFIG_ENV_VAR`] and parses it like
+    /// [`from_config_env_value`](Self::from_config_env_value).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the variable is unset or does not decode to a
+    /// `TracingConfig`.
+    #[cfg(feature = "config-env")]
+    pub fn from_config_env() -> Result<Self> {
+        let value =
+            env::var(CONFIG_ENV_VAR).with_context(|| format!("{CONFIG_ENV_VAR} is not set"))?;
+        Self::from_config_env_value(&value)
+    }
+
+    /// Parses the value of [`CONFIG_ENV_VAR`]: a JSON object, or the same
+    /// object base64-encoded as produced by [`encode_config_env`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` is neither valid JSON nor valid base64 of a
+    /// JSON `TracingConfig`.
+    #[cfg(feature = "config-env")]
+    pub fn from_config_env_value(value: &str) -> Result<Self> {
+        let value = value.trim();
+        if value.starts_with('{') {
+            return serde_json::from_str(value)
+                .with_context(|| format!("{CONFIG_ENV_VAR} is not a valid TracingConfig"));
+        }
+
+        let json = BASE64
+            .decode(value)
+            .with_context(|| format!("{CONFIG_ENV_VAR} is neither JSON nor base64"))?;
+        serde_json::from_slice(&json)
+            .with_context(|| format!("{CONFIG_ENV_VAR} is not a valid base64 TracingConfig"))
+    }
+}
+
+/// Encodes `config` as base64 JSON, the value to put in [`CONFIG_ENV_VAR`].
+///
+/// # Errors
+///
+/// Returns an error if `config` cannot be serialized to JSON.
+///
+/// # Examples
+///
+/// ```
+/// use vibepro_observe::{encode_config_env, TracingConfig};
+///
+/// let config = TracingConfig::new("my-service").with_log_level("debug");
+/// let value = encode_config_env(&config)?;
+/// assert_eq!(TracingConfig::from_config_env_value(&value)?, config);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[cfg(feature = "config-env")]
+pub fn encode_config_env(config: &TracingConfig) -> Result<String> {
+    let json = serde_json::to_vec(config)?;
+    Ok(BASE64.encode(json))
+}
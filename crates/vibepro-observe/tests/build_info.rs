@@ -0,0 +1,25 @@
+use vibepro_observe::{
+    commit_sha, service_version, BUILD_COMMIT_SHA, BUILD_NAME, BUILD_VERSION,
+};
+
+#[test]
+fn build_info_is_baked_in() {
+    assert_eq!(BUILD_NAME, "vibepro-observe");
+    assert_eq!(BUILD_VERSION, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn env_vars_override_build_info() {
+    std::env::remove_var("OTEL_SERVICE_VERSION");
+    std::env::remove_var("VIBEPRO_COMMIT_SHA");
+    assert_eq!(service_version(), BUILD_VERSION);
+    assert_eq!(commit_sha().as_deref(), BUILD_COMMIT_SHA);
+
+    std::env::set_var("OTEL_SERVICE_VERSION", "2.3.4");
+    std::env::set_var("VIBEPRO_COMMIT_SHA", "abc123");
+    assert_eq!(service_version(), "2.3.4");
+    assert_eq!(commit_sha().as_deref(), Some("abc123"));
+
+    std::env::remove_var("OTEL_SERVICE_VERSION");
+    std::env::remove_var("VIBEPRO_COMMIT_SHA");
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "cloud-metadata")]
+
+use opentelemetry::KeyValue;
+use std::time::{Duration, Instant};
+use vibepro_observe::cloud_metadata::{
+    aws_attributes, azure_attributes, container_id_from_cgroup, detect, gcp_attributes,
+};
+
+fn value(attributes: &[KeyValue], key: &str) -> Option<String> {
+    attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == key)
+        .map(|kv| kv.value.to_string())
+}
+
+#[test]
+fn aws_identity_document_maps_to_attributes() {
+    let document = r#"{
+        "accountId": "123456789012",
+        "availabilityZone": "us-east-1b",
+        "imageId": "ami-0abcdef",
+        "instanceId": "i-0123456789abcdef0",
+        "instanceType": "m5.large",
+        "region": "us-east-1"
+    }"#;
+    let attributes = aws_attributes(document).unwrap();
+
+    assert_eq!(value(&attributes, "cloud.provider").as_deref(), Some("aws"));
+    assert_eq!(value(&attributes, "cloud.account.id").as_deref(), Some("123456789012"));
+    assert_eq!(value(&attributes, "cloud.region").as_deref(), Some("us-east-1"));
+    assert_eq!(value(&attributes, "cloud.availability_zone").as_deref(), Some("us-east-1b"));
+    assert_eq!(value(&attributes, "host.id").as_deref(), Some("i-0123456789abcdef0"));
+    assert_eq!(value(&attributes, "host.type").as_deref(), Some("m5.large"));
+}
+
+#[test]
+fn gcp_metadata_maps_zone_and_region() {
+    let document = r#"{
+        "instance": {
+            "id": 4520031799277581759,
+            "name": "web-1",
+            "zone": "projects/123/zones/us-central1-a",
+            "machineType": "projects/123/machineTypes/e2-medium"
+        },
+        "project": { "projectId": "my-project" }
+    }"#;
+    let attributes = gcp_attributes(document).unwrap();
+
+    assert_eq!(value(&attributes, "cloud.provider").as_deref(), Some("gcp"));
+    assert_eq!(value(&attributes, "cloud.account.id").as_deref(), Some("my-project"));
+    assert_eq!(value(&attributes, "cloud.region").as_deref(), Some("us-central1"));
+    assert_eq!(value(&attributes, "cloud.availability_zone").as_deref(), Some("us-central1-a"));
+    assert_eq!(value(&attributes, "host.id").as_deref(), Some("4520031799277581759"));
+    assert_eq!(value(&attributes, "host.type").as_deref(), Some("e2-medium"));
+}
+
+#[test]
+fn azure_compute_metadata_maps_to_attributes() {
+    let document = r#"{
+        "location": "westeurope",
+        "name": "vm-1",
+        "subscriptionId": "sub-1",
+        "vmId": "02aab8a4-74ef-476e-8182-f6d2ba4166a6",
+        "vmSize": "Standard_D2s_v3",
+        "zone": ""
+    }"#;
+    let attributes = azure_attributes(document).unwrap();
+
+    assert_eq!(value(&attributes, "cloud.provider").as_deref(), Some("azure"));
+    assert_eq!(value(&attributes, "cloud.region").as_deref(), Some("westeurope"));
+    assert_eq!(value(&attributes, "host.type").as_deref(), Some("Standard_D2s_v3"));
+    assert_eq!(value(&attributes, "cloud.availability_zone"), None);
+}
+
+#[test]
+fn documents_without_instance_id_are_rejected() {
+    assert!(aws_attributes(r#"{"region": "us-east-1"}"#).is_none());
+    assert!(gcp_attributes("not json").is_none());
+    assert!(azure_attributes("{}").is_none());
+}
+
+#[test]
+fn container_id_is_read_from_cgroup() {
+    let id = "a".repeat(64);
+    let docker = format!("0::/system.slice/docker-{id}.scope\n");
+    let cgroup_v1 = format!("12:cpu,cpuacct:/docker/{id}\n1:name=systemd:/\n");
+
+    assert_eq!(container_id_from_cgroup(&docker), Some(id.clone()));
+    assert_eq!(container_id_from_cgroup(&cgroup_v1), Some(id));
+    assert_eq!(container_id_from_cgroup("0::/\n"), None);
+}
+
+#[test]
+fn detect_is_bounded_by_timeout() {
+    let started = Instant::now();
+    let _ = detect(Duration::from_millis(50));
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "config-env")]
+
+use std::env;
+use vibepro_observe::{
+    encode_config_env, init_tracing_from_config_env, LogFormat, TracingConfig, CONFIG_ENV_VAR,
+};
+
+#[test]
+fn encoded_config_round_trips() {
+    let config = TracingConfig::new("config-env-test")
+        .with_log_level("warn,my_crate=trace")
+        .with_log_format(LogFormat::Compact)
+        .with_otlp(true)
+        .with_otlp_endpoint("http://collector:4318")
+        .with_otlp_protocol("http")
+        .with_resource_attribute("deployment.environment", "ci");
+
+    let value = encode_config_env(&config).unwrap();
+
+    assert!(!value.contains('{'), "expected base64, got {value}");
+    assert_eq!(
+        TracingConfig::from_config_env_value(&value).unwrap(),
+        config
+    );
+}
+
+#[test]
+fn plain_json_fills_in_defaults() {
+    let value = r#"{"service_name": "config-env-test", "enable_otlp": true}"#;
+
+    let config = TracingConfig::from_config_env_value(value).unwrap();
+
+    assert_eq!(
+        config,
+        TracingConfig::new("config-env-test").with_otlp(true)
+    );
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    assert!(TracingConfig::from_config_env_value("{not json").is_err());
+    assert!(TracingConfig::from_config_env_value("not base64!").is_err());
+    // Valid base64 of a JSON object without `service_name`.
+    assert!(TracingConfig::from_config_env_value("eyJsb2dfbGV2ZWwiOiJkZWJ1ZyJ9").is_err());
+}
+
+#[test]
+fn init_reads_config_env_var() {
+    env::remove_var(CONFIG_ENV_VAR);
+    assert!(init_tracing_from_config_env().is_err());
+
+    let config = TracingConfig::new("config-env-test").with_log_level("debug");
+    env::set_var(CONFIG_ENV_VAR, encode_config_env(&config).unwrap());
+    let result = init_tracing_from_config_env();
+    env::remove_var(CONFIG_ENV_VAR);
+
+    assert!(result.is_ok());
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "config-file")]
+
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+use vibepro_observe::config_file::OtelFileConfig;
+
+const ENV_KEYS: [&str; 4] = [
+    "OTEL_CONFIG_FILE",
+    "OTLP_ENDPOINT",
+    "OTLP_PROTOCOL",
+    "OTEL_SDK_DISABLED",
+];
+
+// Restores the variables read by OtelFileConfig::from_env on drop.
+struct ConfigEnvGuard(Vec<(&'static str, Option<String>)>);
+
+impl ConfigEnvGuard {
+    fn capture() -> Self {
+        let saved = ENV_KEYS
+            .into_iter()
+            .map(|key| (key, env::var(key).ok()))
+            .collect();
+        for key in ENV_KEYS {
+            env::remove_var(key);
+        }
+        ConfigEnvGuard(saved)
+    }
+}
+
+impl Drop for ConfigEnvGuard {
+    fn drop(&mut self) {
+        for (key, value) in &self.0 {
+            match value {
+                Some(v) => env::set_var(key, v),
+                None => env::remove_var(key),
+            }
+        }
+    }
+}
+
+fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/otel-config.yaml")
+}
+
+#[test]
+fn config_file_sets_endpoint_and_env_takes_precedence() {
+    let _guard = ConfigEnvGuard::capture();
+    env::set_var("OTEL_CONFIG_FILE", fixture());
+
+    let config = OtelFileConfig::from_env().expect("fixture parses");
+    assert!(!config.disabled);
+    assert_eq!(
+        config.endpoint.as_deref(),
+        Some("http://collector.internal:4318")
+    );
+    assert_eq!(config.protocol.as_deref(), Some("http/protobuf"));
+    assert_eq!(
+        config.headers.get("api-key").map(String::as_str),
+        Some("test-key")
+    );
+    assert_eq!(config.batch.schedule_delay, Some(Duration::from_millis(2500)));
+    assert_eq!(config.batch.max_queue_size, Some(4096));
+    assert_eq!(config.batch.max_export_batch_size, Some(256));
+
+    env::set_var("OTLP_ENDPOINT", "http://127.0.0.1:4317");
+    env::set_var("OTEL_SDK_DISABLED", "true");
+    let config = OtelFileConfig::from_env().expect("fixture parses");
+    assert_eq!(config.endpoint.as_deref(), Some("http://127.0.0.1:4317"));
+    assert_eq!(config.protocol.as_deref(), Some("http/protobuf"));
+    assert!(config.disabled);
+}
+
+#[test]
+fn missing_config_file_is_an_error() {
+    assert!(OtelFileConfig::load("does/not/exist.yaml").is_err());
+}
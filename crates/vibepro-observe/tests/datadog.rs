@@ -0,0 +1,66 @@
+#![cfg(feature = "datadog")]
+
+use opentelemetry::trace::{SpanId, TraceId};
+use std::env;
+use vibepro_observe::datadog::{to_datadog_span_id, to_datadog_trace_id, DatadogConfig};
+
+// Restores DD_* variables on drop so tests don't leak env changes.
+struct DdEnvGuard(Vec<(&'static str, Option<String>)>);
+
+impl DdEnvGuard {
+    fn capture() -> Self {
+        DdEnvGuard(
+            ["DD_SERVICE", "DD_ENV", "DD_VERSION"]
+                .into_iter()
+                .map(|key| (key, env::var(key).ok()))
+                .collect(),
+        )
+    }
+}
+
+impl Drop for DdEnvGuard {
+    fn drop(&mut self) {
+        for (key, value) in &self.0 {
+            match value {
+                Some(v) => env::set_var(key, v),
+                None => env::remove_var(key),
+            }
+        }
+    }
+}
+
+#[test]
+fn datadog_tags_map_to_resource_attributes() {
+    let _guard = DdEnvGuard::capture();
+    env::remove_var("DD_SERVICE");
+    assert!(DatadogConfig::from_env().is_none());
+
+    env::set_var("DD_SERVICE", "checkout");
+    env::set_var("DD_ENV", "prod");
+    env::set_var("DD_VERSION", "1.4.2");
+
+    let config = DatadogConfig::from_env().expect("DD_SERVICE is set");
+    let attributes = config.resource_attributes();
+    let get = |key: &str| {
+        attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.to_string())
+    };
+
+    assert_eq!(get("service.name").as_deref(), Some("checkout"));
+    assert_eq!(get("dd.service").as_deref(), Some("checkout"));
+    assert_eq!(get("deployment.environment").as_deref(), Some("prod"));
+    assert_eq!(get("dd.env").as_deref(), Some("prod"));
+    assert_eq!(get("service.version").as_deref(), Some("1.4.2"));
+    assert_eq!(get("dd.version").as_deref(), Some("1.4.2"));
+}
+
+#[test]
+fn datadog_ids_use_low_64_bits() {
+    let trace_id = TraceId::from_hex("0000000000000001000000000000002a").unwrap();
+    assert_eq!(to_datadog_trace_id(trace_id), 42);
+
+    let span_id = SpanId::from_hex("00000000000000ff").unwrap();
+    assert_eq!(to_datadog_span_id(span_id), 255);
+}
@@ -0,0 +1,40 @@
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Registry;
+use vibepro_observe::{event_counts, init_tracing, EventCounter};
+
+#[test]
+fn counter_tracks_events_by_level() {
+    let counter: &'static EventCounter = Box::leak(Box::new(EventCounter::new()));
+    let subscriber = Registry::default().with(counter);
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::trace!("trace");
+        tracing::debug!("debug");
+        for _ in 0..3 {
+            tracing::info!("info");
+        }
+        tracing::warn!("warn");
+        tracing::error!("error");
+        tracing::error!("error");
+    });
+
+    let counts = counter.counts();
+    assert_eq!(counts.trace_count, 1);
+    assert_eq!(counts.debug_count, 1);
+    assert_eq!(counts.info_count, 3);
+    assert_eq!(counts.warn_count, 1);
+    assert_eq!(counts.error_count, 2);
+}
+
+#[test]
+fn init_tracing_installs_global_counter() {
+    init_tracing("event-counter-test").expect("init_tracing");
+
+    let before = event_counts();
+    tracing::error!("counted error");
+    tracing::warn!("counted warning");
+    let after = event_counts();
+
+    assert!(after.error_count > before.error_count);
+    assert!(after.warn_count > before.warn_count);
+}
@@ -0,0 +1,9 @@
+use vibepro_observe::{init_tracing_with_config, LogFormat, TracingConfig};
+
+#[test]
+fn pretty_format_initializes_and_logs() {
+    let config = TracingConfig::new("log-format-test").with_log_format(LogFormat::Pretty);
+    init_tracing_with_config(config).expect("pretty format should initialize");
+    tracing::info!(answer = 42, "human-readable event");
+    assert!(vibepro_observe::event_counts().info_count >= 1);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "otlp")]
+
+use std::env;
+
+const BATCH_KEYS: [&str; 4] = [
+    "OTLP_BATCH_MAX_QUEUE_SIZE",
+    "OTLP_BATCH_MAX_EXPORT_BATCH_SIZE",
+    "OTLP_BATCH_SCHEDULE_DELAY_MS",
+    "OTLP_BATCH_EXPORT_TIMEOUT_MS",
+];
+
+// Kept as one test: only the first successful init_tracing in a process has an effect.
+#[tokio::test(flavor = "multi_thread")]
+async fn batch_env_vars_are_validated_then_applied() {
+    env::set_var("VIBEPRO_OBSERVE", "1");
+    env::set_var("OTLP_ENDPOINT", "http://127.0.0.1:4317");
+
+    env::set_var("OTLP_BATCH_MAX_QUEUE_SIZE", "lots");
+    let err = vibepro_observe::init_tracing("otlp-batch-test").unwrap_err();
+    assert!(err.to_string().contains("OTLP_BATCH_MAX_QUEUE_SIZE"), "{err}");
+    assert!(!vibepro_observe::is_tracing_initialized());
+
+    for (key, value) in BATCH_KEYS.into_iter().zip(["8192", "1024", "1000", "2000"]) {
+        env::set_var(key, value);
+    }
+    vibepro_observe::init_tracing("otlp-batch-test").expect("valid batch settings");
+    assert!(vibepro_observe::is_otlp_enabled());
+
+    for key in BATCH_KEYS {
+        env::remove_var(key);
+    }
+    env::remove_var("VIBEPRO_OBSERVE");
+    env::remove_var("OTLP_ENDPOINT");
+}
@@ -11,6 +11,8 @@ mod otlp_enabled {
 
         let result = init_tracing("otlp-test");
         assert!(result.is_ok(), "init_tracing should succeed");
+        assert!(vibepro_observe::is_tracing_initialized());
+        assert!(vibepro_observe::is_otlp_enabled());
 
         record_metric("otlp.counter", 3.0);
         // Add verification that metric was recorded if possible
@@ -19,4 +21,53 @@ mod otlp_enabled {
         env::remove_var("VIBEPRO_OBSERVE");
         env::remove_var("OTLP_ENDPOINT");
     }
+
+    #[test]
+    fn meter_creates_typed_instruments() {
+        // Without an installed provider the global meter is a no-op, so creating
+        // and recording instruments must still work.
+        let meter = vibepro_observe::meter("otlp-gates-test");
+
+        let counter = meter.u64_counter("requests").build();
+        counter.add(1, &[]);
+
+        let histogram = meter.f64_histogram("latency_ms").build();
+        histogram.record(12.5, &[]);
+    }
+
+    #[test]
+    fn otlp_headers_env_var_yields_all_pairs() {
+        env::set_var("OTLP_HEADERS", "x-honeycomb-team=my-api-key,x-dataset=prod");
+        let headers = vibepro_observe::otlp_headers_from_env();
+        env::remove_var("OTLP_HEADERS");
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers.get("x-honeycomb-team").map(String::as_str), Some("my-api-key"));
+        assert_eq!(headers.get("x-dataset").map(String::as_str), Some("prod"));
+    }
+
+    #[test]
+    fn sampler_env_vars_select_sampler() {
+        let sampler = |name: &str, ratio: Option<&str>| {
+            env::set_var("OTLP_SAMPLER", name);
+            match ratio {
+                Some(ratio) => env::set_var("OTLP_SAMPLE_RATIO", ratio),
+                None => env::remove_var("OTLP_SAMPLE_RATIO"),
+            }
+            format!("{:?}", vibepro_observe::sampler_from_env())
+        };
+
+        assert_eq!(sampler("always_on", None), "AlwaysOn");
+        assert_eq!(sampler("ALWAYS_OFF", None), "AlwaysOff");
+        assert_eq!(sampler("traceidratio", Some("0.25")), "TraceIdRatioBased(0.25)");
+        assert_eq!(sampler("traceidratio", Some("7")), "TraceIdRatioBased(1.0)");
+        assert_eq!(sampler("traceidratio", Some("nope")), "TraceIdRatioBased(1.0)");
+        let parent_based = sampler("parentbased_traceidratio", Some("0.1"));
+        assert!(parent_based.starts_with("ParentBased"));
+        assert!(parent_based.contains("TraceIdRatioBased(0.1)"));
+        assert!(sampler("unknown", None).starts_with("ParentBased"));
+
+        env::remove_var("OTLP_SAMPLER");
+        env::remove_var("OTLP_SAMPLE_RATIO");
+    }
 }
@@ -0,0 +1,74 @@
+#![cfg(feature = "logs")]
+
+use opentelemetry::logs::{AnyValue, Severity};
+use opentelemetry_sdk::logs::{InMemoryLogExporter, SdkLoggerProvider};
+use tracing_subscriber::layer::SubscriberExt;
+use vibepro_observe::otlp_logs::{logs_endpoint, logs_layer};
+
+#[test]
+fn events_become_log_records() {
+    let exporter = InMemoryLogExporter::default();
+    let provider = SdkLoggerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry().with(logs_layer(&provider));
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(user_id = 7, "login failed");
+        // Exporter internals are not bridged.
+        tracing::info!(target: "opentelemetry_sdk::logs", "exporting batch");
+    });
+
+    provider.force_flush().expect("flush");
+    let logs = exporter.get_emitted_logs().expect("emitted logs");
+    assert_eq!(logs.len(), 1);
+
+    let record = &logs[0].record;
+    assert_eq!(record.severity_number(), Some(Severity::Warn));
+    assert_eq!(record.body(), Some(&AnyValue::from("login failed")));
+}
+
+#[cfg(feature = "redact")]
+#[test]
+fn log_records_are_redacted() {
+    let exporter = InMemoryLogExporter::default();
+    let provider = SdkLoggerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry().with(logs_layer(&provider));
+
+    // VIBEPRO_REDACT_PATTERNS is unset, so the default patterns (email, ...) apply.
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(
+            user = "jane@example.com",
+            attempts = 3,
+            "login failed for jane@example.com"
+        );
+    });
+
+    provider.force_flush().expect("flush");
+    let logs = exporter.get_emitted_logs().expect("emitted logs");
+    let record = &logs[0].record;
+    assert_eq!(
+        record.body(),
+        Some(&AnyValue::from("login failed for [REDACTED]"))
+    );
+    let attribute = |name: &str| {
+        record
+            .attributes_iter()
+            .find(|(key, _)| key.as_str() == name)
+            .map(|(_, value)| value.clone())
+    };
+    assert_eq!(attribute("user"), Some(AnyValue::from("[REDACTED]")));
+    assert_eq!(attribute("attempts"), Some(AnyValue::from(3_i64)));
+}
+
+#[test]
+fn logs_endpoint_defaults_to_trace_endpoint() {
+    std::env::remove_var("OTLP_LOGS_ENDPOINT");
+    assert_eq!(logs_endpoint("http://collector:4317"), "http://collector:4317");
+
+    std::env::set_var("OTLP_LOGS_ENDPOINT", "http://logs:4317");
+    assert_eq!(logs_endpoint("http://collector:4317"), "http://logs:4317");
+    std::env::remove_var("OTLP_LOGS_ENDPOINT");
+}
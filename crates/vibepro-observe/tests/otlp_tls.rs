@@ -0,0 +1,34 @@
+#![cfg(feature = "tls")]
+
+use std::fs;
+use vibepro_observe::otlp_tls::tls_config;
+
+const PEM: &str = "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n";
+
+#[test]
+fn no_paths_means_plaintext() {
+    assert!(tls_config(None, None, None).unwrap().is_none());
+}
+
+#[test]
+fn ca_and_client_identity_are_loaded() {
+    let dir = std::env::temp_dir().join(format!("otlp-tls-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let (ca, cert, key) = (dir.join("ca.pem"), dir.join("client.pem"), dir.join("client.key"));
+    for path in [&ca, &cert, &key] {
+        fs::write(path, PEM).unwrap();
+    }
+
+    assert!(tls_config(Some(&ca), None, None).unwrap().is_some());
+    assert!(tls_config(Some(&ca), Some(&cert), Some(&key)).unwrap().is_some());
+    assert!(tls_config(None, Some(&cert), None).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_file_is_an_error_naming_the_path() {
+    let missing = std::env::temp_dir().join("vibepro-observe-missing-ca.pem");
+    let err = tls_config(Some(&missing), None, None).unwrap_err();
+    assert!(format!("{err:#}").contains("vibepro-observe-missing-ca.pem"));
+}
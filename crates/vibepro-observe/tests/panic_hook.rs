@@ -0,0 +1,15 @@
+use std::thread;
+use vibepro_observe::{event_counts, init_tracing, install_panic_hook};
+
+#[test]
+fn panics_are_logged_as_error_events() {
+    init_tracing("panic-hook-test").expect("tracing should initialize");
+    install_panic_hook();
+    install_panic_hook();
+
+    let before = event_counts().error_count;
+    let result = thread::spawn(|| panic!("boom")).join();
+
+    assert!(result.is_err(), "the previous hook must still let the thread panic");
+    assert_eq!(event_counts().error_count, before + 1);
+}
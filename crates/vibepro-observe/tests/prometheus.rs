@@ -0,0 +1,69 @@
+#![cfg(feature = "prometheus")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::time::Duration;
+use vibepro_observe::prometheus::serve_metrics;
+
+fn get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).expect("connect to metrics endpoint");
+    write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn recorded_metrics_are_served_on_metrics_path() {
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("free port");
+    serve_metrics(addr).expect("serve_metrics");
+
+    vibepro_observe::record_metric("queue.depth", 7.0);
+    vibepro_observe::record_metric_with_labels("http.requests", 3.0, &[("method", "GET")]);
+
+    // Metrics are collected on each scrape, so fresh readings show up at once.
+    let response = get(addr, "/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+    assert!(response.contains("# TYPE queue_depth gauge"), "{response}");
+    assert!(
+        response.contains("queue_depth{otel_scope_name=\"vibepro_observe\"} 7"),
+        "{response}"
+    );
+    assert!(
+        response.contains("http_requests{method=\"GET\",otel_scope_name=\"vibepro_observe\"} 3"),
+        "{response}"
+    );
+
+    assert!(get(addr, "/other").starts_with("HTTP/1.1 404"));
+}
+
+#[test]
+fn slow_client_does_not_block_scrapes() {
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("free port");
+    serve_metrics(addr).expect("serve_metrics");
+
+    // Connects but never sends a request line.
+    let _idle = TcpStream::connect(addr).expect("connect to metrics endpoint");
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || sender.send(get(addr, "/metrics")).unwrap());
+    let response = receiver
+        .recv_timeout(Duration::from_secs(2))
+        .expect("scrape answered while another client is idle");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+}
+
+#[test]
+fn metrics_port_must_be_numeric() {
+    std::env::set_var("VIBEPRO_METRICS_PORT", "http");
+    assert!(vibepro_observe::prometheus::addr_from_env().is_err());
+    std::env::set_var("VIBEPRO_METRICS_PORT", "9464");
+    let addr = vibepro_observe::prometheus::addr_from_env().unwrap().unwrap();
+    assert_eq!(addr.port(), 9464);
+    std::env::remove_var("VIBEPRO_METRICS_PORT");
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "propagation")]
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use std::env;
+use tracing_subscriber::layer::SubscriberExt;
+use vibepro_observe::propagation::{extract, inject, propagator_from_env};
+
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[test]
+fn propagators_env_var_selects_formats() {
+    let fields = |value: Option<&str>| {
+        match value {
+            Some(value) => env::set_var("OTLP_PROPAGATORS", value),
+            None => env::remove_var("OTLP_PROPAGATORS"),
+        }
+        propagator_from_env()
+            .fields()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+    };
+
+    assert!(fields(None).contains(&"traceparent".to_string()));
+    assert_eq!(fields(Some("b3")), vec!["b3"]);
+    let combined = fields(Some("tracecontext, b3multi, unknown"));
+    assert!(combined.contains(&"traceparent".to_string()));
+    assert!(combined.contains(&"x-b3-traceid".to_string()));
+    assert!(fields(Some("unknown")).contains(&"traceparent".to_string()));
+
+    env::remove_var("OTLP_PROPAGATORS");
+}
+
+#[test]
+fn extracted_context_is_injected_from_child_span() {
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let mut incoming = http::HeaderMap::new();
+    incoming.insert("traceparent", TRACEPARENT.parse().unwrap());
+    let remote = extract(&incoming);
+    assert_eq!(
+        remote.span().span_context().trace_id().to_string(),
+        "4bf92f3577b34da6a3ce929d0e0e4736"
+    );
+
+    let tracer = SdkTracerProvider::builder().build().tracer("propagation-test");
+    let subscriber =
+        tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    let mut outgoing = http::HeaderMap::new();
+    tracing::subscriber::with_default(subscriber, || {
+        let _remote = remote.attach();
+        let span = tracing::info_span!("outgoing");
+        span.in_scope(|| inject(&mut outgoing));
+    });
+
+    let traceparent = outgoing["traceparent"].to_str().unwrap();
+    assert!(traceparent.contains("4bf92f3577b34da6a3ce929d0e0e4736"));
+    assert!(!traceparent.contains("00f067aa0ba902b7"), "a child span id is injected");
+}
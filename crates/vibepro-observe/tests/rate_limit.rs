@@ -0,0 +1,47 @@
+#![cfg(feature = "rate-limit")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::Registry;
+use vibepro_observe::rate_limit::RateLimitLayer;
+
+#[derive(Clone, Default)]
+struct EventCounter(Arc<AtomicUsize>);
+
+impl<S: Subscriber> Layer<S> for EventCounter {
+    fn on_event(&self, _event: &Event<'_>, _ctx: Context<'_, S>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn rate_limit_caps_emitted_events() {
+    let counter = EventCounter::default();
+    let subscriber = Registry::default().with(RateLimitLayer::new(counter.clone(), 100));
+
+    tracing::subscriber::with_default(subscriber, || {
+        for i in 0..10_000 {
+            tracing::info!(i, "hot loop event");
+        }
+    });
+
+    let emitted = counter.0.load(Ordering::Relaxed);
+    assert!(emitted >= 100, "expected the burst to pass, got {emitted}");
+    assert!(emitted <= 110, "expected at most ~110 events, got {emitted}");
+}
+
+#[test]
+fn zero_rate_disables_limiting() {
+    let counter = EventCounter::default();
+    let subscriber = Registry::default().with(RateLimitLayer::new(counter.clone(), 0));
+
+    tracing::subscriber::with_default(subscriber, || {
+        for i in 0..1_000 {
+            tracing::info!(i, "unlimited event");
+        }
+    });
+
+    assert_eq!(counter.0.load(Ordering::Relaxed), 1_000);
+}
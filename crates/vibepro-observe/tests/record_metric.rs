@@ -0,0 +1,64 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::fmt::MakeWriter;
+use vibepro_observe::{record_metric, record_metric_with_labels};
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Buffer {
+    type Writer = Buffer;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+fn capture_json(emit: impl FnOnce()) -> serde_json::Value {
+    let buffer = Buffer::default();
+    let subscriber = tracing_subscriber::fmt()
+        .json()
+        .with_writer(buffer.clone())
+        .finish();
+    tracing::subscriber::with_default(subscriber, emit);
+
+    let output = buffer.0.lock().unwrap().clone();
+    serde_json::from_slice(&output).expect("one JSON log line")
+}
+
+#[test]
+fn labels_are_recorded_as_json_object() {
+    let line = capture_json(|| {
+        record_metric_with_labels(
+            "http_requests_total",
+            1.0,
+            &[("method", "GET"), ("status", "200")],
+        )
+    });
+
+    let fields = &line["fields"];
+    assert_eq!(fields["metric.key"], "http_requests_total");
+    assert_eq!(fields["metric.value"], 1.0);
+    let labels: serde_json::Value =
+        serde_json::from_str(fields["metric.labels"].as_str().unwrap()).unwrap();
+    assert_eq!(labels, serde_json::json!({ "method": "GET", "status": "200" }));
+}
+
+#[test]
+fn record_metric_has_no_labels_field() {
+    let line = capture_json(|| record_metric("files_processed", 42.0));
+
+    assert_eq!(line["fields"]["metric.key"], "files_processed");
+    assert!(line["fields"].get("metric.labels").is_none());
+}
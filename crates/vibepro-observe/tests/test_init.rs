@@ -0,0 +1,20 @@
+use std::thread;
+
+#[test]
+fn init_for_test_is_safe_from_many_threads() {
+    let handles: Vec<_> = (0..8)
+        .map(|_| thread::spawn(vibepro_observe::init_tracing_for_test))
+        .collect();
+    for handle in handles {
+        handle.join().expect("init_tracing_for_test should not panic");
+    }
+
+    tracing::info!("discarded unless RUST_LOG is set");
+}
+
+#[test]
+fn init_tracing_after_test_init_is_noop() {
+    vibepro_observe::init_tracing_for_test();
+    assert!(vibepro_observe::init_tracing("test-init").is_ok());
+    vibepro_observe::init_tracing_for_test();
+}
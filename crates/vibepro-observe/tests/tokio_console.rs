@@ -0,0 +1,21 @@
+#![cfg(feature = "tokio-console")]
+
+use std::net::SocketAddr;
+use vibepro_observe::tokio_console::{bind_addr, is_enabled, DEFAULT_BIND};
+
+#[test]
+fn init_tracing_installs_console_layer() {
+    assert!(!is_enabled());
+    std::env::set_var("TOKIO_CONSOLE", "1");
+    assert!(is_enabled());
+
+    // Bind to an ephemeral port so parallel test runs do not collide.
+    std::env::set_var("TOKIO_CONSOLE_BIND", "127.0.0.1:0");
+    assert_eq!(bind_addr(), "127.0.0.1:0".parse::<SocketAddr>().unwrap());
+
+    vibepro_observe::init_tracing("tokio-console-test").expect("init_tracing");
+    tracing::info!("console and JSON layers installed");
+
+    std::env::set_var("TOKIO_CONSOLE_BIND", "not-an-address");
+    assert_eq!(bind_addr(), DEFAULT_BIND.parse::<SocketAddr>().unwrap());
+}
@@ -0,0 +1,60 @@
+use std::env;
+use vibepro_observe::{init_tracing_with_config, LogFormat, TracingConfig};
+
+#[test]
+fn from_env_reads_existing_variables() {
+    env::set_var("RUST_LOG", "debug");
+    env::set_var("VIBEPRO_LOG_FORMAT", "Pretty");
+    env::set_var("VIBEPRO_OBSERVE", "1");
+    env::set_var("OTLP_ENDPOINT", "http://collector:4317");
+    env::remove_var("OTLP_PROTOCOL");
+
+    let config = TracingConfig::from_env("config-test");
+
+    env::remove_var("RUST_LOG");
+    env::remove_var("VIBEPRO_LOG_FORMAT");
+    env::remove_var("VIBEPRO_OBSERVE");
+    env::remove_var("OTLP_ENDPOINT");
+
+    assert_eq!(config.service_name, "config-test");
+    assert_eq!(config.log_level, "debug");
+    assert_eq!(config.log_format, LogFormat::Pretty);
+    assert!(config.enable_otlp);
+    assert_eq!(config.otlp_endpoint.as_deref(), Some("http://collector:4317"));
+    assert_eq!(config.otlp_protocol, None);
+    assert!(config.resource_attributes.is_empty());
+}
+
+#[test]
+fn fields_override_defaults() {
+    let config = TracingConfig::new("config-test")
+        .with_log_level("warn,my_crate=trace")
+        .with_log_format(LogFormat::Compact)
+        .with_otlp(true)
+        .with_otlp_endpoint("http://localhost:4318")
+        .with_otlp_protocol("http")
+        .with_resource_attribute("deployment.environment", "ci");
+
+    assert_eq!(config.log_level, "warn,my_crate=trace");
+    assert_eq!(config.log_format, LogFormat::Compact);
+    assert!(config.enable_otlp);
+    assert_eq!(config.otlp_endpoint.as_deref(), Some("http://localhost:4318"));
+    assert_eq!(config.otlp_protocol.as_deref(), Some("http"));
+    assert_eq!(config.resource_attributes["deployment.environment"], "ci");
+}
+
+#[test]
+fn log_format_parses_known_names() {
+    assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert_eq!("PRETTY".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+    assert_eq!(" compact ".parse::<LogFormat>().unwrap(), LogFormat::Compact);
+    assert!("yaml".parse::<LogFormat>().is_err());
+    assert_eq!(TracingConfig::new("config-test").log_format, LogFormat::Json);
+}
+
+#[test]
+fn init_with_config_is_idempotent() {
+    let config = TracingConfig::new("config-test").with_log_level("info");
+    assert!(init_tracing_with_config(config.clone()).is_ok());
+    assert!(init_tracing_with_config(config).is_ok());
+}
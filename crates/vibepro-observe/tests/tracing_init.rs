@@ -13,6 +13,7 @@ fn init_multiple_times_is_idempotent() {
         result1.is_ok() || result2.is_ok(),
         "At least one init should succeed"
     );
+    assert!(vibepro_observe::is_tracing_initialized());
 }
 
 #[test]
@@ -38,3 +39,18 @@ fn respect_env_flag_without_otlp_feature() {
     let _ = init_tracing("unit-test-env");
     record_metric("unit.counter", 2.0);
 }
+
+#[cfg(not(feature = "otlp"))]
+#[test]
+fn otlp_never_enabled_without_feature() {
+    let _ = init_tracing("unit-test-otlp-state");
+    assert!(!vibepro_observe::is_otlp_enabled());
+}
+
+#[test]
+fn shutdown_with_timeout_without_exporter_is_ok() {
+    let _ = init_tracing("unit-test-shutdown");
+    let timeout = std::time::Duration::from_millis(500);
+    assert!(vibepro_observe::shutdown_tracing_with_timeout(timeout).is_ok());
+    assert!(vibepro_observe::shutdown_tracing().is_ok());
+}